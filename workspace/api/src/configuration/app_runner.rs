@@ -1,8 +1,10 @@
+use std::net::SocketAddr;
+
 use anyhow::{bail, Result};
 use infrastructure::database::migrations::migrator::Migrator;
 use tracing::{error, info};
 
-use crate::{configuration::routes, errors::app_errors::UnexpectedError};
+use crate::{configuration::routes, errors::app_errors::UnexpectedError, jobs::{import_job, outbound_message_retry_job, stub_entity_enrichment_job, stub_entity_purge_job}};
 
 use super::app_state::AppState;
 
@@ -11,6 +13,11 @@ pub async fn run() -> Result<()> {
 
     execute_migrations(&state).await?;
 
+    stub_entity_purge_job::spawn(state.stub_entity_use_case.clone())?;
+    import_job::spawn(state.import_job_service.clone());
+    stub_entity_enrichment_job::spawn(state.stub_entity_update_service.clone())?;
+    outbound_message_retry_job::spawn(state.stub_entity_use_case.clone())?;
+
     let app = routes::build_routes(state).await;
     let port = "3000";
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port))
@@ -23,7 +30,9 @@ pub async fn run() -> Result<()> {
         "Application listening on port {}", 
         port);
 
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 
     Ok(())
 }
@@ -1,16 +1,29 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use aws_config::{retry::RetryConfig, BehaviorVersion};
 use domain::ports::{
     messaging::messaging_service_port::MessagingServicePort,
     repositories::{
         mockserver_http_service_port::MockserverHttpServicePort,
+        api_key_repository_port::ApiKeyRepositoryPort,
+        import_job_repository_port::ImportJobRepositoryPort,
+        outbound_message_repository_port::OutboundMessageRepositoryPort,
+        stub_entity_history_repository_port::StubEntityHistoryRepositoryPort,
         stub_entity_repository_port::StubEntityRepositoryPort,
     },
 };
 use infrastructure::{
-    database::repositories::{
-        database_data::DatabaseConnection,
-        stub_entity_sea_orm_postgres_repository::StubEntitySeaOrmPostgresRepository,
+    cache::stub_entity_caching_repository::StubEntityCachingRepository,
+    database::{
+        notifications::stub_entity_change_listener::StubEntityChangeListener,
+        repositories::{
+            api_key_sea_orm_postgres_repository::ApiKeySeaOrmPostgresRepository,
+            database_data::DatabaseConnection,
+            import_job_sea_orm_postgres_repository::ImportJobSeaOrmPostgresRepository,
+            outbound_message_sea_orm_postgres_repository::OutboundMessageSeaOrmPostgresRepository,
+            stub_entity_history_sea_orm_postgres_repository::StubEntityHistorySeaOrmPostgresRepository,
+            stub_entity_projection_sea_orm_postgres_repository::StubEntityProjectionSeaOrmPostgresRepository,
+            stub_entity_sea_orm_postgres_repository::StubEntitySeaOrmPostgresRepository,
+        },
     },
     http::mockserver::{
         mockserver_configuration::get_mockserver_base_url,
@@ -19,28 +32,82 @@ use infrastructure::{
     messaging::{
         aws_sqs_messaging_configuration::get_rust_test_aws_sqs_queue_url,
         aws_sqs_messaging_service::AwsSqsMessagingService,
+        kafka_messaging_configuration::get_kafka_producer_config,
+        kafka_messaging_service::KafkaMessagingService,
+        handlers::{
+            handler_registry::HandlerRegistry,
+            stub_entity_projection_handler::StubEntityProjectionHandler,
+        },
+        local_messaging_configuration::get_local_messaging_config,
+        local_messaging_service::LocalMessagingService,
     },
 };
-use std::{sync::Arc, time::Duration};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use crate::{
-    services::stub_entity_update_service::StubEntityUpdateService,
-    use_cases::stub_entity_use_case::StubEntityUseCase,
+    configuration::authentication_configuration::{
+        get_auth_audience, get_auth_enabled, get_auth_issuer, get_auth_jwks_path,
+        get_auth_jwks_refresh_interval_seconds, get_auth_jwks_url, get_auth_leeway_seconds,
+    },
+    configuration::import_job_configuration::{
+        get_import_job_lease_seconds, get_import_job_retry_interval_seconds, get_import_max_bytes,
+    },
+    configuration::messaging_configuration::{get_messaging_backend, MessagingBackend},
+    configuration::outbound_message_configuration::get_publish_failure_policy,
+    configuration::rate_limit_configuration::{
+        get_max_concurrent_requests, get_rate_limit_burst, get_rate_limit_enabled,
+        get_rate_limit_requests_per_second, get_rate_limit_routes, get_rate_limit_trusted_proxies,
+    },
+    configuration::stub_entity_batch_configuration::get_stub_entity_batch_max_items,
+    configuration::stub_entity_cache_configuration::{
+        get_stub_entity_cache_capacity, get_stub_entity_cache_max_age_seconds,
+        get_stub_entity_cache_ttl_seconds,
+    },
+    configuration::stub_entity_changes_configuration::get_stub_entity_changes_heartbeat_seconds,
+    configuration::stub_entity_enrichment_configuration::{
+        get_stub_entity_enrichment_degraded_mode, get_stub_entity_enrichment_policy,
+    },
+    services::{
+        import_job_service::ImportJobService,
+        jwt_authentication_service::{JwksSource, JwtAuthenticationService},
+        rate_limit_service::{RateLimitPolicy, RateLimitService},
+        stub_entity_change_feed_service::StubEntityChangeFeedService,
+        stub_entity_update_service::StubEntityUpdateService,
+    },
+    use_cases::{
+        api_key_use_case::ApiKeyUseCase,
+        stub_entity_history_use_case::StubEntityHistoryUseCase,
+        stub_entity_use_case::StubEntityUseCase,
+    },
 };
 
 pub struct AppState {
     pub database_connection: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
     pub stub_entity_use_case: Arc<StubEntityUseCase>,
     pub stub_entity_update_service: Arc<StubEntityUpdateService>,
+    pub stub_entity_history_use_case: Arc<StubEntityHistoryUseCase>,
+    pub stub_entity_change_feed_service: Arc<StubEntityChangeFeedService>,
+    pub stub_entity_batch_max_items: usize,
+    pub stub_entity_cache_max_age_seconds: u64,
+    pub import_job_service: Arc<ImportJobService>,
+    pub import_max_bytes: usize,
     pub aws_client: Arc<aws_sdk_sqs::Client>,
     pub messaging_service: Arc<dyn MessagingServicePort>,
+    pub jwt_authentication_service: Option<Arc<JwtAuthenticationService>>,
+    pub api_key_use_case: Arc<ApiKeyUseCase>,
+    pub auth_enabled: bool,
+    pub rate_limit_service: Option<Arc<RateLimitService>>,
+    pub max_concurrent_requests: usize,
 }
 
 impl AppState {
     pub async fn new() -> Result<Arc<AppState>> {
         let database_connection = DatabaseConnection::new().await?;
 
-        let stub_entity_repository = build_stub_entity_repository(&database_connection);
+        let change_listener = StubEntityChangeListener::start(&database_connection).await?;
+
+        let stub_entity_repository =
+            build_stub_entity_repository(&database_connection, &change_listener)?;
 
         let mockserver_http_service = build_mock_server_http_service();
 
@@ -57,23 +124,59 @@ impl AppState {
 
         let aws_client: Arc<aws_sdk_sqs::Client> = Arc::new(aws_sdk_sqs::Client::new(&config));
 
-        let messaging_service = build_messaging_service(&aws_client).await;
+        let messaging_service = build_messaging_service(&aws_client, &database_connection).await?;
 
         let stub_entity_use_case = build_stub_entity_use_case(
             &stub_entity_repository,
             &mockserver_http_service,
             &messaging_service,
-        );
+            &database_connection,
+        )?;
 
         let stub_entity_update_service =
             build_stub_entity_update_service(&stub_entity_use_case, &database_connection);
 
+        let stub_entity_history_use_case = build_stub_entity_history_use_case(&database_connection);
+
+        let stub_entity_change_feed_service =
+            build_stub_entity_change_feed_service(&stub_entity_history_use_case, &change_listener)?;
+
+        let stub_entity_batch_max_items = get_stub_entity_batch_max_items()? as usize;
+
+        let stub_entity_cache_max_age_seconds = get_stub_entity_cache_max_age_seconds()?;
+
+        let import_job_service =
+            build_import_job_service(&stub_entity_use_case, &database_connection)?;
+
+        let import_max_bytes = get_import_max_bytes()? as usize;
+
+        let auth_enabled = get_auth_enabled()?;
+
+        let jwt_authentication_service = build_jwt_authentication_service(auth_enabled).await?;
+
+        let api_key_use_case = build_api_key_use_case(&database_connection);
+
+        let rate_limit_service = build_rate_limit_service()?;
+
+        let max_concurrent_requests = get_max_concurrent_requests()? as usize;
+
         let app_state = Self {
             database_connection,
             stub_entity_use_case,
             stub_entity_update_service,
+            stub_entity_history_use_case,
+            stub_entity_change_feed_service,
+            stub_entity_batch_max_items,
+            stub_entity_cache_max_age_seconds,
+            import_job_service,
+            import_max_bytes,
             aws_client,
-            messaging_service
+            messaging_service,
+            jwt_authentication_service,
+            api_key_use_case,
+            auth_enabled,
+            rate_limit_service,
+            max_concurrent_requests,
         };
 
         Ok(Arc::new(app_state))
@@ -86,12 +189,109 @@ fn build_mock_server_http_service() -> Arc<dyn MockserverHttpServicePort> {
     Arc::new(MockserverHttpService::new(reqwest_client, base_url))
 }
 
+async fn build_jwt_authentication_service(
+    auth_enabled: bool,
+) -> Result<Option<Arc<JwtAuthenticationService>>> {
+    if !auth_enabled {
+        return Ok(None);
+    }
+
+    let jwks_source = match (get_auth_jwks_path(), get_auth_jwks_url()) {
+        (Some(path), _) => JwksSource::File(path),
+        (None, Some(url)) => JwksSource::Url(url),
+        (None, None) => bail!("AUTH_JWKS_PATH or AUTH_JWKS_URL is required when AUTH_ENABLED"),
+    };
+
+    let service = JwtAuthenticationService::new(
+        jwks_source,
+        get_auth_issuer()?,
+        get_auth_audience()?,
+        get_auth_leeway_seconds()?,
+        Duration::from_secs(get_auth_jwks_refresh_interval_seconds()?),
+        Arc::new(reqwest::Client::new()),
+    )
+    .await?;
+    Ok(Some(Arc::new(service)))
+}
+
+fn build_rate_limit_service() -> Result<Option<Arc<RateLimitService>>> {
+    if !get_rate_limit_enabled()? {
+        return Ok(None);
+    }
+
+    let default_policy = RateLimitPolicy {
+        requests_per_second: get_rate_limit_requests_per_second()?,
+        burst: get_rate_limit_burst()?,
+    };
+    Ok(Some(Arc::new(RateLimitService::new(
+        default_policy,
+        get_rate_limit_routes()?,
+        get_rate_limit_trusted_proxies()?,
+    ))))
+}
+
+fn build_api_key_use_case(
+    database_connection: &Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+) -> Arc<ApiKeyUseCase> {
+    let repository: Arc<dyn ApiKeyRepositoryPort> = Arc::new(
+        ApiKeySeaOrmPostgresRepository::new(database_connection.clone()),
+    );
+    Arc::new(ApiKeyUseCase::new(repository))
+}
+
 fn build_stub_entity_repository(
     database_connection: &Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
-) -> Arc<dyn StubEntityRepositoryPort> {
-    Arc::new(StubEntitySeaOrmPostgresRepository::new(
+    change_listener: &Arc<StubEntityChangeListener>,
+) -> Result<Arc<dyn StubEntityRepositoryPort>> {
+    let repository: Arc<dyn StubEntityRepositoryPort> = Arc::new(
+        StubEntitySeaOrmPostgresRepository::new(database_connection.clone()),
+    );
+
+    let Some(capacity) = NonZeroUsize::new(get_stub_entity_cache_capacity()? as usize) else {
+        return Ok(repository);
+    };
+    Ok(Arc::new(StubEntityCachingRepository::new(
+        repository,
+        capacity,
+        Duration::from_secs(get_stub_entity_cache_ttl_seconds()?),
+        change_listener.subscribe(),
+    )))
+}
+
+fn build_stub_entity_history_use_case(
+    database_connection: &Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+) -> Arc<StubEntityHistoryUseCase> {
+    let repository: Arc<dyn StubEntityHistoryRepositoryPort> = Arc::new(
+        StubEntityHistorySeaOrmPostgresRepository::new(database_connection.clone()),
+    );
+    Arc::new(StubEntityHistoryUseCase::new(repository))
+}
+
+fn build_stub_entity_change_feed_service(
+    stub_entity_history_use_case: &Arc<StubEntityHistoryUseCase>,
+    change_listener: &Arc<StubEntityChangeListener>,
+) -> Result<Arc<StubEntityChangeFeedService>> {
+    Ok(Arc::new(StubEntityChangeFeedService::new(
+        stub_entity_history_use_case.clone(),
+        change_listener.clone(),
+        Duration::from_secs(get_stub_entity_changes_heartbeat_seconds()?),
+    )))
+}
+
+fn build_import_job_service(
+    stub_entity_use_case: &Arc<StubEntityUseCase>,
+    database_connection: &Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+) -> Result<Arc<ImportJobService>> {
+    let repository: Arc<dyn ImportJobRepositoryPort> = Arc::new(
+        ImportJobSeaOrmPostgresRepository::new(database_connection.clone()),
+    );
+    Ok(Arc::new(ImportJobService::new(
+        repository,
+        stub_entity_use_case.clone(),
         database_connection.clone(),
-    ))
+        Duration::from_secs(get_import_job_lease_seconds()?),
+        Duration::from_secs(get_import_job_retry_interval_seconds()?),
+    )))
 }
 
 fn build_stub_entity_update_service(
@@ -108,20 +308,43 @@ fn build_stub_entity_use_case(
     repository: &Arc<dyn StubEntityRepositoryPort>,
     mockserver_http_service: &Arc<dyn MockserverHttpServicePort>,
     messaging_service: &Arc<dyn MessagingServicePort>,
-) -> Arc<StubEntityUseCase> {
-    Arc::new(StubEntityUseCase::new(
+    database_connection: &Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+) -> Result<Arc<StubEntityUseCase>> {
+    let outbound_message_repository: Arc<dyn OutboundMessageRepositoryPort> = Arc::new(
+        OutboundMessageSeaOrmPostgresRepository::new(database_connection.clone()),
+    );
+    Ok(Arc::new(StubEntityUseCase::new(
         repository.clone(),
         mockserver_http_service.clone(),
         messaging_service.clone(),
-    ))
+        outbound_message_repository,
+        get_stub_entity_enrichment_policy()?,
+        get_stub_entity_enrichment_degraded_mode()?,
+        get_publish_failure_policy()?,
+    )))
 }
 
 async fn build_messaging_service(
     aws_client: &Arc<aws_sdk_sqs::Client>,
-) -> Arc<dyn MessagingServicePort> {
-    let aws_sqs_queue_url = get_rust_test_aws_sqs_queue_url().unwrap();
-    Arc::new(AwsSqsMessagingService::new(
-        aws_client.clone(),
-        aws_sqs_queue_url.clone(),
-    ))
+    database_connection: &Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+) -> Result<Arc<dyn MessagingServicePort>> {
+    match get_messaging_backend()? {
+        MessagingBackend::Sqs => Ok(Arc::new(AwsSqsMessagingService::new(
+            aws_client.clone(),
+            get_rust_test_aws_sqs_queue_url()?,
+        ))),
+        MessagingBackend::Kafka => Ok(Arc::new(KafkaMessagingService::from_config(
+            &get_kafka_producer_config()?,
+        )?)),
+        MessagingBackend::Local => {
+            let registry = StubEntityProjectionHandler::new(Arc::new(
+                StubEntityProjectionSeaOrmPostgresRepository::new(database_connection.clone()),
+            ))
+            .register(HandlerRegistry::default());
+
+            Ok(Arc::new(
+                LocalMessagingService::start(registry, &get_local_messaging_config()?).await?,
+            ))
+        }
+    }
 }
@@ -0,0 +1,34 @@
+use std::env;
+
+use anyhow::Result;
+use infrastructure::env_var::env_var_util::{
+    get_bool_env_var, get_required_string_env_var, get_u64_env_var,
+};
+
+pub fn get_auth_enabled() -> Result<bool> {
+    get_bool_env_var("AUTH_ENABLED", true)
+}
+
+pub fn get_auth_jwks_path() -> Option<String> {
+    env::var("AUTH_JWKS_PATH").ok()
+}
+
+pub fn get_auth_jwks_url() -> Option<String> {
+    env::var("AUTH_JWKS_URL").ok()
+}
+
+pub fn get_auth_issuer() -> Result<String> {
+    get_required_string_env_var("AUTH_ISSUER")
+}
+
+pub fn get_auth_audience() -> Result<String> {
+    get_required_string_env_var("AUTH_AUDIENCE")
+}
+
+pub fn get_auth_jwks_refresh_interval_seconds() -> Result<u64> {
+    get_u64_env_var("AUTH_JWKS_REFRESH_INTERVAL_SECONDS", 60)
+}
+
+pub fn get_auth_leeway_seconds() -> Result<u64> {
+    get_u64_env_var("AUTH_LEEWAY_SECONDS", 30)
+}
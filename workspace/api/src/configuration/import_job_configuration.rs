@@ -0,0 +1,14 @@
+use anyhow::Result;
+use infrastructure::env_var::env_var_util::get_u64_env_var;
+
+pub fn get_import_max_bytes() -> Result<u64> {
+    get_u64_env_var("IMPORT_MAX_BYTES", 10 * 1024 * 1024)
+}
+
+pub fn get_import_job_lease_seconds() -> Result<u64> {
+    get_u64_env_var("IMPORT_JOB_LEASE_SECONDS", 60)
+}
+
+pub fn get_import_job_retry_interval_seconds() -> Result<u64> {
+    get_u64_env_var("IMPORT_JOB_RETRY_INTERVAL_SECONDS", 30)
+}
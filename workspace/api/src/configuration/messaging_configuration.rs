@@ -0,0 +1,29 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use infrastructure::env_var::env_var_util::get_env_var;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessagingBackend {
+    Sqs,
+    Kafka,
+    /// Delivers events to the projection handlers inside the API process
+    Local,
+}
+
+impl FromStr for MessagingBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "sqs" => Ok(Self::Sqs),
+            "kafka" => Ok(Self::Kafka),
+            "local" => Ok(Self::Local),
+            _ => bail!("Unknown messaging backend {}", value),
+        }
+    }
+}
+
+pub fn get_messaging_backend() -> Result<MessagingBackend> {
+    get_env_var("MESSAGING_BACKEND", MessagingBackend::Sqs)
+}
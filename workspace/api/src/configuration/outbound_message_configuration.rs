@@ -0,0 +1,30 @@
+use anyhow::Result;
+use domain::entities::publish_failure_policy_domain_entity::PublishFailurePolicy;
+use infrastructure::env_var::env_var_util::{get_env_var, get_u64_env_var};
+
+pub fn get_publish_failure_policy() -> Result<PublishFailurePolicy> {
+    get_env_var("PUBLISH_FAILURE_POLICY", PublishFailurePolicy::Retry)
+}
+
+pub fn get_outbound_message_retry_interval_seconds() -> Result<u64> {
+    get_u64_env_var("OUTBOUND_MESSAGE_RETRY_INTERVAL_SECONDS", 30)
+}
+
+pub fn get_outbound_message_retry_batch_size() -> Result<u64> {
+    get_u64_env_var("OUTBOUND_MESSAGE_RETRY_BATCH_SIZE", 100)
+}
+
+/// Attempts, the first one included, before a queued message is given up
+pub fn get_outbound_message_retry_max_attempts() -> Result<i32> {
+    get_env_var("OUTBOUND_MESSAGE_RETRY_MAX_ATTEMPTS", 10)
+}
+
+/// How long a retry run holds the messages it claimed before other instances may send them
+pub fn get_outbound_message_retry_lease_seconds() -> Result<i64> {
+    get_env_var("OUTBOUND_MESSAGE_RETRY_LEASE_SECONDS", 60)
+}
+
+/// Delay before the second retry, doubled for each retry after it
+pub fn get_outbound_message_retry_backoff_seconds() -> Result<i64> {
+    get_env_var("OUTBOUND_MESSAGE_RETRY_BACKOFF_SECONDS", 30)
+}
@@ -0,0 +1,35 @@
+use std::net::IpAddr;
+
+use anyhow::Result;
+use infrastructure::env_var::env_var_util::{
+    get_bool_env_var, get_env_var, get_u64_env_var, get_vec_env_var,
+};
+
+use crate::services::rate_limit_service::RouteRateLimit;
+
+pub fn get_rate_limit_enabled() -> Result<bool> {
+    get_bool_env_var("RATE_LIMIT_ENABLED", true)
+}
+
+pub fn get_rate_limit_requests_per_second() -> Result<f64> {
+    get_env_var("RATE_LIMIT_REQUESTS_PER_SECOND", 50.0)
+}
+
+pub fn get_rate_limit_burst() -> Result<f64> {
+    get_env_var("RATE_LIMIT_BURST", 100.0)
+}
+
+/// Comma-separated `<METHOD> <path pattern>=<requests per second>:<burst>` overrides
+pub fn get_rate_limit_routes() -> Result<Vec<RouteRateLimit>> {
+    get_vec_env_var("RATE_LIMIT_ROUTES", Vec::new())
+}
+
+/// Comma-separated addresses of the proxies whose `X-Forwarded-For` is trusted
+pub fn get_rate_limit_trusted_proxies() -> Result<Vec<IpAddr>> {
+    get_vec_env_var("RATE_LIMIT_TRUSTED_PROXIES", Vec::new())
+}
+
+/// Requests handled at once before new ones are shed with 503; 0 disables the limit
+pub fn get_max_concurrent_requests() -> Result<u64> {
+    get_u64_env_var("MAX_CONCURRENT_REQUESTS", 512)
+}
@@ -1,48 +1,218 @@
-use std::{
-    // time::Duration,
-    future::ready, sync::Arc };
+use std::{future::ready, sync::Arc};
 
 use crate::{
-    handlers::stub_entity_handler::{
-        add_stub_entity_handler, get_stub_entity_handler, list_stub_entity_handler,
-        update_stub_entity_handler,
+    handlers::{
+        api_key_handler::{add_api_key_handler, list_api_keys_handler, revoke_api_key_handler},
+        import_job_handler::{add_import_job_handler, get_import_job_handler},
+        stub_entity_batch_handler::{
+            add_stub_entity_batch_handler, require_batch_action, update_stub_entity_batch_handler,
+        },
+        stub_entity_handler::{
+            add_stub_entity_handler, delete_stub_entity_handler, get_stub_entity_handler,
+            list_stub_entity_handler, patch_stub_entity_handler, restore_stub_entity_handler,
+            search_stub_entity_handler, update_stub_entity_handler,
+        },
+        stub_entity_export_handler::export_stub_entity_handler,
+        stub_entity_history_handler::{
+            list_stub_entity_changes_handler, list_stub_entity_history_handler,
+        },
+        stub_entity_tree_handler::{
+            list_stub_entity_ancestors_handler, list_stub_entity_children_handler,
+            list_stub_entity_descendants_handler,
+        },
+    },
+    middleware::{
+        api_key_authentication_middleware::ApiKeyAuthenticationLayer,
+        authentication_middleware::AuthenticationLayer,
+        authorization_middleware::{
+            require_scope, API_KEY_ADMIN, STUB_ENTITY_READ, STUB_ENTITY_WRITE,
+        },
+        rate_limit_middleware::{handle_load_shedding_error, RateLimitLayer},
+        request_metrics_middleware::RequestMetricsLayer,
+        request_middleware::RequestLayer,
+        tenant_middleware::TenantLayer,
     },
-    middleware::{request_metrics_middleware::RequestMetricsLayer, request_middleware::RequestLayer},
 };
 
 use axum::{
-    // error_handling::HandleErrorLayer,
-    // http::StatusCode,
-    routing::{get, post, put},
+    error_handling::HandleErrorLayer,
+    extract::DefaultBodyLimit,
+    middleware::from_fn,
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use tower::{
-    // buffer::BufferLayer, 
-    // limit::RateLimitLayer, 
-    ServiceBuilder};
+    limit::GlobalConcurrencyLimitLayer, load_shed::LoadShedLayer, util::option_layer,
+    ServiceBuilder,
+};
 
 use super::{app_metrics_configuration::setup_metrics_recorder, app_state::AppState};
 
+/// matchit has no literal `:batch` suffix support, so the custom method is captured as a
+/// parameter (including the colon) and any other action is answered 404
+const STUB_ENTITY_ACTION_ROUTE: &str = "/api/v1/stub-entity:action";
+
 pub async fn build_routes(state: Arc<AppState>) -> Router {
 
     let recorder_handle = setup_metrics_recorder();
 
 
     let middleware_stacks = ServiceBuilder::new()
-        // .layer(HandleErrorLayer::new(|_| async move {
-        //     (StatusCode::INTERNAL_SERVER_ERROR, "Unexpected error")
-        // }))
-        // .layer(BufferLayer::new(1024))
-        // .layer(RateLimitLayer::new(1, Duration::from_secs(60)))
         .layer(RequestMetricsLayer)
         .layer(RequestLayer);
 
+    // Sheds API requests beyond the concurrency limit with 503 instead of queueing them;
+    // `/_/metrics` stays outside so the service can still be observed while overloaded
+    let load_shedding_stack = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(handle_load_shedding_error))
+        .layer(LoadShedLayer::new())
+        .layer(option_layer(
+            (state.max_concurrent_requests > 0)
+                .then(|| GlobalConcurrencyLimitLayer::new(state.max_concurrent_requests)),
+        ));
+
+    let api_routes = Router::new()
+        .route(
+            "/api/v1/stub-entity",
+            get(list_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity/search",
+            get(search_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity/export",
+            get(export_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity/changes",
+            get(list_stub_entity_changes_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id",
+            get(get_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity",
+            post(add_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_WRITE)),
+        )
+        .route(
+            STUB_ENTITY_ACTION_ROUTE,
+            post(add_stub_entity_batch_handler)
+                .route_layer(require_scope(STUB_ENTITY_WRITE))
+                .route_layer(from_fn(require_batch_action)),
+        )
+        .route(
+            STUB_ENTITY_ACTION_ROUTE,
+            put(update_stub_entity_batch_handler)
+                .route_layer(require_scope(STUB_ENTITY_WRITE))
+                .route_layer(from_fn(require_batch_action)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id",
+            put(update_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_WRITE)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id",
+            patch(patch_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_WRITE)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id",
+            delete(delete_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_WRITE)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id/restore",
+            post(restore_stub_entity_handler).route_layer(require_scope(STUB_ENTITY_WRITE)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id/history",
+            get(list_stub_entity_history_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id/children",
+            get(list_stub_entity_children_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id/descendants",
+            get(list_stub_entity_descendants_handler)
+                .route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/stub-entity/:id/ancestors",
+            get(list_stub_entity_ancestors_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/imports",
+            post(add_import_job_handler)
+                .route_layer(DefaultBodyLimit::max(state.import_max_bytes))
+                .route_layer(require_scope(STUB_ENTITY_WRITE)),
+        )
+        .route(
+            "/api/v1/imports/:id",
+            get(get_import_job_handler).route_layer(require_scope(STUB_ENTITY_READ)),
+        )
+        .route(
+            "/api/v1/admin/api-keys",
+            get(list_api_keys_handler).route_layer(require_scope(API_KEY_ADMIN)),
+        )
+        .route(
+            "/api/v1/admin/api-keys",
+            post(add_api_key_handler).route_layer(require_scope(API_KEY_ADMIN)),
+        )
+        .route(
+            "/api/v1/admin/api-keys/:id",
+            delete(revoke_api_key_handler).route_layer(require_scope(API_KEY_ADMIN)),
+        )
+        .route_layer(RateLimitLayer::new(state.rate_limit_service.clone()))
+        .route_layer(TenantLayer)
+        .route_layer(AuthenticationLayer::new(
+            state.jwt_authentication_service.clone(),
+        ))
+        .route_layer(ApiKeyAuthenticationLayer::new(
+            state.auth_enabled.then(|| state.api_key_use_case.clone()),
+        ))
+        .layer(load_shedding_stack);
+
     Router::new()
-        .route("/api/v1/stub-entity", get(list_stub_entity_handler))
-        .route("/api/v1/stub-entity/:id", get(get_stub_entity_handler))
-        .route("/api/v1/stub-entity", post(add_stub_entity_handler))
-        .route("/api/v1/stub-entity/:id", put(update_stub_entity_handler))
+        .merge(api_routes)
         .route("/_/metrics", get(move || ready(recorder_handle.render())))
         .layer(middleware_stacks)
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header::CONTENT_TYPE, StatusCode},
+        Json,
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// Unknown actions are answered 404 even when their body would be rejected
+    #[tokio::test]
+    async fn test_only_the_batch_action_is_routed() {
+        let router: Router = Router::new().route(
+            STUB_ENTITY_ACTION_ROUTE,
+            post(|Json(_): Json<Value>| async { StatusCode::OK })
+                .route_layer(from_fn(require_batch_action)),
+        );
+
+        for (uri, body, status) in [
+            ("/api/v1/stub-entity:batch", "{}", StatusCode::OK),
+            ("/api/v1/stub-entity:batch", "not json", StatusCode::BAD_REQUEST),
+            ("/api/v1/stub-entity:foo", "not json", StatusCode::NOT_FOUND),
+            ("/api/v1/stub-entityX", "not json", StatusCode::NOT_FOUND),
+        ] {
+            let request = axum::http::Request::post(uri)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status, "{}", uri);
+        }
+    }
+}
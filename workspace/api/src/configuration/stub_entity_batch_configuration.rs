@@ -0,0 +1,6 @@
+use anyhow::Result;
+use infrastructure::env_var::env_var_util::get_u64_env_var;
+
+pub fn get_stub_entity_batch_max_items() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_BATCH_MAX_ITEMS", 100)
+}
@@ -0,0 +1,16 @@
+use anyhow::Result;
+use infrastructure::env_var::env_var_util::get_u64_env_var;
+
+/// Entities kept in memory per instance; 0 disables the cache
+pub fn get_stub_entity_cache_capacity() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_CACHE_CAPACITY", 10000)
+}
+
+pub fn get_stub_entity_cache_ttl_seconds() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_CACHE_TTL_SECONDS", 60)
+}
+
+/// `max-age` sent to clients, which revalidate with `If-None-Match` afterwards
+pub fn get_stub_entity_cache_max_age_seconds() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_CACHE_MAX_AGE_SECONDS", 0)
+}
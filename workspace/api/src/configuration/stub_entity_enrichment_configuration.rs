@@ -0,0 +1,19 @@
+use anyhow::Result;
+use domain::entities::stub_enrichment_domain_entity::EnrichmentPolicy;
+use infrastructure::env_var::env_var_util::{get_bool_env_var, get_env_var, get_u64_env_var};
+
+pub fn get_stub_entity_enrichment_policy() -> Result<EnrichmentPolicy> {
+    get_env_var("STUB_ENTITY_ENRICHMENT_POLICY", EnrichmentPolicy::Always)
+}
+
+pub fn get_stub_entity_enrichment_degraded_mode() -> Result<bool> {
+    get_bool_env_var("STUB_ENTITY_ENRICHMENT_DEGRADED_MODE", false)
+}
+
+pub fn get_stub_entity_enrichment_retry_interval_seconds() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_ENRICHMENT_RETRY_INTERVAL_SECONDS", 60)
+}
+
+pub fn get_stub_entity_enrichment_retry_batch_size() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_ENRICHMENT_RETRY_BATCH_SIZE", 100)
+}
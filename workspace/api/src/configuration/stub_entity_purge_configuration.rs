@@ -0,0 +1,18 @@
+use anyhow::Result;
+use infrastructure::env_var::env_var_util::{get_bool_env_var, get_u64_env_var};
+
+pub fn get_stub_entity_purge_enabled() -> Result<bool> {
+    get_bool_env_var("STUB_ENTITY_PURGE_ENABLED", true)
+}
+
+pub fn get_stub_entity_purge_retention_days() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_PURGE_RETENTION_DAYS", 30)
+}
+
+pub fn get_stub_entity_purge_interval_seconds() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_PURGE_INTERVAL_SECONDS", 3600)
+}
+
+pub fn get_stub_entity_purge_batch_size() -> Result<u64> {
+    get_u64_env_var("STUB_ENTITY_PURGE_BATCH_SIZE", 100)
+}
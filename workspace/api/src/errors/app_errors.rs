@@ -1,11 +1,12 @@
 use core::fmt;
 
-use axum::extract::rejection::JsonRejection;
-use axum::http::StatusCode;
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use domain::errors::domain_errors::DomainError;
+use domain::ports::messaging::messaging_service_port::SendError;
 use infrastructure::log_with_span;
-use infrastructure::logging::logging_task_local::REQUEST_DATA;
 use opentelemetry::trace::TraceContextExt;
 use sea_orm::DbErr;
 use serde_json::{json, Value};
@@ -18,7 +19,17 @@ pub enum AppError {
     UnexpectedError(UnexpectedError),
     ValidationError(ValidationErrors),
     JsonRejection(JsonRejection),
+    QueryRejection(QueryRejection),
     UnprocessableEntity(String),
+    CycleDetected(String, Vec<i32>),
+    UnsupportedMediaType(String),
+    NotAcceptable(String),
+    Unauthorized(String),
+    InsufficientScope(String),
+    Forbidden(String),
+    BadRequest(String),
+    TooManyRequests(String),
+    ServiceUnavailable(String),
 }
 
 impl fmt::Display for AppError {
@@ -56,9 +67,47 @@ impl IntoResponse for AppError {
             AppError::JsonRejection(e) => {
                 build_error_response(e.body_text(), e.status()).into_response()
             }
+            AppError::QueryRejection(e) => {
+                build_error_response(e.body_text(), e.status()).into_response()
+            }
             AppError::UnprocessableEntity(message) => {
                 build_error_response(message, StatusCode::UNPROCESSABLE_ENTITY).into_response()
             }
+            AppError::CycleDetected(message, cycle) => {
+                let body = json!({
+                    "message": message,
+                    "cycle": cycle
+                });
+                (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response()
+            }
+            AppError::UnsupportedMediaType(message) => {
+                build_error_response(message, StatusCode::UNSUPPORTED_MEDIA_TYPE).into_response()
+            }
+            AppError::NotAcceptable(message) => {
+                build_error_response(message, StatusCode::NOT_ACCEPTABLE).into_response()
+            }
+            AppError::Unauthorized(detail) => build_problem_response(
+                StatusCode::UNAUTHORIZED,
+                detail,
+                Some(String::from("Bearer error=\"invalid_token\"")),
+            ),
+            AppError::InsufficientScope(scope) => build_problem_response(
+                StatusCode::FORBIDDEN,
+                format!("Missing required scope {}", scope),
+                Some(format!("Bearer error=\"insufficient_scope\", scope=\"{}\"", scope)),
+            ),
+            AppError::Forbidden(detail) => {
+                build_problem_response(StatusCode::FORBIDDEN, detail, None)
+            }
+            AppError::BadRequest(message) => {
+                build_error_response(message, StatusCode::BAD_REQUEST).into_response()
+            }
+            AppError::TooManyRequests(message) => {
+                build_error_response(message, StatusCode::TOO_MANY_REQUESTS).into_response()
+            }
+            AppError::ServiceUnavailable(message) => {
+                build_error_response(message, StatusCode::SERVICE_UNAVAILABLE).into_response()
+            }
         }
     }
 }
@@ -70,7 +119,31 @@ fn build_error_response(message: String, status_code: StatusCode) -> (StatusCode
     (status_code, Json(body))
 }
 
-fn extract_validation_errors(
+/// RFC 7807 problem details, used for authentication and authorization failures
+fn build_problem_response(
+    status_code: StatusCode,
+    detail: String,
+    www_authenticate: Option<String>,
+) -> Response {
+    let body = json!({
+        "type": "about:blank",
+        "title": status_code.canonical_reason().unwrap_or_default(),
+        "status": status_code.as_u16(),
+        "detail": detail
+    });
+    let mut response = (status_code, Json(body)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/problem+json"),
+    );
+    if let Some(Ok(value)) = www_authenticate.map(|value| HeaderValue::from_str(&value)) {
+        headers.insert(header::WWW_AUTHENTICATE, value);
+    }
+    response
+}
+
+pub(crate) fn extract_validation_errors(
     errors: &ValidationErrors,
 ) -> serde_json::Map<String, serde_json::Value> {
     let mut json_errors: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
@@ -141,6 +214,26 @@ impl UnexpectedError {
 
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
+        if let Some(domain_error) = err.downcast_ref::<DomainError>() {
+            return match domain_error {
+                DomainError::UnprocessableEntity(message) => {
+                    AppError::UnprocessableEntity(message.clone())
+                }
+                DomainError::CycleDetected(cycle) => {
+                    AppError::CycleDetected(domain_error.to_string(), cycle.clone())
+                }
+            };
+        }
+
+        // Raised after the change was committed, with the fail_request publish failure policy
+        if let Some(send_error) = err.downcast_ref::<SendError>() {
+            if send_error.is_retryable() {
+                return AppError::ServiceUnavailable(
+                    "Change saved but its event was not published".to_string(),
+                );
+            }
+        }
+
         if let Some(db_error) = err.downcast_ref::<DbErr>() {
             if db_error.to_string().contains("fk-stub-table-ref") {
                 return AppError::UnprocessableEntity("auto_ref does not exist".to_string());
@@ -165,6 +258,12 @@ impl From<JsonRejection> for AppError {
     }
 }
 
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        AppError::QueryRejection(rejection)
+    }
+}
+
 impl From<ValidationErrors> for AppError {
     fn from(errors: ValidationErrors) -> Self {
         AppError::ValidationError(errors)
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use axum_extra::extract::WithRejection;
+use infrastructure::log_with_span;
+use opentelemetry::trace::TraceContextExt;
+use serde_json::{json, Value};
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use validator::Validate;
+
+use crate::{configuration::app_state::AppState, errors::app_errors::AppError};
+
+use super::dtos::api_key_dtos::ApiKeyAddDto;
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn list_api_keys_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let api_keys = state.api_key_use_case.list().await?;
+    let json_value = serde_json::to_value(api_keys)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "list_api_keys_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn add_api_key_handler(
+    State(state): State<Arc<AppState>>,
+    WithRejection(Json(payload), _): WithRejection<Json<ApiKeyAddDto>, AppError>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    payload.validate()?;
+    let (api_key, key) = state
+        .api_key_use_case
+        .add(payload.name, payload.scopes, payload.expires_at)
+        .await?;
+    let body = json!({
+        "api_key": api_key,
+        "key": key
+    });
+    log_with_span!(Level::INFO, "add_api_key_handler executed");
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn revoke_api_key_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let revoked = state.api_key_use_case.revoke(id).await?;
+    if revoked.is_none() {
+        let body = json!({
+            "message": "Active API key not found"
+        });
+        return Ok((StatusCode::NOT_FOUND, Json(body)));
+    }
+    let json_value = serde_json::to_value(revoked)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "revoke_api_key_handler executed");
+    Ok((StatusCode::OK, body))
+}
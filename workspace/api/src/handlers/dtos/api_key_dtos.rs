@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApiKeyAddDto {
+    #[validate(length(min = 1, max = 100, message = "Name must have between 1 and 100 characters"))]
+    pub name: String,
+
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<String>,
+
+    pub expires_at: Option<DateTime<Utc>>,
+}
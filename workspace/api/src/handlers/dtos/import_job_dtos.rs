@@ -0,0 +1,105 @@
+use domain::entities::import_job_domain_entity::{ImportFormat, ImportLine};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use validator::Validate;
+
+use super::stub_entity_dtos::KeyValueDto;
+
+/// One stub entity of an import file. `parent_key` points at the `key` of another row of the
+/// same file, for hierarchies whose ids are only known once the rows are created.
+#[derive(Debug, Deserialize, Validate)]
+pub struct StubEntityImportItemDto {
+    pub key: Option<String>,
+
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: String,
+
+    #[validate(nested)]
+    pub value: KeyValueDto,
+
+    #[validate(range(min = 1, message = "auto_ref must be greater than 0"))]
+    pub auto_ref: Option<i32>,
+
+    pub parent_key: Option<String>,
+}
+
+pub fn import_format_from_content_type(content_type: &str) -> Option<ImportFormat> {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "application/x-ndjson" | "application/ndjson" | "application/jsonl" => {
+            Some(ImportFormat::Ndjson)
+        }
+        "text/csv" => Some(ImportFormat::Csv),
+        _ => None,
+    }
+}
+
+/// Splits an upload into numbered records. Records that cannot be parsed are kept as failed
+/// lines so they show up in the job report.
+pub fn parse_import_file(format: ImportFormat, content: &[u8]) -> Vec<ImportLine> {
+    match format {
+        ImportFormat::Ndjson => parse_ndjson(content),
+        ImportFormat::Csv => parse_csv(content),
+    }
+}
+
+fn parse_ndjson(content: &[u8]) -> Vec<ImportLine> {
+    content
+        .split(|byte| *byte == b'\n')
+        .enumerate()
+        .filter(|(_, line)| !line.iter().all(u8::is_ascii_whitespace))
+        .map(|(index, line)| {
+            let line_number = index as i32 + 1;
+            match serde_json::from_slice::<Value>(line) {
+                Ok(content) => ImportLine::parsed(line_number, content),
+                Err(err) => ImportLine::unparsable(line_number, err.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Columns follow the export layout: key, name, value_id, value_name, auto_ref, parent_key
+fn parse_csv(content: &[u8]) -> Vec<ImportLine> {
+    let mut reader = csv::Reader::from_reader(content);
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(err) => return vec![ImportLine::unparsable(1, err.to_string())],
+    };
+
+    reader
+        .records()
+        .enumerate()
+        .map(|(index, record)| {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    let line_number = err.position().map_or(index as i32 + 2, |p| p.line() as i32);
+                    return ImportLine::unparsable(line_number, err.to_string());
+                }
+            };
+            let line_number = record.position().map_or(index as i32 + 2, |p| p.line() as i32);
+            let field = |name: &str| {
+                headers
+                    .iter()
+                    .position(|header| header == name)
+                    .and_then(|position| record.get(position))
+                    .filter(|value| !value.is_empty())
+            };
+            let number = |name: &str| {
+                field(name).map(|value| match value.parse::<i64>() {
+                    Ok(number) => Value::from(number),
+                    Err(_) => Value::from(value),
+                })
+            };
+            ImportLine::parsed(
+                line_number,
+                json!({
+                    "key": field("key"),
+                    "name": field("name"),
+                    "value": { "id": number("value_id"), "name": field("value_name") },
+                    "auto_ref": number("auto_ref"),
+                    "parent_key": field("parent_key"),
+                }),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use validator::{
+    Validate, ValidateLength, ValidateRange, ValidateRequired, ValidationErrors,
+};
+
+/// Tri-state field for JSON Merge Patch (RFC 7396) bodies: an absent member
+/// leaves the target unchanged, `null` removes it and any other value replaces it.
+/// Fields must be annotated with `#[serde(default)]` so absence maps to `Missing`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Patch<T> {
+    #[default]
+    Missing,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn apply_to(self, target: &mut T) {
+        if let Patch::Value(value) = self {
+            *target = value;
+        }
+    }
+
+    pub fn apply_to_option(self, target: &mut Option<T>) {
+        match self {
+            Patch::Missing => {}
+            Patch::Null => *target = None,
+            Patch::Value(value) => *target = Some(value),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(|value| match value {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
+
+/// The `Validate` derive serializes a rejected field into its error params
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Patch::Value(value) => value.serialize(serializer),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T: Validate> Validate for Patch<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Patch::Value(value) => value.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T: ValidateLength<u64>> ValidateLength<u64> for Patch<T> {
+    fn length(&self) -> Option<u64> {
+        match self {
+            Patch::Value(value) => value.length(),
+            _ => None,
+        }
+    }
+}
+
+impl<T: ValidateRange<T>> ValidateRange<T> for Patch<T> {
+    fn greater_than(&self, max: T) -> Option<bool> {
+        match self {
+            Patch::Value(value) => value.greater_than(max),
+            _ => None,
+        }
+    }
+
+    fn less_than(&self, min: T) -> Option<bool> {
+        match self {
+            Patch::Value(value) => value.less_than(min),
+            _ => None,
+        }
+    }
+}
+
+/// `required` on a `Patch` field rejects an explicit `null` while still
+/// allowing the member to be omitted.
+impl<T> ValidateRequired for Patch<T> {
+    fn is_some(&self) -> bool {
+        !matches!(self, Patch::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Body {
+        #[serde(default)]
+        #[validate(length(min = 1))]
+        name: Patch<String>,
+
+        #[serde(default)]
+        #[validate(range(min = 1))]
+        count: Patch<i32>,
+
+        #[serde(default)]
+        #[validate(required)]
+        label: Patch<String>,
+    }
+
+    fn parse(json: &str) -> Body {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let body = parse(r#"{"name": null, "count": 3}"#);
+        assert_eq!(body.name, Patch::Null);
+        assert_eq!(body.count, Patch::Value(3));
+        assert_eq!(body.label, Patch::Missing);
+    }
+
+    #[test]
+    fn test_validate_skips_missing_and_null() {
+        assert!(parse("{}").validate().is_ok());
+        assert!(parse(r#"{"name": null, "count": null}"#).validate().is_ok());
+
+        let errors = parse(r#"{"name": "", "count": 0}"#).validate().unwrap_err();
+        let fields = errors.field_errors();
+        assert!(fields.contains_key("name"));
+        assert!(fields.contains_key("count"));
+    }
+
+    #[test]
+    fn test_required_rejects_null_only() {
+        assert!(parse(r#"{"label": "set"}"#).validate().is_ok());
+        let errors = parse(r#"{"label": null}"#).validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("label"));
+    }
+}
@@ -0,0 +1,69 @@
+use domain::entities::{
+    stub_batch_domain_entity::{BatchItemResult, BatchMode},
+    stub_enrichment_domain_entity::EnrichmentPolicy,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::Validate;
+
+use super::stub_entity_dtos::{KeyValueDto, StubEntityUpdateDto};
+
+/// Items are kept as raw JSON so a malformed item is reported on its own
+/// instead of rejecting the whole batch
+#[derive(Debug, Deserialize)]
+pub struct StubEntityBatchDto {
+    #[serde(default)]
+    pub mode: BatchMode,
+    /// Overrides the configured enrichment policy when adding
+    pub enrichment: Option<EnrichmentPolicy>,
+    pub items: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StubEntityBatchUpdateItemDto {
+    #[validate(range(min = 1, message = "ID must be greater than 0"))]
+    pub id: i32,
+
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: String,
+
+    #[validate(nested)]
+    pub value: KeyValueDto,
+
+    #[validate(range(min = 1, message = "auto_ref must be greater than 0"))]
+    pub auto_ref: Option<i32>,
+}
+
+impl StubEntityBatchUpdateItemDto {
+    pub fn into_update(self) -> (i32, StubEntityUpdateDto) {
+        (
+            self.id,
+            StubEntityUpdateDto {
+                name: self.name,
+                value: self.value,
+                auto_ref: self.auto_ref,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StubEntityBatchResponseDto {
+    pub mode: BatchMode,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BatchItemResult>,
+}
+
+impl StubEntityBatchResponseDto {
+    pub fn new(mode: BatchMode, mut items: Vec<BatchItemResult>) -> Self {
+        items.sort_by_key(|item| item.index);
+        let succeeded = items.iter().filter(|item| item.is_success()).count();
+        Self {
+            mode,
+            succeeded,
+            failed: items.len() - succeeded,
+            items,
+        }
+    }
+}
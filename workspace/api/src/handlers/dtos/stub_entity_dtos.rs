@@ -1,19 +1,43 @@
-use domain::entities::stub_domain_entity::{KeyValue, StubEntity};
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use domain::entities::{
+    stub_domain_entity::{KeyValue, StubEntity, StubEntityFilter},
+    stub_enrichment_domain_entity::{EnrichmentPolicy, KeyValueDraft, StubEntityDraft},
+    stub_search_domain_entity::StubEntitySearch,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use validator::Validate;
 
+use super::merge_patch::Patch;
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct StubEntityAddDto {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
 
+    /// May be partial or absent when the enrichment policy fills it in
     #[validate(nested)]
-    pub value: KeyValueDto,
+    pub value: Option<KeyValueDraftDto>,
 
     #[validate(range(min = 1, message = "auto_ref must be greater than 0"))]
     pub auto_ref: Option<i32>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct KeyValueDraftDto {
+    #[validate(range(min = 1, message = "ID must be greater than 0"))]
+    pub id: Option<i32>,
+
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StubEntityAddQueryDto {
+    /// Overrides the configured enrichment policy
+    pub enrichment: Option<EnrichmentPolicy>,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct KeyValueDto {
 
@@ -25,16 +49,24 @@ pub struct KeyValueDto {
 }
 
 impl StubEntityAddDto {
-    pub fn to_domain(&self) -> StubEntity {
-        StubEntity {
-            id: None,
+    pub fn to_domain(&self) -> StubEntityDraft {
+        StubEntityDraft {
             name: self.name.clone(),
-            value: self.value.to_domain(),
+            value: self.value.as_ref().map(KeyValueDraftDto::to_domain),
             auto_ref: self.auto_ref,
         }
     }
 }
 
+impl KeyValueDraftDto {
+    pub fn to_domain(&self) -> KeyValueDraft {
+        KeyValueDraft {
+            id: self.id,
+            name: self.name.clone(),
+        }
+    }
+}
+
 impl KeyValueDto {
     pub fn to_domain(&self) -> KeyValue {
         KeyValue {
@@ -53,11 +85,136 @@ pub struct ValidatedRequest<T>(pub T);
 #[derive(Debug, Deserialize, Validate)]
 pub struct StubEntityUpdateDto {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
-    pub name: Option<String>,
+    pub name: String,
 
     #[validate(nested)]
-    pub value: Option<KeyValueDto>,
+    pub value: KeyValueDto,
 
     #[validate(range(min = 1, message = "auto_ref must be greater than 0"))]
     pub auto_ref: Option<i32>,
 }
+
+impl StubEntityUpdateDto {
+    pub fn apply_to(self, entity: &mut StubEntity) {
+        entity.name = self.name;
+        entity.value = self.value.to_domain();
+        entity.auto_ref = self.auto_ref;
+        entity.enrichment_pending_since = None;
+    }
+}
+
+/// Body of a `PATCH` with `application/merge-patch+json` semantics
+#[derive(Debug, Deserialize, Validate)]
+pub struct StubEntityPatchDto {
+    #[serde(default)]
+    #[validate(
+        required(message = "name cannot be null"),
+        length(min = 1, message = "Name cannot be empty")
+    )]
+    pub name: Patch<String>,
+
+    #[serde(default)]
+    #[validate(required(message = "value cannot be null"), nested)]
+    pub value: Patch<KeyValuePatchDto>,
+
+    #[serde(default)]
+    #[validate(range(min = 1, message = "auto_ref must be greater than 0"))]
+    pub auto_ref: Patch<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct KeyValuePatchDto {
+    #[serde(default)]
+    #[validate(
+        required(message = "ID cannot be null"),
+        range(min = 1, message = "ID must be greater than 0")
+    )]
+    pub id: Patch<i32>,
+
+    #[serde(default)]
+    #[validate(
+        required(message = "Name cannot be null"),
+        length(min = 1, message = "Name cannot be empty")
+    )]
+    pub name: Patch<String>,
+}
+
+impl StubEntityPatchDto {
+    pub fn apply_to(self, entity: &mut StubEntity) {
+        self.name.apply_to(&mut entity.name);
+        if let Patch::Value(value) = self.value {
+            value.apply_to(&mut entity.value);
+            entity.enrichment_pending_since = None;
+        }
+        self.auto_ref.apply_to_option(&mut entity.auto_ref);
+    }
+}
+
+impl KeyValuePatchDto {
+    pub fn apply_to(self, key_value: &mut KeyValue) {
+        self.id.apply_to(&mut key_value.id);
+        self.name.apply_to(&mut key_value.name);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StubEntityGetQueryDto {
+    pub as_of: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StubEntityListQueryDto {
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+impl StubEntityListQueryDto {
+    pub fn to_domain(&self) -> StubEntityFilter {
+        StubEntityFilter {
+            include_deleted: self.include_deleted,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StubEntitySearchQueryDto {
+    pub q: Option<String>,
+
+    /// JSON object matched by containment against `value`, e.g. `{"id":1}`
+    pub value: Option<String>,
+
+    #[serde(default)]
+    pub include_deleted: bool,
+
+    pub page: Option<u64>,
+
+    #[validate(range(min = 1, max = 100, message = "page_size must be between 1 and 100"))]
+    pub page_size: Option<u64>,
+}
+
+impl StubEntitySearchQueryDto {
+    pub fn to_domain(&self) -> Result<StubEntitySearch, String> {
+        let value = match &self.value {
+            Some(value) => match serde_json::from_str::<Value>(value) {
+                Ok(value @ Value::Object(_)) => Some(value),
+                _ => return Err(String::from("value must be a JSON object")),
+            },
+            None => None,
+        };
+
+        let search = StubEntitySearch {
+            text: self.q.clone(),
+            value,
+            include_deleted: self.include_deleted,
+            page: self.page.unwrap_or(0),
+            page_size: self.page_size.unwrap_or(20),
+        };
+        if search.prefix_query().is_none() && search.value.is_none() {
+            return Err(String::from("q or value is required"));
+        }
+        Ok(search)
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use domain::entities::stub_domain_entity::StubEntity;
+use serde::Serialize;
+
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+const CSV_CONTENT_TYPE: &str = "text/csv";
+
+/// Must follow the field order of `StubEntityCsvRowDto`
+const CSV_COLUMNS: [&str; 6] = ["id", "name", "value_id", "value_name", "auto_ref", "deleted_at"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Ndjson,
+    Csv,
+}
+
+/// CSV has no nesting, so the `value` KeyValue is flattened into two columns
+#[derive(Debug, Serialize)]
+struct StubEntityCsvRowDto<'a> {
+    id: Option<i32>,
+    name: &'a str,
+    value_id: i32,
+    value_name: &'a str,
+    auto_ref: Option<i32>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl ExportFormat {
+    /// Picks the first supported media range of an `Accept` header, NDJSON being the default
+    pub fn from_accept(accept: Option<&str>) -> Option<Self> {
+        let Some(accept) = accept else {
+            return Some(Self::Ndjson);
+        };
+
+        accept
+            .split(',')
+            .map(|media_range| media_range.split(';').next().unwrap_or_default().trim())
+            .find_map(|media_type| match media_type {
+                NDJSON_CONTENT_TYPE | "application/ndjson" | "application/jsonl" | "*/*"
+                | "application/*" => Some(Self::Ndjson),
+                CSV_CONTENT_TYPE | "text/*" => Some(Self::Csv),
+                _ => None,
+            })
+    }
+
+    pub fn supported_content_types() -> String {
+        format!("{}, {}", NDJSON_CONTENT_TYPE, CSV_CONTENT_TYPE)
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Ndjson => NDJSON_CONTENT_TYPE,
+            Self::Csv => CSV_CONTENT_TYPE,
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+
+    pub fn header(&self) -> Result<Option<Vec<u8>>> {
+        match self {
+            Self::Ndjson => Ok(None),
+            Self::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                writer.write_record(CSV_COLUMNS)?;
+                Ok(Some(writer.into_inner()?))
+            }
+        }
+    }
+
+    pub fn encode(&self, entity: &StubEntity) -> Result<Vec<u8>> {
+        match self {
+            Self::Ndjson => {
+                let mut line = serde_json::to_vec(entity)?;
+                line.push(b'\n');
+                Ok(line)
+            }
+            Self::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(Vec::new());
+                writer.serialize(StubEntityCsvRowDto {
+                    id: entity.id,
+                    name: &entity.name,
+                    value_id: entity.value.id,
+                    value_name: &entity.value.name,
+                    auto_ref: entity.auto_ref,
+                    deleted_at: entity.deleted_at,
+                })?;
+                Ok(writer.into_inner()?)
+            }
+        }
+    }
+}
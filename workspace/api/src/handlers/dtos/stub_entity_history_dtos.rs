@@ -0,0 +1,36 @@
+use domain::entities::stub_history_domain_entity::StubEntityChangeFilter;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StubEntityHistoryQueryDto {
+    pub page: Option<u64>,
+
+    #[validate(range(min = 1, max = 100, message = "page_size must be between 1 and 100"))]
+    pub page_size: Option<u64>,
+}
+
+impl StubEntityHistoryQueryDto {
+    pub fn page(&self) -> u64 {
+        self.page.unwrap_or(0)
+    }
+
+    pub fn page_size(&self) -> u64 {
+        self.page_size.unwrap_or(20)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StubEntityChangesQueryDto {
+    pub id: Option<i32>,
+    pub auto_ref: Option<i32>,
+}
+
+impl StubEntityChangesQueryDto {
+    pub fn to_domain(&self) -> StubEntityChangeFilter {
+        StubEntityChangeFilter {
+            entity_id: self.id,
+            auto_ref: self.auto_ref,
+        }
+    }
+}
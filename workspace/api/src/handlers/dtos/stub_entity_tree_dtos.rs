@@ -0,0 +1,25 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StubEntityTreeFormatDto {
+    #[default]
+    Flat,
+    Tree,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StubEntityTreeQueryDto {
+    #[validate(range(min = 1, max = 50, message = "max_depth must be between 1 and 50"))]
+    pub max_depth: Option<i32>,
+
+    #[serde(default)]
+    pub format: StubEntityTreeFormatDto,
+}
+
+impl StubEntityTreeQueryDto {
+    pub fn max_depth(&self) -> i32 {
+        self.max_depth.unwrap_or(10)
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use infrastructure::log_with_span;
+use opentelemetry::trace::TraceContextExt;
+use serde_json::{json, Value};
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::{configuration::app_state::AppState, errors::app_errors::AppError};
+
+use super::dtos::import_job_dtos::{import_format_from_content_type, parse_import_file};
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn add_import_job_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, HeaderMap, Json<Value>), AppError> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let format = import_format_from_content_type(content_type).ok_or_else(|| {
+        AppError::UnsupportedMediaType(String::from(
+            "Expected request with `Content-Type: application/x-ndjson` or `Content-Type: text/csv`",
+        ))
+    })?;
+
+    let lines = parse_import_file(format, &body);
+    if lines.is_empty() {
+        return Err(AppError::BadRequest(String::from("Import file has no lines")));
+    }
+
+    let report = state.import_job_service.create(format, lines).await?;
+    let mut response_headers = HeaderMap::new();
+    if let Ok(location) = format!("/api/v1/imports/{}", report.job.id).parse() {
+        response_headers.insert(header::LOCATION, location);
+    }
+    let json_value = serde_json::to_value(report)?;
+    log_with_span!(Level::INFO, "add_import_job_handler executed");
+    Ok((StatusCode::ACCEPTED, response_headers, Json(json_value)))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn get_import_job_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let report = state.import_job_service.get(id).await?;
+    if report.is_none() {
+        let body = json!({
+            "message": "Import job not found"
+        });
+        return Ok((StatusCode::NOT_FOUND, Json(body)));
+    }
+    let json_value = serde_json::to_value(report)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "get_import_job_handler executed");
+    Ok((StatusCode::OK, body))
+}
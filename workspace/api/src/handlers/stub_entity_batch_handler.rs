@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::WithRejection;
+use domain::entities::stub_batch_domain_entity::{BatchItemResult, BatchItemStatus, BatchMode};
+use infrastructure::log_with_span;
+use opentelemetry::trace::TraceContextExt;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use validator::Validate;
+
+use crate::{
+    configuration::app_state::AppState,
+    errors::app_errors::{extract_validation_errors, AppError},
+};
+
+use super::dtos::{
+    stub_entity_batch_dtos::{
+        StubEntityBatchDto, StubEntityBatchResponseDto, StubEntityBatchUpdateItemDto,
+    },
+    stub_entity_dtos::StubEntityAddDto,
+};
+
+/// Custom method suffix of `/api/v1/stub-entity:batch`, captured by the `:action` route parameter
+const BATCH_ACTION: &str = ":batch";
+
+/// Answers 404 for any other action, before the handler reads the body
+pub async fn require_batch_action(Path(action): Path<String>, request: Request, next: Next) -> Response {
+    if action != BATCH_ACTION {
+        return not_found().into_response();
+    }
+    next.run(request).await
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn add_stub_entity_batch_handler(
+    State(state): State<Arc<AppState>>,
+    WithRejection(Json(payload), _): WithRejection<Json<StubEntityBatchDto>, AppError>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    ensure_batch_size(&payload, state.stub_entity_batch_max_items)?;
+
+    let (items, mut results) = parse_items::<StubEntityAddDto>(payload.items);
+    if payload.mode == BatchMode::AllOrNothing && !results.is_empty() {
+        results.extend(items.iter().map(|(index, _)| BatchItemResult::skipped(*index)));
+    } else {
+        let drafts = items
+            .into_iter()
+            .map(|(index, item)| (index, item.to_domain()))
+            .collect();
+        results.extend(
+            state
+                .stub_entity_use_case
+                .add_batch(drafts, payload.mode, payload.enrichment)
+                .await?,
+        );
+    }
+
+    log_with_span!(Level::INFO, "add_stub_entity_batch_handler executed");
+    build_batch_response(payload.mode, results)
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn update_stub_entity_batch_handler(
+    State(state): State<Arc<AppState>>,
+    WithRejection(Json(payload), _): WithRejection<Json<StubEntityBatchDto>, AppError>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    ensure_batch_size(&payload, state.stub_entity_batch_max_items)?;
+
+    let (items, mut results) = parse_items::<StubEntityBatchUpdateItemDto>(payload.items);
+    if payload.mode == BatchMode::AllOrNothing && !results.is_empty() {
+        results.extend(items.iter().map(|(index, _)| BatchItemResult::skipped(*index)));
+    } else {
+        let updates = items
+            .into_iter()
+            .map(|(index, item)| {
+                let (id, dto) = item.into_update();
+                (index, id, dto)
+            })
+            .collect();
+        results.extend(
+            state
+                .stub_entity_update_service
+                .update_batch(updates, payload.mode)
+                .await?,
+        );
+    }
+
+    log_with_span!(Level::INFO, "update_stub_entity_batch_handler executed");
+    build_batch_response(payload.mode, results)
+}
+
+fn ensure_batch_size(payload: &StubEntityBatchDto, max_items: usize) -> Result<(), AppError> {
+    if payload.items.is_empty() {
+        return Err(AppError::BadRequest(String::from(
+            "A batch needs at least one item",
+        )));
+    }
+    if payload.items.len() > max_items {
+        return Err(AppError::BadRequest(format!(
+            "A batch accepts at most {} items",
+            max_items
+        )));
+    }
+    Ok(())
+}
+
+/// Splits raw items into the ones that deserialize and validate, and `Invalid` results for the rest
+fn parse_items<T: DeserializeOwned + Validate>(
+    items: Vec<Value>,
+) -> (Vec<(usize, T)>, Vec<BatchItemResult>) {
+    let mut parsed = Vec::with_capacity(items.len());
+    let mut invalid = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let error = match serde_json::from_value::<T>(item) {
+            Ok(item) => match item.validate() {
+                Ok(()) => {
+                    parsed.push((index, item));
+                    continue;
+                }
+                Err(errors) => json!({ "errors": extract_validation_errors(&errors) }),
+            },
+            Err(err) => json!({ "message": err.to_string() }),
+        };
+        invalid.push(BatchItemResult::failed(index, BatchItemStatus::Invalid, error));
+    }
+    (parsed, invalid)
+}
+
+fn build_batch_response(
+    mode: BatchMode,
+    results: Vec<BatchItemResult>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let response = StubEntityBatchResponseDto::new(mode, results);
+    let status_code = match (response.failed, mode) {
+        (0, _) => StatusCode::OK,
+        (_, BatchMode::AllOrNothing) => StatusCode::UNPROCESSABLE_ENTITY,
+        (_, BatchMode::BestEffort) => StatusCode::MULTI_STATUS,
+    };
+    let json_value = serde_json::to_value(response)?;
+    Ok((status_code, Json(json_value)))
+}
+
+fn not_found() -> (StatusCode, Json<Value>) {
+    let body = json!({
+        "message": "Not found"
+    });
+    (StatusCode::NOT_FOUND, Json(body))
+}
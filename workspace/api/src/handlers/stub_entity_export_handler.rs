@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use axum_extra::extract::WithRejection;
+use domain::entities::principal_domain_entity::Principal;
+use futures::{stream, StreamExt};
+use infrastructure::log_with_span;
+use opentelemetry::trace::TraceContextExt;
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::{
+    configuration::app_state::AppState,
+    errors::app_errors::AppError,
+    middleware::authorization_middleware::{ensure_scope, STUB_ENTITY_ADMIN},
+};
+
+use super::dtos::{
+    stub_entity_dtos::StubEntityListQueryDto, stub_entity_export_dtos::ExportFormat,
+};
+
+/// Streams every stub entity matching the list filters, one row at a time, as NDJSON or CSV
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn export_stub_entity_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    headers: HeaderMap,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityListQueryDto>, AppError>,
+) -> Result<Response, AppError> {
+    if query.include_deleted {
+        ensure_scope(&principal, STUB_ENTITY_ADMIN)?;
+    }
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    let format = ExportFormat::from_accept(accept).ok_or_else(|| {
+        AppError::NotAcceptable(format!(
+            "Supported export formats are {}",
+            ExportFormat::supported_content_types()
+        ))
+    })?;
+
+    let header = format.header()?;
+    let entities = state.stub_entity_use_case.export(&query.to_domain()).await?;
+    let rows = entities.map(move |entity| {
+        let row = entity.and_then(|entity| format.encode(&entity));
+        if let Err(err) = &row {
+            log_with_span!(Level::ERROR, "Stub entity export aborted: {:?}", err);
+        }
+        row
+    });
+    let body = stream::iter(header.map(Ok)).chain(rows);
+
+    log_with_span!(Level::INFO, "export_stub_entity_handler executed");
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"stub-entities.{}\"",
+                    format.file_extension()
+                ),
+            ),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
@@ -2,22 +2,33 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    Extension,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use axum_extra::extract::WithRejection;
+use domain::entities::principal_domain_entity::Principal;
 use infrastructure::{create_correlated_span, log_with_span};
 use infrastructure::logging::logging_task_local::REQUEST_DATA;
 use opentelemetry::trace::TraceContextExt;
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 use tracing::{Level, span};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 use validator::Validate;
 
-use crate::{configuration::app_state::AppState, errors::app_errors::AppError};
+use crate::{
+    configuration::app_state::AppState,
+    errors::app_errors::AppError,
+    middleware::authorization_middleware::{ensure_scope, STUB_ENTITY_ADMIN},
+};
 
-use super::dtos::stub_entity_dtos::{StubEntityAddDto, StubEntityUpdateDto};
+use super::dtos::stub_entity_dtos::{
+    StubEntityAddDto, StubEntityAddQueryDto, StubEntityGetQueryDto, StubEntityListQueryDto,
+    StubEntityPatchDto, StubEntitySearchQueryDto, StubEntityUpdateDto,
+};
 use tracing::Instrument;
 
 #[axum::debug_handler]
@@ -27,11 +38,16 @@ use tracing::Instrument;
 // )]
 pub async fn list_stub_entity_handler(
     State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityListQueryDto>, AppError>,
 ) -> Result<(StatusCode, Json<Value>), AppError> {
+    if query.include_deleted {
+        ensure_scope(&principal, STUB_ENTITY_ADMIN)?;
+    }
     let span = create_correlated_span!(Level::INFO, "list_stub_entity_handler");
     async move {
         let use_case = &*state.stub_entity_use_case;
-        let stub_entities = use_case.list().await?;
+        let stub_entities = use_case.list(&query.to_domain()).await?;
         let json_value = serde_json::to_value(stub_entities)?;
         let body: Json<Value> = Json(json_value);
         log_with_span!(Level::INFO, "list_stub_entity_handler executed");
@@ -41,16 +57,36 @@ pub async fn list_stub_entity_handler(
     .await
 }
 
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn search_stub_entity_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntitySearchQueryDto>, AppError>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    query.validate()?;
+    if query.include_deleted {
+        ensure_scope(&principal, STUB_ENTITY_ADMIN)?;
+    }
+    let search = query.to_domain().map_err(AppError::BadRequest)?;
+    let use_case = &*state.stub_entity_use_case;
+    let hits = use_case.search(&search).await?;
+    let json_value = serde_json::to_value(hits)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "search_stub_entity_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
 #[axum::debug_handler]
 #[tracing::instrument(skip_all)]
 pub async fn add_stub_entity_handler(
     State(state): State<Arc<AppState>>,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityAddQueryDto>, AppError>,
     WithRejection(Json(payload), _): WithRejection<Json<StubEntityAddDto>, AppError>,
 ) -> Result<(StatusCode, Json<Value>), AppError> {
     payload.validate()?;
     let use_case = &*state.stub_entity_use_case;
-    let mut stub_entity = payload.to_domain();
-    let inserted_entity = use_case.add(&mut stub_entity).await?;
+    let inserted_entity = use_case.add(payload.to_domain(), query.enrichment).await?;
     let json_value = serde_json::to_value(inserted_entity)?;
     let body: Json<Value> = Json(json_value);
     log_with_span!(Level::INFO, "add_stub_entity_handler executed");
@@ -74,22 +110,143 @@ pub async fn update_stub_entity_handler(
     Ok((StatusCode::OK, body))
 }
 
+const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
 #[axum::debug_handler]
 #[tracing::instrument(skip_all)]
-pub async fn get_stub_entity_handler(
+pub async fn patch_stub_entity_handler(
     State(state): State<Arc<AppState>>,
     Path(id): Path<i32>,
+    headers: HeaderMap,
+    WithRejection(Json(payload), _): WithRejection<Json<StubEntityPatchDto>, AppError>,
 ) -> Result<(StatusCode, Json<Value>), AppError> {
-    let use_case = &*state.stub_entity_use_case;
-    let retrieved_entity = use_case.get(id, None).await?;
-    if retrieved_entity.is_none() {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with(MERGE_PATCH_CONTENT_TYPE) {
+        return Err(AppError::UnsupportedMediaType(format!(
+            "Expected request with `Content-Type: {}`",
+            MERGE_PATCH_CONTENT_TYPE
+        )));
+    }
+    payload.validate()?;
+    let service = &*state.stub_entity_update_service;
+    let patched_entity = service.patch(id, payload).await?;
+    if patched_entity.is_none() {
         let body = json!({
             "message": "Stub entity not found"
         });
         return Ok((StatusCode::NOT_FOUND, Json(body)));
     }
-    let json_value = serde_json::to_value(retrieved_entity)?;
+    let json_value = serde_json::to_value(patched_entity)?;
     let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "patch_stub_entity_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn get_stub_entity_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Extension(principal): Extension<Principal>,
+    headers: HeaderMap,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityGetQueryDto>, AppError>,
+) -> Result<Response, AppError> {
+    if query.include_deleted {
+        ensure_scope(&principal, STUB_ENTITY_ADMIN)?;
+    }
+    let retrieved_entity = match query.as_of {
+        Some(as_of) => {
+            state
+                .stub_entity_history_use_case
+                .get_as_of(id, as_of, query.include_deleted)
+                .await?
+        }
+        None => {
+            state
+                .stub_entity_use_case
+                .get(id, query.include_deleted, None)
+                .await?
+        }
+    };
+    if retrieved_entity.is_none() {
+        let body = json!({
+            "message": "Stub entity not found"
+        });
+        return Ok((StatusCode::NOT_FOUND, Json(body)).into_response());
+    }
+    let json_value = serde_json::to_value(retrieved_entity)?;
+    let etag = entity_tag(&json_value)?;
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (
+            header::CACHE_CONTROL,
+            format!("private, max-age={}", state.stub_entity_cache_max_age_seconds),
+        ),
+    ];
     log_with_span!(Level::INFO, "get_stub_entity_handler executed");
+    if matches_if_none_match(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+    let body: Json<Value> = Json(json_value);
+    Ok((StatusCode::OK, cache_headers, body).into_response())
+}
+
+/// Strong validator derived from the response body, so it changes exactly when the body does
+fn entity_tag(value: &Value) -> Result<String, AppError> {
+    let digest = Sha256::digest(serde_json::to_vec(value)?);
+    Ok(format!("\"{:x}\"", digest))
+}
+
+/// `If-None-Match` uses weak comparison (RFC 9110 13.1.2), so `W/` prefixes are ignored
+fn matches_if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|candidate| candidate.trim())
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn delete_stub_entity_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let service = &*state.stub_entity_update_service;
+    let deleted_entity = service.delete(id).await?;
+    if deleted_entity.is_none() {
+        let body = json!({
+            "message": "Stub entity not found"
+        });
+        return Ok((StatusCode::NOT_FOUND, Json(body)));
+    }
+    let json_value = serde_json::to_value(deleted_entity)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "delete_stub_entity_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn restore_stub_entity_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let service = &*state.stub_entity_update_service;
+    let restored_entity = service.restore(id).await?;
+    if restored_entity.is_none() {
+        let body = json!({
+            "message": "Deleted stub entity not found"
+        });
+        return Ok((StatusCode::NOT_FOUND, Json(body)));
+    }
+    let json_value = serde_json::to_value(restored_entity)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "restore_stub_entity_handler executed");
     Ok((StatusCode::OK, body))
 }
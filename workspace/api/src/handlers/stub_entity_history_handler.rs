@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use axum_extra::extract::WithRejection;
+use domain::entities::stub_history_domain_entity::StubEntityChangeCursor;
+use futures::{Stream, StreamExt};
+use infrastructure::log_with_span;
+use opentelemetry::trace::TraceContextExt;
+use serde_json::Value;
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use validator::Validate;
+
+use crate::{configuration::app_state::AppState, errors::app_errors::AppError};
+
+use super::dtos::stub_entity_history_dtos::{StubEntityChangesQueryDto, StubEntityHistoryQueryDto};
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn list_stub_entity_history_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityHistoryQueryDto>, AppError>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    query.validate()?;
+    let use_case = &*state.stub_entity_history_use_case;
+    let history = use_case.list(id, query.page(), query.page_size()).await?;
+    let json_value = serde_json::to_value(history)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "list_stub_entity_history_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
+/// Server-Sent Events feed of the tenant's changes; the event id is the change cursor,
+/// so reconnecting clients resume through the `Last-Event-ID` header
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn list_stub_entity_changes_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityChangesQueryDto>, AppError>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, AppError> {
+    let last_event_id = match headers.get(LAST_EVENT_ID_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<StubEntityChangeCursor>().ok())
+                .ok_or_else(|| AppError::BadRequest(String::from("Invalid Last-Event-ID")))?,
+        ),
+        None => None,
+    };
+
+    let service = &*state.stub_entity_change_feed_service;
+    let changes = service.subscribe(last_event_id, query.to_domain()).await?;
+    let events = changes.map(|change| {
+        Event::default()
+            .id(change.cursor().to_string())
+            .event(change.operation.as_str())
+            .json_data(&change)
+    });
+    log_with_span!(Level::INFO, "list_stub_entity_changes_handler executed");
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(service.heartbeat_interval())))
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use axum_extra::extract::WithRejection;
+use domain::entities::stub_tree_domain_entity::{StubEntityNode, StubEntityTree};
+use infrastructure::log_with_span;
+use opentelemetry::trace::TraceContextExt;
+use serde_json::{json, Value};
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use validator::Validate;
+
+use crate::{configuration::app_state::AppState, errors::app_errors::AppError};
+
+use super::dtos::stub_entity_tree_dtos::{StubEntityTreeFormatDto, StubEntityTreeQueryDto};
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn list_stub_entity_children_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let use_case = &*state.stub_entity_use_case;
+    if use_case.get(id, false, None).await?.is_none() {
+        return Ok(not_found());
+    }
+    let children = use_case.children(id).await?;
+    let json_value = serde_json::to_value(children)?;
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "list_stub_entity_children_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn list_stub_entity_descendants_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityTreeQueryDto>, AppError>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    query.validate()?;
+    let use_case = &*state.stub_entity_use_case;
+    let Some(root) = use_case.get(id, false, None).await? else {
+        return Ok(not_found());
+    };
+    let descendants = use_case.descendants(id, query.max_depth()).await?;
+    let json_value = match query.format {
+        StubEntityTreeFormatDto::Flat => serde_json::to_value(descendants)?,
+        StubEntityTreeFormatDto::Tree => {
+            serde_json::to_value(StubEntityTree::build(root, descendants))?
+        }
+    };
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "list_stub_entity_descendants_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn list_stub_entity_ancestors_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    WithRejection(Query(query), _): WithRejection<Query<StubEntityTreeQueryDto>, AppError>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    query.validate()?;
+    let use_case = &*state.stub_entity_use_case;
+    let Some(entity) = use_case.get(id, false, None).await? else {
+        return Ok(not_found());
+    };
+    let ancestors = use_case.ancestors(id, query.max_depth()).await?;
+    let json_value = match query.format {
+        StubEntityTreeFormatDto::Flat => serde_json::to_value(ancestors)?,
+        StubEntityTreeFormatDto::Tree => {
+            let mut chain = ancestors.into_iter().rev();
+            let tree = match chain.next() {
+                Some(root) => {
+                    let mut descendants: Vec<_> = chain.collect();
+                    descendants.push(StubEntityNode { depth: 0, entity });
+                    StubEntityTree::build(root.entity, descendants)
+                }
+                None => StubEntityTree::build(entity, Vec::new()),
+            };
+            serde_json::to_value(tree)?
+        }
+    };
+    let body: Json<Value> = Json(json_value);
+    log_with_span!(Level::INFO, "list_stub_entity_ancestors_handler executed");
+    Ok((StatusCode::OK, body))
+}
+
+fn not_found() -> (StatusCode, Json<Value>) {
+    let body = json!({
+        "message": "Stub entity not found"
+    });
+    (StatusCode::NOT_FOUND, Json(body))
+}
@@ -0,0 +1,9 @@
+use std::sync::Arc;
+
+use crate::services::import_job_service::ImportJobService;
+
+pub fn spawn(import_job_service: Arc<ImportJobService>) {
+    tokio::spawn(async move {
+        import_job_service.run_forever().await;
+    });
+}
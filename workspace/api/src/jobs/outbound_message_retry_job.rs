@@ -0,0 +1,50 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use tracing::{error, info};
+
+use crate::{
+    configuration::outbound_message_configuration::{
+        get_outbound_message_retry_backoff_seconds, get_outbound_message_retry_batch_size,
+        get_outbound_message_retry_interval_seconds, get_outbound_message_retry_lease_seconds,
+        get_outbound_message_retry_max_attempts,
+    },
+    use_cases::stub_entity_use_case::StubEntityUseCase,
+};
+
+/// Runs regardless of the publish failure policy so messages queued before it changed still go out
+pub fn spawn(stub_entity_use_case: Arc<StubEntityUseCase>) -> Result<()> {
+    let interval = Duration::from_secs(get_outbound_message_retry_interval_seconds()?);
+    let batch_size = get_outbound_message_retry_batch_size()?;
+    let max_attempts = get_outbound_message_retry_max_attempts()?;
+    let backoff = chrono::Duration::seconds(get_outbound_message_retry_backoff_seconds()?);
+    let lease = chrono::Duration::seconds(get_outbound_message_retry_lease_seconds()?);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            match stub_entity_use_case
+                .retry_outbound_messages(batch_size, max_attempts, backoff, lease)
+                .await
+            {
+                Ok(0) => {}
+                Ok(sent) => info!(
+                    app.name = %env!("CARGO_PKG_NAME"),
+                    app.version = %env!("CARGO_PKG_VERSION"),
+                    sent,
+                    "Outbound message retry job executed"
+                ),
+                Err(err) => error!(
+                    app.name = %env!("CARGO_PKG_NAME"),
+                    app.version = %env!("CARGO_PKG_VERSION"),
+                    error_message = %err,
+                    "Outbound message retry job failed"
+                ),
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use tracing::{error, info};
+
+use crate::{
+    configuration::stub_entity_enrichment_configuration::{
+        get_stub_entity_enrichment_retry_batch_size,
+        get_stub_entity_enrichment_retry_interval_seconds,
+    },
+    services::stub_entity_update_service::StubEntityUpdateService,
+};
+
+/// Runs regardless of the degraded mode so entities marked before it was turned off still settle
+pub fn spawn(stub_entity_update_service: Arc<StubEntityUpdateService>) -> Result<()> {
+    let interval = Duration::from_secs(get_stub_entity_enrichment_retry_interval_seconds()?);
+    let batch_size = get_stub_entity_enrichment_retry_batch_size()?;
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            match stub_entity_update_service
+                .retry_pending_enrichments(batch_size)
+                .await
+            {
+                Ok(0) => {}
+                Ok(enriched) => info!(
+                    app.name = %env!("CARGO_PKG_NAME"),
+                    app.version = %env!("CARGO_PKG_VERSION"),
+                    enriched,
+                    "Stub entity enrichment job executed"
+                ),
+                Err(err) => error!(
+                    app.name = %env!("CARGO_PKG_NAME"),
+                    app.version = %env!("CARGO_PKG_VERSION"),
+                    error_message = %err,
+                    "Stub entity enrichment job failed"
+                ),
+            }
+        }
+    });
+
+    Ok(())
+}
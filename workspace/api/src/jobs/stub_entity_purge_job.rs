@@ -0,0 +1,51 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use chrono::Utc;
+use tracing::{error, info};
+
+use crate::{
+    configuration::stub_entity_purge_configuration::{
+        get_stub_entity_purge_batch_size, get_stub_entity_purge_enabled,
+        get_stub_entity_purge_interval_seconds, get_stub_entity_purge_retention_days,
+    },
+    use_cases::stub_entity_use_case::StubEntityUseCase,
+};
+
+pub fn spawn(stub_entity_use_case: Arc<StubEntityUseCase>) -> Result<()> {
+    if !get_stub_entity_purge_enabled()? {
+        return Ok(());
+    }
+
+    let retention = chrono::Duration::days(get_stub_entity_purge_retention_days()? as i64);
+    let interval = Duration::from_secs(get_stub_entity_purge_interval_seconds()?);
+    let batch_size = get_stub_entity_purge_batch_size()?;
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let deleted_before = Utc::now() - retention;
+
+            match stub_entity_use_case
+                .purge_deleted(deleted_before, batch_size)
+                .await
+            {
+                Ok(purged) => info!(
+                    app.name = %env!("CARGO_PKG_NAME"),
+                    app.version = %env!("CARGO_PKG_VERSION"),
+                    purged,
+                    "Stub entity purge job executed"
+                ),
+                Err(err) => error!(
+                    app.name = %env!("CARGO_PKG_NAME"),
+                    app.version = %env!("CARGO_PKG_VERSION"),
+                    error_message = %err,
+                    "Stub entity purge job failed"
+                ),
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -1,17 +1,40 @@
-use configuration::{app_runner, tracing_configuration};
+use configuration::app_runner;
+use infrastructure::tracing::tracing_configuration;
 
 pub mod configuration {
     pub mod app_state;
     pub mod routes;
-    pub mod tracing_configuration;
     pub mod app_runner;
     pub mod app_metrics_configuration;
+    pub mod stub_entity_purge_configuration;
+    pub mod authentication_configuration;
+    pub mod stub_entity_batch_configuration;
+    pub mod stub_entity_changes_configuration;
+    pub mod import_job_configuration;
+    pub mod stub_entity_cache_configuration;
+    pub mod rate_limit_configuration;
+    pub mod stub_entity_enrichment_configuration;
+    pub mod messaging_configuration;
+    pub mod outbound_message_configuration;
 }
 
 pub mod handlers {
     pub mod stub_entity_handler;
+    pub mod stub_entity_history_handler;
+    pub mod stub_entity_tree_handler;
+    pub mod stub_entity_batch_handler;
+    pub mod stub_entity_export_handler;
+    pub mod import_job_handler;
+    pub mod api_key_handler;
     pub mod dtos {
+        pub mod merge_patch;
         pub mod stub_entity_dtos;
+        pub mod stub_entity_history_dtos;
+        pub mod stub_entity_tree_dtos;
+        pub mod stub_entity_batch_dtos;
+        pub mod stub_entity_export_dtos;
+        pub mod import_job_dtos;
+        pub mod api_key_dtos;
     }
 }
 
@@ -21,23 +44,40 @@ pub mod errors {
 
 pub mod use_cases {
     pub mod stub_entity_use_case;
+    pub mod stub_entity_history_use_case;
+    pub mod api_key_use_case;
 }
 
 pub mod services {
     pub mod stub_entity_update_service;
+    pub mod jwt_authentication_service;
+    pub mod stub_entity_change_feed_service;
+    pub mod import_job_service;
+    pub mod rate_limit_service;
+}
+
+pub mod jobs {
+    pub mod stub_entity_purge_job;
+    pub mod import_job;
+    pub mod stub_entity_enrichment_job;
+    pub mod outbound_message_retry_job;
 }
 
 pub mod middleware {
     pub mod request_middleware;
     pub mod request_metrics_middleware;
+    pub mod authentication_middleware;
+    pub mod authorization_middleware;
+    pub mod api_key_authentication_middleware;
+    pub mod tenant_middleware;
+    pub mod rate_limit_middleware;
 }
 
 #[tokio::main]
 async fn main() {
-    //TODO: Endpoints to delete 
     //TODO: Circuit break + Retry in Database ops
     //TODO: Teste integrado de endpoint
     //TODO: reqwst http call
-    tracing_configuration::configure_tracing();
+    tracing_configuration::configure_tracing(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
     app_runner::run().await.unwrap();
 }
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use futures_util::future::BoxFuture;
+use infrastructure::log_with_span;
+use opentelemetry::trace::TraceContextExt;
+use tower::{Layer, Service};
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::{errors::app_errors::AppError, use_cases::api_key_use_case::ApiKeyUseCase};
+
+use super::authentication_middleware::call_as_principal;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Authenticates service-to-service callers sending `x-api-key`; requests without the
+/// header fall through to the bearer token `AuthenticationLayer`.
+#[derive(Clone)]
+pub struct ApiKeyAuthenticationLayer {
+    api_key_use_case: Option<Arc<ApiKeyUseCase>>,
+}
+
+impl ApiKeyAuthenticationLayer {
+    pub fn new(api_key_use_case: Option<Arc<ApiKeyUseCase>>) -> Self {
+        Self { api_key_use_case }
+    }
+}
+
+impl<S> Layer<S> for ApiKeyAuthenticationLayer
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+{
+    type Service = ApiKeyAuthenticationMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyAuthenticationMiddleware {
+            inner,
+            api_key_use_case: self.api_key_use_case.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiKeyAuthenticationMiddleware<S: Clone> {
+    inner: S,
+    api_key_use_case: Option<Arc<ApiKeyUseCase>>,
+}
+
+impl<S> Service<Request> for ApiKeyAuthenticationMiddleware<S>
+where
+    S: Service<Request, Response = Response> + Send + Clone + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let key = request
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let (Some(api_key_use_case), Some(key)) = (self.api_key_use_case.clone(), key) else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            match api_key_use_case.authenticate(&key).await {
+                Ok(Some(principal)) => call_as_principal(&mut inner, request, principal).await,
+                Ok(None) => {
+                    log_with_span!(Level::WARN, "API key rejected");
+                    Ok(AppError::Unauthorized(String::from("Invalid API key")).into_response())
+                }
+                Err(err) => Ok(AppError::from(err).into_response()),
+            }
+        })
+    }
+}
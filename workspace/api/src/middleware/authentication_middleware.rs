@@ -0,0 +1,162 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use axum::{
+    extract::Request,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use domain::entities::principal_domain_entity::Principal;
+use futures_util::future::BoxFuture;
+use infrastructure::log_with_span;
+use infrastructure::logging::logging_task_local::{RequestData, REQUEST_DATA};
+use opentelemetry::trace::TraceContextExt;
+use tower::{Layer, Service};
+use tracing::{Level, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::{
+    errors::app_errors::AppError,
+    services::jwt_authentication_service::JwtAuthenticationService,
+};
+
+/// Resolves the caller of every `/api` route into a [`Principal`] request extension.
+/// With authentication disabled (`None`) every request runs as an unrestricted principal.
+#[derive(Clone)]
+pub struct AuthenticationLayer {
+    jwt_authentication_service: Option<Arc<JwtAuthenticationService>>,
+}
+
+impl AuthenticationLayer {
+    pub fn new(jwt_authentication_service: Option<Arc<JwtAuthenticationService>>) -> Self {
+        Self {
+            jwt_authentication_service,
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthenticationLayer
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+{
+    type Service = AuthenticationMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthenticationMiddleware {
+            inner,
+            jwt_authentication_service: self.jwt_authentication_service.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthenticationMiddleware<S: Clone> {
+    inner: S,
+    jwt_authentication_service: Option<Arc<JwtAuthenticationService>>,
+}
+
+impl<S> Service<Request> for AuthenticationMiddleware<S>
+where
+    S: Service<Request, Response = Response> + Send + Clone + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let jwt_authentication_service = self.jwt_authentication_service.clone();
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            // Already authenticated by an outer layer (API key)
+            if request.extensions().get::<Principal>().is_some() {
+                return inner.call(request).await;
+            }
+
+            let Some(jwt_authentication_service) = jwt_authentication_service else {
+                request
+                    .extensions_mut()
+                    .insert(Principal::authentication_disabled());
+                return inner.call(request).await;
+            };
+
+            let Some(token) = retrieve_bearer_token(&request) else {
+                return Ok(
+                    AppError::Unauthorized(String::from("Missing bearer token or API key"))
+                        .into_response(),
+                );
+            };
+
+            let principal = match jwt_authentication_service.authenticate(&token).await {
+                Ok(principal) => principal,
+                Err(err) => {
+                    log_with_span!(Level::WARN, "Bearer token rejected: {}", err);
+                    return Ok(
+                        AppError::Unauthorized(String::from("Invalid bearer token"))
+                            .into_response(),
+                    );
+                }
+            };
+
+            call_as_principal(&mut inner, request, principal).await
+        })
+    }
+}
+
+/// Runs the rest of the stack as `principal`: request extension, `REQUEST_DATA` actor and
+/// `ROOT_REQUEST` span fields. The principal is also copied to the response extensions so
+/// the request log can name the caller.
+pub async fn call_as_principal<S>(
+    inner: &mut S,
+    mut request: Request,
+    principal: Principal,
+) -> Result<Response, S::Error>
+where
+    S: Service<Request, Response = Response>,
+{
+    let span = Span::current();
+    span.record("auth.principal", principal.subject.as_str());
+    span.record("auth.method", principal.method.as_str());
+
+    let actor = principal.subject.clone();
+    request.extensions_mut().insert(principal.clone());
+
+    let response = match REQUEST_DATA.try_with(|request_data| request_data.clone()) {
+        Ok(request_data) => {
+            REQUEST_DATA
+                .scope(
+                    RequestData {
+                        actor: Some(actor),
+                        ..request_data
+                    },
+                    inner.call(request),
+                )
+                .await
+        }
+        Err(_) => inner.call(request).await,
+    };
+
+    response.map(|mut response| {
+        response.extensions_mut().insert(principal);
+        response
+    })
+}
+
+fn retrieve_bearer_token(request: &Request) -> Option<String> {
+    let value = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())?;
+    let (scheme, token) = value.split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") || token.trim().is_empty() {
+        return None;
+    }
+    Some(token.trim().to_string())
+}
@@ -0,0 +1,81 @@
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use domain::entities::principal_domain_entity::Principal;
+use futures_util::future::BoxFuture;
+use tower::{Layer, Service};
+
+use crate::errors::app_errors::AppError;
+
+pub const STUB_ENTITY_READ: &str = "stub-entity:read";
+pub const STUB_ENTITY_WRITE: &str = "stub-entity:write";
+pub const STUB_ENTITY_ADMIN: &str = "stub-entity:admin";
+pub const API_KEY_ADMIN: &str = "api-key:admin";
+
+/// Declares the scope a route requires; must run inside `AuthenticationLayer`.
+pub fn require_scope(scope: &'static str) -> RequireScopeLayer {
+    RequireScopeLayer { scope }
+}
+
+pub fn ensure_scope(principal: &Principal, scope: &'static str) -> Result<(), AppError> {
+    if principal.has_scope(scope) {
+        Ok(())
+    } else {
+        Err(AppError::InsufficientScope(scope.to_string()))
+    }
+}
+
+#[derive(Clone)]
+pub struct RequireScopeLayer {
+    scope: &'static str,
+}
+
+impl<S> Layer<S> for RequireScopeLayer
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+{
+    type Service = RequireScopeMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequireScopeMiddleware {
+            inner,
+            scope: self.scope,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequireScopeMiddleware<S: Clone> {
+    inner: S,
+    scope: &'static str,
+}
+
+impl<S> Service<Request> for RequireScopeMiddleware<S>
+where
+    S: Service<Request, Response = Response> + Send + Clone + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let authorization = match request.extensions().get::<Principal>() {
+            Some(principal) => ensure_scope(principal, self.scope),
+            None => Err(AppError::Unauthorized(String::from("Missing bearer token"))),
+        };
+
+        match authorization {
+            Ok(()) => Box::pin(self.inner.call(request)),
+            Err(err) => Box::pin(async move { Ok(err.into_response()) }),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use axum::{
+    extract::{ConnectInfo, MatchedPath, Request},
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use domain::entities::principal_domain_entity::Principal;
+use futures_util::future::BoxFuture;
+use tower::{load_shed::error::Overloaded, Layer, Service};
+
+use crate::{
+    errors::app_errors::{AppError, UnexpectedError},
+    services::rate_limit_service::{RateLimitService, RateLimitStatus},
+};
+
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+const RATE_LIMIT_LIMIT_HEADER: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATE_LIMIT_REMAINING_HEADER: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Throttles each client (principal, or IP address when authentication is disabled) with the
+/// token bucket of the matched route; must run inside the authentication layers.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    rate_limit_service: Option<Arc<RateLimitService>>,
+}
+
+impl RateLimitLayer {
+    pub fn new(rate_limit_service: Option<Arc<RateLimitService>>) -> Self {
+        Self { rate_limit_service }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+{
+    type Service = RateLimitMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitMiddleware {
+            inner,
+            rate_limit_service: self.rate_limit_service.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitMiddleware<S: Clone> {
+    inner: S,
+    rate_limit_service: Option<Arc<RateLimitService>>,
+}
+
+impl<S> Service<Request> for RateLimitMiddleware<S>
+where
+    S: Service<Request, Response = Response> + Send + Clone + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let Some(rate_limit_service) = &self.rate_limit_service else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        let path_pattern = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|path| path.as_str().to_string())
+            .unwrap_or_else(|| request.uri().path().to_string());
+        let route = format!("{} {}", request.method(), path_pattern);
+        let status = rate_limit_service.acquire(&route, &client_key(rate_limit_service, &request));
+
+        if !status.allowed {
+            metrics::counter!(
+                "http_requests_throttled_total",
+                "reason" => "rate_limited",
+                "request.path_pattern" => path_pattern
+            )
+            .increment(1);
+
+            let mut response = AppError::TooManyRequests(String::from("Rate limit exceeded"))
+                .into_response();
+            insert_rate_limit_headers(response.headers_mut(), &status);
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(status.retry_after_seconds));
+            return Box::pin(async move { Ok(response) });
+        }
+
+        let future = self.inner.call(request);
+        Box::pin(async move {
+            let mut response = future.await?;
+            insert_rate_limit_headers(response.headers_mut(), &status);
+            Ok(response)
+        })
+    }
+}
+
+fn client_key(rate_limit_service: &RateLimitService, request: &Request) -> String {
+    if let Some(principal) = request
+        .extensions()
+        .get::<Principal>()
+        .filter(|principal| principal.is_authenticated())
+    {
+        return format!("{}:{}", principal.method.as_str(), principal.subject);
+    }
+
+    let Some(ConnectInfo(peer)) = request.extensions().get::<ConnectInfo<SocketAddr>>() else {
+        return String::from("ip:unknown");
+    };
+    let forwarded_for = request
+        .headers()
+        .get(FORWARDED_FOR_HEADER)
+        .and_then(|value| value.to_str().ok());
+    format!("ip:{}", rate_limit_service.client_ip(peer.ip(), forwarded_for))
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, status: &RateLimitStatus) {
+    headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(status.limit));
+    headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(status.remaining));
+    headers.insert(RATE_LIMIT_RESET_HEADER, HeaderValue::from(status.reset_seconds));
+}
+
+/// Turns errors of the load shedding stack into responses; requests arriving while every
+/// concurrency slot is taken are rejected right away instead of queueing.
+pub async fn handle_load_shedding_error(err: BoxError) -> Response {
+    if err.is::<Overloaded>() {
+        metrics::counter!("http_requests_throttled_total", "reason" => "overloaded").increment(1);
+        let mut response =
+            AppError::ServiceUnavailable(String::from("Server is overloaded")).into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(1));
+        return response;
+    }
+
+    AppError::UnexpectedError(UnexpectedError::new(anyhow::anyhow!(err))).into_response()
+}
@@ -7,6 +7,8 @@ use futures_util::future::BoxFuture;
 use std::task::{Context, Poll};
 use tower::{Layer, Service};
 
+use super::tenant_middleware::Tenant;
+
 #[derive(Clone)]
 pub struct RequestMetricsLayer;
 
@@ -58,6 +60,11 @@ where
 
             let latency = start_time.elapsed().as_secs_f64();
             let response_status_code = response.status().to_string();
+            let tenant_id = response
+                .extensions()
+                .get::<Tenant>()
+                .map(|tenant| tenant.0.clone())
+                .unwrap_or_else(|| "none".to_string());
 
             let labels = [
                 ("app.name", env!("CARGO_PKG_NAME").to_string()),
@@ -65,6 +72,7 @@ where
                 ("request.http_method", request_http_method),
                 ("request.path_pattern", request_path_pattern),
                 ("response.status_code", response_status_code),
+                ("tenant", tenant_id),
             ];
 
             metrics::counter!("http_requests_total", &labels).increment(1);
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use axum::extract::MatchedPath;
+use domain::entities::principal_domain_entity::Principal;
 use axum::{extract::Request, response::Response};
 use futures_util::future::BoxFuture;
 use infrastructure::log_with_span;
@@ -17,6 +18,8 @@ use tracing::Span;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 use uuid::Uuid;
 
+use super::tenant_middleware::Tenant;
+
 #[derive(Clone)]
 pub struct RequestLayer;
 
@@ -80,7 +83,7 @@ where
 
         Box::pin(
             REQUEST_DATA.scope(
-                RequestData::new(correlation_id.clone()),
+                RequestData::new(correlation_id.clone(), None, None),
                 async move {
                     let mut response: axum::http::Response<axum::body::Body> = future.await?;
 
@@ -124,6 +127,9 @@ fn start_span(
             request.path_pattern = %request_path_pattern, 
             request.path = %request_path, 
             response.status_code = field::Empty, 
+            auth.principal = field::Empty,
+            auth.method = field::Empty,
+            tenant.id = field::Empty,
             correlation_id = %correlation_id);
 
     span
@@ -138,6 +144,16 @@ fn log_request_processed(
 ) {
     let duration = start_time.elapsed();
     let duration_ms = duration.as_millis();
+    let (auth_principal, auth_method) = response
+        .extensions()
+        .get::<Principal>()
+        .map(|principal| (principal.subject.as_str(), principal.method.as_str()))
+        .unwrap_or(("none", "none"));
+    let tenant_id = response
+        .extensions()
+        .get::<Tenant>()
+        .map(|tenant| tenant.0.as_str())
+        .unwrap_or("none");
 
     if response.status().is_server_error() {
         log_with_span!(
@@ -146,6 +162,9 @@ fn log_request_processed(
             request.path_pattern=%request_path_pattern,
             request.path=%request_path,
             response.status_code=%response.status().as_u16(), 
+            auth.principal=%auth_principal,
+            auth.method=%auth_method,
+            tenant.id=%tenant_id,
             duration_ms=%duration_ms,
             "[HTTP REQUEST PROCESSED]");
     } else {
@@ -155,6 +174,9 @@ fn log_request_processed(
             request.path_pattern=%request_path_pattern,
             request.path=%request_path,
             response.status_code=%response.status().as_u16(), 
+            auth.principal=%auth_principal,
+            auth.method=%auth_method,
+            tenant.id=%tenant_id,
             duration_ms=%duration_ms,
             "[HTTP REQUEST PROCESSED]");
     }
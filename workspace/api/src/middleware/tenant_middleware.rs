@@ -0,0 +1,179 @@
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use domain::entities::principal_domain_entity::Principal;
+use futures_util::future::BoxFuture;
+use infrastructure::logging::logging_task_local::{RequestData, DEFAULT_TENANT_ID, REQUEST_DATA};
+use tower::{Layer, Service};
+use tracing::Span;
+
+use crate::errors::app_errors::AppError;
+
+const TENANT_ID_HEADER: &str = "x-tenant-id";
+const TENANT_ID_MAX_LENGTH: usize = 64;
+
+/// Tenant the request was served for, copied to the response for metrics and logs
+#[derive(Debug, Clone)]
+pub struct Tenant(pub String);
+
+/// Resolves the request tenant and scopes `REQUEST_DATA` with it; must run inside the
+/// authentication layers. An authenticated caller is served for the tenant of its credential,
+/// and the `x-tenant-id` header only picks the tenant while authentication is disabled.
+#[derive(Clone)]
+pub struct TenantLayer;
+
+impl<S> Layer<S> for TenantLayer
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+{
+    type Service = TenantMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TenantMiddleware { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct TenantMiddleware<S: Clone> {
+    inner: S,
+}
+
+impl<S> Service<Request> for TenantMiddleware<S>
+where
+    S: Service<Request, Response = Response> + Send + Clone + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let principal = request.extensions().get::<Principal>();
+        let requested_tenant_id = request
+            .headers()
+            .get(TENANT_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let tenant_id = match resolve_tenant_id(principal, requested_tenant_id) {
+            Ok(tenant_id) => tenant_id,
+            Err(err) => return Box::pin(async move { Ok(err.into_response()) }),
+        };
+
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            Span::current().record("tenant.id", tenant_id.as_str());
+
+            let response = match REQUEST_DATA.try_with(|request_data| request_data.clone()) {
+                Ok(request_data) => {
+                    REQUEST_DATA
+                        .scope(
+                            RequestData {
+                                tenant_id: Some(tenant_id.clone()),
+                                ..request_data
+                            },
+                            inner.call(request),
+                        )
+                        .await
+                }
+                Err(_) => inner.call(request).await,
+            };
+
+            response.map(|mut response| {
+                response.extensions_mut().insert(Tenant(tenant_id));
+                response
+            })
+        })
+    }
+}
+
+fn resolve_tenant_id(
+    principal: Option<&Principal>,
+    requested_tenant_id: Option<String>,
+) -> Result<String, AppError> {
+    let tenant_id = match principal.filter(|principal| principal.is_authenticated()) {
+        Some(principal) => match (&principal.tenant_id, requested_tenant_id) {
+            (None, _) => {
+                return Err(AppError::Forbidden(String::from(
+                    "The credential is not bound to a tenant",
+                )))
+            }
+            (Some(claimed), Some(requested)) if *claimed != requested => {
+                return Err(AppError::Forbidden(String::from(
+                    "x-tenant-id does not match the authenticated tenant",
+                )))
+            }
+            (Some(claimed), _) => claimed.clone(),
+        },
+        None => requested_tenant_id.unwrap_or_else(|| DEFAULT_TENANT_ID.to_string()),
+    };
+
+    let valid = !tenant_id.is_empty()
+        && tenant_id.len() <= TENANT_ID_MAX_LENGTH
+        && tenant_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(AppError::BadRequest(String::from("Invalid tenant id")));
+    }
+
+    Ok(tenant_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use domain::entities::principal_domain_entity::AuthenticationMethod;
+
+    use super::*;
+
+    fn principal(tenant_id: Option<&str>) -> Principal {
+        Principal {
+            tenant_id: tenant_id.map(str::to_string),
+            ..Principal::new(String::from("batch-job"), HashSet::new(), AuthenticationMethod::ApiKey)
+        }
+    }
+
+    #[test]
+    fn test_authenticated_caller_is_bound_to_its_tenant() {
+        let bound = principal(Some("tenant-a"));
+        assert_eq!(resolve_tenant_id(Some(&bound), None).unwrap(), "tenant-a");
+        assert_eq!(
+            resolve_tenant_id(Some(&bound), Some(String::from("tenant-a"))).unwrap(),
+            "tenant-a"
+        );
+        assert!(matches!(
+            resolve_tenant_id(Some(&bound), Some(String::from("tenant-b"))),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            resolve_tenant_id(Some(&principal(None)), Some(String::from("tenant-b"))),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test_header_picks_the_tenant_without_authentication() {
+        let anonymous = Principal::authentication_disabled();
+        assert_eq!(
+            resolve_tenant_id(Some(&anonymous), Some(String::from("tenant-b"))).unwrap(),
+            "tenant-b"
+        );
+        assert_eq!(resolve_tenant_id(Some(&anonymous), None).unwrap(), DEFAULT_TENANT_ID);
+        assert!(matches!(
+            resolve_tenant_id(None, Some(String::from("bad tenant"))),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
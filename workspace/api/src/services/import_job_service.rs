@@ -0,0 +1,394 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use domain::{
+    entities::{
+        import_job_domain_entity::{
+            ImportFormat, ImportJob, ImportJobReport, ImportJobStatus, ImportLine, ImportLineStatus,
+        },
+        stub_domain_entity::StubEntity,
+        stub_event_domain_entity::StubEntityEvent,
+    },
+    errors::domain_errors::DomainError,
+    ports::repositories::import_job_repository_port::ImportJobRepositoryPort,
+};
+use infrastructure::database::repositories::database_data::{DatabaseConnection, Transaction};
+use infrastructure::log_with_span;
+use infrastructure::logging::logging_task_local::{RequestData, REQUEST_DATA};
+use opentelemetry::trace::TraceContextExt;
+use tokio::sync::Notify;
+use tracing::{instrument, Level};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    errors::app_errors::extract_validation_errors,
+    handlers::dtos::import_job_dtos::StubEntityImportItemDto,
+    services::stub_entity_update_service::commit_or_rollback,
+    use_cases::stub_entity_use_case::StubEntityUseCase,
+};
+
+/// Failed lines listed in a job report
+const MAX_REPORTED_ERRORS: u64 = 100;
+
+pub struct ImportJobService {
+    import_job_repository: Arc<dyn ImportJobRepositoryPort>,
+    stub_entity_use_case: Arc<StubEntityUseCase>,
+    database_connection: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+    jobs_available: Notify,
+    /// Identifies the jobs this instance claimed
+    worker_id: String,
+    lease: Duration,
+    retry_interval: Duration,
+}
+
+impl ImportJobService {
+    pub fn new(
+        import_job_repository: Arc<dyn ImportJobRepositoryPort>,
+        stub_entity_use_case: Arc<StubEntityUseCase>,
+        database_connection: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+        lease: Duration,
+        retry_interval: Duration,
+    ) -> Self {
+        Self {
+            import_job_repository,
+            stub_entity_use_case,
+            database_connection,
+            jobs_available: Notify::new(),
+            worker_id: Uuid::new_v4().to_string(),
+            lease,
+            retry_interval,
+        }
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn create(&self, format: ImportFormat, lines: Vec<ImportLine>) -> Result<ImportJobReport> {
+        let job = self.import_job_repository.add(format, &lines).await?;
+        self.jobs_available.notify_one();
+        self.report(job).await
+    }
+
+    pub async fn get(&self, id: i32) -> Result<Option<ImportJobReport>> {
+        match self.import_job_repository.get(id).await? {
+            Some(job) => Ok(Some(self.report(job).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn report(&self, job: ImportJob) -> Result<ImportJobReport> {
+        let counts = self.import_job_repository.get_counts(job.id).await?;
+        let errors = self
+            .import_job_repository
+            .get_failed_lines(job.id, MAX_REPORTED_ERRORS)
+            .await?;
+        Ok(ImportJobReport { job, counts, errors })
+    }
+
+    /// Processes pending jobs, and those whose worker stopped, one at a time, then waits for
+    /// new ones. Each job is claimed first, so instances never run the same job at once.
+    /// Every created line is recorded in the transaction that creates its entity,
+    /// so running a job again (e.g. after a restart) picks up where it stopped.
+    pub async fn run_forever(&self) {
+        loop {
+            match self.import_job_repository.get_unfinished().await {
+                Ok(jobs) => {
+                    for job in jobs {
+                        self.run(job.id).await;
+                    }
+                }
+                Err(err) => log_with_span!(Level::ERROR, "Failed to load import jobs: {}", err),
+            }
+            // Jobs of other instances and jobs backing off are only seen by polling
+            let _ = tokio::time::timeout(self.retry_interval, self.jobs_available.notified()).await;
+        }
+    }
+
+    async fn run(&self, job_id: i32) {
+        let job = match self
+            .import_job_repository
+            .claim(job_id, &self.worker_id, Utc::now() + self.lease)
+            .await
+        {
+            Ok(Some(job)) => job,
+            Ok(None) => return,
+            Err(err) => {
+                log_with_span!(Level::ERROR, "Failed to claim import job {}: {}", job_id, err);
+                return;
+            }
+        };
+        let request_data = RequestData::new(
+            format!("import-job-{}", job_id),
+            job.actor.clone(),
+            Some(job.tenant_id.clone()),
+        );
+
+        REQUEST_DATA.scope(request_data, self.run_in_scope(job_id)).await;
+    }
+
+    /// Errors other than invalid lines, e.g. a lost connection, leave the job running
+    /// and claimed until `retry_interval` has passed, when it is processed again
+    async fn run_in_scope(&self, job_id: i32) {
+        let result = match self.process(job_id).await {
+            Ok(()) => {
+                log_with_span!(Level::INFO, "Import job {} completed", job_id);
+                self.import_job_repository
+                    .finish(job_id, &self.worker_id, ImportJobStatus::Completed, None)
+                    .await
+            }
+            Err(err) => {
+                log_with_span!(
+                    Level::ERROR,
+                    "Import job {} interrupted, retrying in {}s: {}",
+                    job_id,
+                    self.retry_interval.as_secs(),
+                    err
+                );
+                self.import_job_repository
+                    .renew_lease(job_id, &self.worker_id, Utc::now() + self.retry_interval)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(true) => {}
+            Ok(false) => log_with_span!(
+                Level::WARN,
+                "Import job {} was taken over by another worker",
+                job_id
+            ),
+            Err(err) => log_with_span!(
+                Level::ERROR,
+                "Failed to record import job {} status: {}",
+                job_id,
+                err
+            ),
+        }
+    }
+
+    /// Renews the lease once half of it has passed, and stops the job once another worker
+    /// took it over
+    async fn keep_claim(&self, job_id: i32, renewed_at: &mut Instant) -> Result<()> {
+        if renewed_at.elapsed() < self.lease / 2 {
+            return Ok(());
+        }
+        let now = Instant::now();
+        if !self
+            .import_job_repository
+            .renew_lease(job_id, &self.worker_id, Utc::now() + self.lease)
+            .await?
+        {
+            bail!("Import job {} was taken over by another worker", job_id);
+        }
+        *renewed_at = now;
+        Ok(())
+    }
+
+    /// Lines are created in passes: each pass creates the lines whose parent is known, until
+    /// a pass makes no progress. What is left then points at a missing key or forms a cycle.
+    async fn process(&self, job_id: i32) -> Result<()> {
+        let repository = &self.import_job_repository;
+        let mut renewed_at = Instant::now();
+
+        let lines = repository.get_lines(job_id).await?;
+
+        let mut key_lines: HashMap<String, i32> = HashMap::new();
+        let mut created_keys: HashMap<String, i32> = HashMap::new();
+        let mut failed_keys: HashMap<String, i32> = HashMap::new();
+        let mut pending = Vec::new();
+
+        for mut line in lines {
+            self.keep_claim(job_id, &mut renewed_at).await?;
+            let item = line
+                .content
+                .take()
+                .map(serde_json::from_value::<StubEntityImportItemDto>);
+            let key = match &item {
+                Some(Ok(item)) => item.key.clone(),
+                _ => None,
+            };
+            if let Some(key) = &key {
+                if let Some(first_line) = key_lines.get(key) {
+                    if line.status == ImportLineStatus::Pending {
+                        let error = format!("key {} is already used by line {}", key, first_line);
+                        repository.fail_line(job_id, line.line_number, error).await?;
+                    }
+                    continue;
+                }
+                key_lines.insert(key.clone(), line.line_number);
+            }
+
+            match (line.status, item) {
+                (ImportLineStatus::Created, _) => {
+                    if let (Some(key), Some(entity_id)) = (key, line.entity_id) {
+                        created_keys.insert(key, entity_id);
+                    }
+                }
+                (ImportLineStatus::Failed, _) => {
+                    if let Some(key) = key {
+                        failed_keys.insert(key, line.line_number);
+                    }
+                }
+                (ImportLineStatus::Pending, Some(Ok(item))) => {
+                    match validate_item(&item) {
+                        Ok(()) => pending.push((line.line_number, item)),
+                        Err(error) => {
+                            repository.fail_line(job_id, line.line_number, error).await?;
+                            if let Some(key) = key {
+                                failed_keys.insert(key, line.line_number);
+                            }
+                        }
+                    }
+                }
+                (ImportLineStatus::Pending, Some(Err(err))) => {
+                    repository
+                        .fail_line(job_id, line.line_number, err.to_string())
+                        .await?;
+                }
+                (ImportLineStatus::Pending, None) => {
+                    repository
+                        .fail_line(job_id, line.line_number, String::from("Line has no content"))
+                        .await?;
+                }
+            }
+        }
+
+        loop {
+            let mut remaining = Vec::new();
+            let mut progress = false;
+
+            for (line_number, item) in pending {
+                self.keep_claim(job_id, &mut renewed_at).await?;
+                let auto_ref = match &item.parent_key {
+                    None => item.auto_ref,
+                    Some(parent_key) => match created_keys.get(parent_key) {
+                        Some(parent_id) => Some(*parent_id),
+                        None => {
+                            match failed_keys.get(parent_key) {
+                                Some(parent_line) => {
+                                    let error = format!(
+                                        "parent_key {} refers to line {}, which failed",
+                                        parent_key, parent_line
+                                    );
+                                    repository.fail_line(job_id, line_number, error).await?;
+                                    if let Some(key) = &item.key {
+                                        failed_keys.insert(key.clone(), line_number);
+                                    }
+                                    progress = true;
+                                }
+                                None => remaining.push((line_number, item)),
+                            }
+                            continue;
+                        }
+                    },
+                };
+
+                match self.create_line(job_id, line_number, &item, auto_ref).await {
+                    Ok(entity_id) => {
+                        if let Some(key) = &item.key {
+                            created_keys.insert(key.clone(), entity_id);
+                        }
+                    }
+                    Err(err) if err.is::<DomainError>() => {
+                        repository.fail_line(job_id, line_number, err.to_string()).await?;
+                        if let Some(key) = &item.key {
+                            failed_keys.insert(key.clone(), line_number);
+                        }
+                    }
+                    Err(err) => return Err(err),
+                }
+                progress = true;
+            }
+
+            let settled = remaining.is_empty() || !progress;
+            pending = remaining;
+            if settled {
+                break;
+            }
+        }
+
+        for (line_number, item) in pending {
+            let parent_key = item.parent_key.unwrap_or_default();
+            let error = if key_lines.contains_key(&parent_key) {
+                format!("parent_key {} is part of a cycle", parent_key)
+            } else {
+                format!("parent_key {} does not match any line", parent_key)
+            };
+            repository.fail_line(job_id, line_number, error).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn create_line(
+        &self,
+        job_id: i32,
+        line_number: i32,
+        item: &StubEntityImportItemDto,
+        auto_ref: Option<i32>,
+    ) -> Result<i32> {
+        let entity = StubEntity {
+            id: None,
+            name: item.name.clone(),
+            value: item.value.to_domain(),
+            auto_ref,
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 0,
+        };
+
+        let txn = Transaction::begin(&self.database_connection).await?;
+        let result = async {
+            let entity = self
+                .stub_entity_use_case
+                .add_within_transaction(&entity, txn.as_ref())
+                .await?;
+            self.import_job_repository
+                .complete_line_within_transaction(job_id, line_number, entity.id.unwrap(), txn.as_ref())
+                .await?;
+            Ok(entity)
+        }
+        .await;
+        let entity = commit_or_rollback(txn, result).await?;
+        let entity_id = entity.id.unwrap();
+
+        // The line is committed, so a failed publish must not fail it or the job
+        if let Err(err) = self
+            .stub_entity_use_case
+            .publish(vec![StubEntityEvent::Created(entity)])
+            .await
+        {
+            log_with_span!(
+                Level::ERROR,
+                "Import job {} line {} created stub entity {} without its event: {}",
+                job_id,
+                line_number,
+                entity_id,
+                err
+            );
+        }
+        Ok(entity_id)
+    }
+}
+
+fn validate_item(item: &StubEntityImportItemDto) -> Result<(), String> {
+    if let Err(errors) = item.validate() {
+        return Err(serde_json::Value::Object(extract_validation_errors(&errors)).to_string());
+    }
+    if item.auto_ref.is_some() && item.parent_key.is_some() {
+        return Err(String::from("auto_ref and parent_key cannot be used together"));
+    }
+    Ok(())
+}
+
+impl fmt::Debug for ImportJobService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImportJobService").finish()
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use domain::entities::principal_domain_entity::{AuthenticationMethod, Principal};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::instrument;
+
+const SUPPORTED_ALGORITHMS: [Algorithm; 2] = [Algorithm::RS256, Algorithm::ES256];
+
+#[derive(Debug, Clone)]
+pub enum JwksSource {
+    File(String),
+    Url(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+    /// OAuth 2.0 style space-delimited scopes
+    #[serde(default)]
+    scope: Option<String>,
+    /// Array style scopes (Azure AD, Okta)
+    #[serde(default)]
+    scp: Option<Vec<String>>,
+    #[serde(default)]
+    tenant_id: Option<String>,
+}
+
+impl Claims {
+    fn into_principal(self) -> Principal {
+        let mut scopes: HashSet<String> = HashSet::new();
+        if let Some(scope) = self.scope {
+            scopes.extend(scope.split_whitespace().map(String::from));
+        }
+        if let Some(scp) = self.scp {
+            scopes.extend(scp);
+        }
+        Principal {
+            tenant_id: self.tenant_id,
+            ..Principal::new(self.sub, scopes, AuthenticationMethod::Jwt)
+        }
+    }
+}
+
+struct CachedJwks {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+pub struct JwtAuthenticationService {
+    jwks_source: JwksSource,
+    jwks: RwLock<CachedJwks>,
+    issuer: String,
+    audience: String,
+    leeway_seconds: u64,
+    refresh_interval: Duration,
+    http_client: Arc<reqwest::Client>,
+}
+
+impl fmt::Debug for JwtAuthenticationService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtAuthenticationService")
+            .field("jwks_source", &self.jwks_source)
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish()
+    }
+}
+
+impl JwtAuthenticationService {
+    pub async fn new(
+        jwks_source: JwksSource,
+        issuer: String,
+        audience: String,
+        leeway_seconds: u64,
+        refresh_interval: Duration,
+        http_client: Arc<reqwest::Client>,
+    ) -> Result<Self> {
+        let keys = load_jwks(&jwks_source, &http_client).await?;
+        Ok(Self {
+            jwks_source,
+            jwks: RwLock::new(CachedJwks {
+                keys,
+                fetched_at: Instant::now(),
+            }),
+            issuer,
+            audience,
+            leeway_seconds,
+            refresh_interval,
+            http_client,
+        })
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn authenticate(&self, token: &str) -> Result<Principal> {
+        let header = decode_header(token).context("Malformed token header")?;
+        if !SUPPORTED_ALGORITHMS.contains(&header.alg) {
+            bail!("Unsupported token algorithm {:?}", header.alg);
+        }
+
+        let jwk = self.find_key(header.kid.as_deref()).await?;
+        let decoding_key = DecodingKey::from_jwk(&jwk).context("Unusable JWKS key")?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        validation.leeway = self.leeway_seconds;
+
+        let token_data = decode::<Claims>(token, &decoding_key, &validation)?;
+        Ok(token_data.claims.into_principal())
+    }
+
+    async fn find_key(&self, kid: Option<&str>) -> Result<Jwk> {
+        if let Some(jwk) = select_key(&self.jwks.read().await.keys, kid) {
+            return Ok(jwk);
+        }
+
+        // Unknown kid: the issuer may have rotated its keys, so reload them (throttled)
+        let mut jwks = self.jwks.write().await;
+        if jwks.fetched_at.elapsed() >= self.refresh_interval {
+            jwks.keys = load_jwks(&self.jwks_source, &self.http_client).await?;
+            jwks.fetched_at = Instant::now();
+        }
+        match select_key(&jwks.keys, kid) {
+            Some(jwk) => Ok(jwk),
+            None => bail!("No JWKS key matches kid {:?}", kid),
+        }
+    }
+}
+
+fn select_key(keys: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => keys.find(kid).cloned(),
+        None if keys.keys.len() == 1 => keys.keys.first().cloned(),
+        None => None,
+    }
+}
+
+async fn load_jwks(jwks_source: &JwksSource, http_client: &reqwest::Client) -> Result<JwkSet> {
+    let keys = match jwks_source {
+        JwksSource::File(path) => {
+            let content = tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| format!("Failed to read JWKS file {}", path))?;
+            serde_json::from_str(&content)?
+        }
+        JwksSource::Url(url) => http_client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<JwkSet>()
+            .await
+            .with_context(|| format!("Failed to fetch JWKS from {}", url))?,
+    };
+    Ok(keys)
+}
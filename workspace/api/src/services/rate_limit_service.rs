@@ -0,0 +1,301 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    net::IpAddr,
+    str::FromStr,
+    sync::Mutex,
+    time::Instant,
+};
+
+use anyhow::{bail, Context, Result};
+
+/// Buckets tracked before full (idle) ones are dropped; a full bucket behaves like a new one
+const MAX_TRACKED_BUCKETS: usize = 10000;
+
+/// Buckets kept by an eviction, leaving room so the next one is thousands of clients away
+const EVICTION_TARGET_BUCKETS: usize = MAX_TRACKED_BUCKETS * 9 / 10;
+
+/// Routes without their own policy share one bucket per client
+const DEFAULT_ROUTE: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitPolicy {
+    pub requests_per_second: f64,
+    pub burst: f64,
+}
+
+/// Policy for a single route, written as `<METHOD> <path pattern>=<requests per second>:<burst>`,
+/// e.g. `POST /api/v1/imports=0.2:2`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteRateLimit {
+    pub route: String,
+    pub policy: RateLimitPolicy,
+}
+
+impl FromStr for RouteRateLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let (route, policy) = value
+            .rsplit_once('=')
+            .with_context(|| format!("Missing policy in route rate limit {}", value))?;
+        let (requests_per_second, burst) = policy
+            .split_once(':')
+            .with_context(|| format!("Missing burst in route rate limit {}", value))?;
+        let policy = RateLimitPolicy {
+            requests_per_second: requests_per_second.trim().parse()?,
+            burst: burst.trim().parse()?,
+        };
+        if policy.requests_per_second <= 0.0 || policy.burst < 1.0 {
+            bail!("Invalid route rate limit {}", value);
+        }
+        Ok(Self {
+            route: route.trim().to_string(),
+            policy,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitStatus {
+    pub allowed: bool,
+    pub limit: u64,
+    pub remaining: u64,
+    /// Seconds until the bucket is full again
+    pub reset_seconds: u64,
+    /// Seconds until the next request is allowed, when throttled
+    pub retry_after_seconds: u64,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, policy: &RateLimitPolicy, now: Instant) {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * policy.requests_per_second).min(policy.burst);
+        self.updated_at = now;
+    }
+}
+
+/// Token buckets per client and route, kept in memory, so limits apply per instance
+pub struct RateLimitService {
+    default_policy: RateLimitPolicy,
+    route_policies: HashMap<String, RateLimitPolicy>,
+    trusted_proxies: Vec<IpAddr>,
+    buckets: Mutex<HashMap<(String, String), TokenBucket>>,
+}
+
+impl fmt::Debug for RateLimitService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitService")
+            .field("default_policy", &self.default_policy)
+            .field("route_policies", &self.route_policies)
+            .field("trusted_proxies", &self.trusted_proxies)
+            .finish()
+    }
+}
+
+impl RateLimitService {
+    pub fn new(
+        default_policy: RateLimitPolicy,
+        route_limits: Vec<RouteRateLimit>,
+        trusted_proxies: Vec<IpAddr>,
+    ) -> Self {
+        Self {
+            default_policy,
+            route_policies: route_limits
+                .into_iter()
+                .map(|route_limit| (route_limit.route, route_limit.policy))
+                .collect(),
+            trusted_proxies,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Address of the client behind `peer`. `X-Forwarded-For` is only read when `peer` is a
+    /// trusted proxy, from the right, skipping the trusted proxies it went through; anything
+    /// left of the first untrusted address could have been written by the client itself.
+    pub fn client_ip(&self, peer: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+        let mut client = peer;
+        if !self.trusted_proxies.contains(&client) {
+            return client;
+        }
+        for address in forwarded_for.unwrap_or_default().rsplit(',') {
+            match address.trim().parse::<IpAddr>() {
+                Ok(address) => client = address,
+                Err(_) => break,
+            }
+            if !self.trusted_proxies.contains(&client) {
+                break;
+            }
+        }
+        client
+    }
+
+    /// Takes a token from the client's bucket for `route` (`<METHOD> <path pattern>`)
+    pub fn acquire(&self, route: &str, client: &str) -> RateLimitStatus {
+        self.acquire_at(route, client, Instant::now())
+    }
+
+    fn acquire_at(&self, route: &str, client: &str, now: Instant) -> RateLimitStatus {
+        let (route, policy) = match self.route_policies.get_key_value(route) {
+            Some((route, policy)) => (route.as_str(), *policy),
+            None => (DEFAULT_ROUTE, self.default_policy),
+        };
+        let key = (route.to_string(), client.to_string());
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_TRACKED_BUCKETS && !buckets.contains_key(&key) {
+            self.evict(&mut buckets, now);
+        }
+
+        let bucket = buckets.entry(key).or_insert(TokenBucket {
+            tokens: policy.burst,
+            updated_at: now,
+        });
+        bucket.refill(&policy, now);
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        RateLimitStatus {
+            allowed,
+            limit: policy.burst as u64,
+            remaining: bucket.tokens.floor() as u64,
+            reset_seconds: ((policy.burst - bucket.tokens) / policy.requests_per_second).ceil() as u64,
+            retry_after_seconds: if allowed {
+                0
+            } else {
+                ((1.0 - bucket.tokens) / policy.requests_per_second).ceil() as u64
+            },
+        }
+    }
+
+    /// Drops the full buckets and, when too many clients are active, the fullest remaining
+    /// ones, which only lets those clients start over with a full bucket
+    fn evict(&self, buckets: &mut HashMap<(String, String), TokenBucket>, now: Instant) {
+        buckets.retain(|(route, _), bucket| {
+            let policy = self.route_policies.get(route).unwrap_or(&self.default_policy);
+            bucket.refill(policy, now);
+            bucket.tokens < policy.burst
+        });
+        if buckets.len() <= EVICTION_TARGET_BUCKETS {
+            return;
+        }
+
+        let mut by_tokens: Vec<_> = buckets
+            .iter()
+            .map(|(key, bucket)| (bucket.tokens, key.clone()))
+            .collect();
+        by_tokens.sort_unstable_by(|(left, _), (right, _)| right.total_cmp(left));
+        let excess = buckets.len() - EVICTION_TARGET_BUCKETS;
+        for (_, key) in by_tokens.into_iter().take(excess) {
+            buckets.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const ROUTE: &str = "GET /api/v1/stub-entity";
+
+    fn service(requests_per_second: f64, burst: f64) -> RateLimitService {
+        RateLimitService::new(
+            RateLimitPolicy {
+                requests_per_second,
+                burst,
+            },
+            Vec::new(),
+            vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+        )
+    }
+
+    #[test]
+    fn test_acquire_refills_tokens_over_time() {
+        let service = service(2.0, 3.0);
+        let now = Instant::now();
+
+        let statuses: Vec<_> = (0..4).map(|_| service.acquire_at(ROUTE, "client", now)).collect();
+        assert!(statuses[..3].iter().all(|status| status.allowed));
+        assert_eq!(statuses[2].remaining, 0);
+        assert_eq!(statuses[2].reset_seconds, 2);
+        assert!(!statuses[3].allowed);
+
+        let status = service.acquire_at(ROUTE, "client", now + Duration::from_millis(500));
+        assert!(status.allowed);
+        assert_eq!(status.remaining, 0);
+
+        let status = service.acquire_at(ROUTE, "client", now + Duration::from_secs(60));
+        assert!(status.allowed);
+        assert_eq!(status.remaining, 2);
+    }
+
+    #[test]
+    fn test_acquire_sets_retry_after_when_throttled() {
+        let service = service(0.2, 1.0);
+        let now = Instant::now();
+
+        assert!(service.acquire_at(ROUTE, "client", now).allowed);
+        let status = service.acquire_at(ROUTE, "client", now);
+        assert!(!status.allowed);
+        assert_eq!(status.retry_after_seconds, 5);
+
+        let status = service.acquire_at(ROUTE, "client", now + Duration::from_millis(2500));
+        assert!(!status.allowed);
+        assert_eq!(status.retry_after_seconds, 3);
+        assert!(service.acquire_at(ROUTE, "other", now).allowed);
+    }
+
+    #[test]
+    fn test_acquire_evicts_full_then_fullest_buckets() {
+        let service = service(1.0, 10.0);
+        let now = Instant::now();
+
+        // One idle client, then active clients with fewer tokens the later they came
+        service.acquire_at(ROUTE, "idle", now - Duration::from_secs(60));
+        for client in 1..MAX_TRACKED_BUCKETS {
+            let times = 1 + client * 8 / MAX_TRACKED_BUCKETS;
+            for _ in 0..times {
+                service.acquire_at(ROUTE, &client.to_string(), now);
+            }
+        }
+        assert_eq!(service.buckets.lock().unwrap().len(), MAX_TRACKED_BUCKETS);
+
+        service.acquire_at(ROUTE, "new", now);
+
+        let buckets = service.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), EVICTION_TARGET_BUCKETS + 1);
+        let key = |client: &str| (DEFAULT_ROUTE.to_string(), client.to_string());
+        assert!(!buckets.contains_key(&key("idle")));
+        // 1249 clients were left with 9 tokens, the fullest; "new" now has 9 as well
+        let fullest = buckets.values().filter(|bucket| bucket.tokens >= 9.0).count();
+        assert_eq!(fullest, 1249 - (MAX_TRACKED_BUCKETS - 1 - EVICTION_TARGET_BUCKETS) + 1);
+        assert!(buckets.contains_key(&key(&(MAX_TRACKED_BUCKETS - 1).to_string())));
+        assert!(buckets.contains_key(&key("new")));
+    }
+
+    #[test]
+    fn test_client_ip_only_trusts_forwarded_for_from_trusted_proxies() {
+        let service = service(1.0, 1.0);
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+
+        assert_eq!(service.client_ip(client, Some("198.51.100.1")), client);
+        assert_eq!(service.client_ip(proxy, None), proxy);
+        assert_eq!(
+            service.client_ip(proxy, Some("198.51.100.1, 203.0.113.7, 10.0.0.2")),
+            client
+        );
+        assert_eq!(service.client_ip(proxy, Some("spoofed, 203.0.113.7")), client);
+        assert_eq!(service.client_ip(proxy, Some("203.0.113.7, spoofed")), proxy);
+    }
+}
@@ -0,0 +1,146 @@
+use std::{collections::VecDeque, fmt, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use domain::entities::stub_history_domain_entity::{
+    StubEntityChangeCursor, StubEntityChangeFilter, StubEntityHistory,
+};
+use futures::{stream, Stream};
+use infrastructure::database::notifications::stub_entity_change_listener::{
+    StubEntityChangeListener, StubEntityChangeNotification,
+};
+use infrastructure::logging::logging_task_local::{current_tenant_id, RequestData, REQUEST_DATA};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::error;
+
+use crate::use_cases::stub_entity_history_use_case::StubEntityHistoryUseCase;
+
+/// History rows read per query while catching up
+const CHANGES_PAGE_SIZE: u64 = 100;
+
+/// Re-reads the history even without a notification, covering the ones lost while the listener reconnects
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct StubEntityChangeFeedService {
+    stub_entity_history_use_case: Arc<StubEntityHistoryUseCase>,
+    change_listener: Arc<StubEntityChangeListener>,
+    heartbeat_interval: Duration,
+}
+
+impl StubEntityChangeFeedService {
+    pub fn new(
+        stub_entity_history_use_case: Arc<StubEntityHistoryUseCase>,
+        change_listener: Arc<StubEntityChangeListener>,
+        heartbeat_interval: Duration,
+    ) -> Self {
+        Self {
+            stub_entity_history_use_case,
+            change_listener,
+            heartbeat_interval,
+        }
+    }
+
+    pub fn heartbeat_interval(&self) -> Duration {
+        self.heartbeat_interval
+    }
+
+    /// Streams the current tenant's changes committed after `last_event_id`, or after now when absent.
+    /// The stream outlives the request scope, so the request data is captured here and restored for every read.
+    pub async fn subscribe(
+        &self,
+        last_event_id: Option<StubEntityChangeCursor>,
+        filter: StubEntityChangeFilter,
+    ) -> Result<impl Stream<Item = StubEntityHistory>> {
+        let request_data = REQUEST_DATA.try_with(|data| data.clone())?;
+        // Subscribe before reading the cursor so no commit can slip in between
+        let receiver = self.change_listener.subscribe();
+        let cursor = match last_event_id {
+            Some(last_event_id) => last_event_id,
+            None => self.stub_entity_history_use_case.current_change_cursor().await?,
+        };
+
+        let feed = ChangeFeed {
+            stub_entity_history_use_case: self.stub_entity_history_use_case.clone(),
+            tenant_id: current_tenant_id(),
+            request_data,
+            receiver,
+            cursor,
+            pending: VecDeque::new(),
+            filter,
+        };
+
+        Ok(stream::unfold(feed, |mut feed| async move {
+            feed.next_change().await.map(|change| (change, feed))
+        }))
+    }
+}
+
+impl fmt::Debug for StubEntityChangeFeedService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StubEntityChangeFeedService")
+            .field("heartbeat_interval", &self.heartbeat_interval)
+            .finish()
+    }
+}
+
+struct ChangeFeed {
+    stub_entity_history_use_case: Arc<StubEntityHistoryUseCase>,
+    tenant_id: String,
+    request_data: RequestData,
+    receiver: broadcast::Receiver<StubEntityChangeNotification>,
+    cursor: StubEntityChangeCursor,
+    pending: VecDeque<StubEntityHistory>,
+    filter: StubEntityChangeFilter,
+}
+
+impl ChangeFeed {
+    /// Ends the feed on read errors; clients reconnect with `Last-Event-ID` and resume
+    async fn next_change(&mut self) -> Option<StubEntityHistory> {
+        loop {
+            while let Some(change) = self.pending.pop_front() {
+                if self.filter.matches(&change) {
+                    return Some(change);
+                }
+            }
+
+            let changes = REQUEST_DATA
+                .scope(
+                    self.request_data.clone(),
+                    self.stub_entity_history_use_case
+                        .changes_since(self.cursor, CHANGES_PAGE_SIZE),
+                )
+                .await;
+            match changes {
+                Ok(changes) if !changes.is_empty() => {
+                    self.cursor = changes.last().map(StubEntityHistory::cursor).unwrap_or(self.cursor);
+                    self.pending.extend(changes);
+                    continue;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    error!(
+                        app.name = %env!("CARGO_PKG_NAME"),
+                        app.version = %env!("CARGO_PKG_VERSION"),
+                        correlation_id = %self.request_data.correlation_id,
+                        error_message = %err,
+                        "Stub entity change feed failed"
+                    );
+                    return None;
+                }
+            }
+
+            if !self.wait_for_change().await {
+                return None;
+            }
+        }
+    }
+
+    async fn wait_for_change(&mut self) -> bool {
+        loop {
+            match tokio::time::timeout(POLL_INTERVAL, self.receiver.recv()).await {
+                Ok(Ok(change)) if change.tenant_id != self.tenant_id => continue,
+                Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) | Err(_) => return true,
+                Ok(Err(RecvError::Closed)) => return false,
+            }
+        }
+    }
+}
@@ -1,13 +1,24 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use domain::entities::stub_domain_entity::StubEntity;
+use domain::{
+    entities::{
+        stub_batch_domain_entity::{BatchItemResult, BatchItemStatus, BatchMode},
+        stub_domain_entity::StubEntity,
+        stub_enrichment_domain_entity::PendingEnrichment,
+        stub_event_domain_entity::StubEntityEvent,
+    },
+    errors::domain_errors::DomainError,
+    ports::repositories::transaction_port::TransactionPort,
+};
 use infrastructure::database::repositories::database_data::{DatabaseConnection, Transaction};
+use infrastructure::logging::logging_task_local::{RequestData, REQUEST_DATA};
+use serde_json::json;
 use tracing::instrument;
 
 use crate::{
-    handlers::dtos::stub_entity_dtos::StubEntityUpdateDto,
-    use_cases::stub_entity_use_case::StubEntityUseCase,
+    handlers::dtos::stub_entity_dtos::{StubEntityPatchDto, StubEntityUpdateDto},
+    use_cases::stub_entity_use_case::{batch_item_failure, StubEntityUseCase},
 };
 
 #[derive(Debug)]
@@ -29,39 +40,221 @@ impl StubEntityUpdateService {
 
     #[instrument(skip(self, id, dto), err)]
     pub async fn update(&self, id: i32, dto: StubEntityUpdateDto) -> Result<Option<StubEntity>> {
+        self.modify(id, |entity| dto.apply_to(entity)).await
+    }
+
+    #[instrument(skip(self, id, dto), err)]
+    pub async fn patch(&self, id: i32, dto: StubEntityPatchDto) -> Result<Option<StubEntity>> {
+        self.modify(id, |entity| dto.apply_to(entity)).await
+    }
+
+    /// Replaces the client value of entities stored in degraded mode, each in its own tenant.
+    /// Stops at the first enrichment failure since the rest of the batch would fail as well.
+    #[instrument(skip(self), err)]
+    pub async fn retry_pending_enrichments(&self, batch_size: u64) -> Result<u64> {
+        let pending = self
+            .stub_entity_use_case
+            .pending_enrichments(batch_size)
+            .await?;
+
+        let mut enriched = 0;
+        for PendingEnrichment { tenant_id, entity_id } in pending {
+            let value = self.stub_entity_use_case.fetch_enrichment().await?;
+            let request_data = RequestData::new(
+                format!("enrichment-retry-{}", entity_id),
+                None,
+                Some(tenant_id),
+            );
+            // An update made since the entity was listed already settled its value
+            let entity = REQUEST_DATA
+                .scope(
+                    request_data,
+                    self.modify(entity_id, |entity| {
+                        if entity.enrichment_pending_since.take().is_some() {
+                            entity.value = value;
+                        }
+                    }),
+                )
+                .await?;
+            if entity.is_some() {
+                enriched += 1;
+            }
+        }
+        Ok(enriched)
+    }
+
+    async fn modify<F>(&self, id: i32, apply: F) -> Result<Option<StubEntity>>
+    where
+        F: FnOnce(&mut StubEntity),
+    {
         let txn = Transaction::begin(&self.database_connection).await?;
 
-        let entity = self.stub_entity_use_case.get(id, Some(&txn)).await?;
+        let entity = self.stub_entity_use_case.get(id, false, Some(txn.as_ref())).await?;
 
         match entity {
             Some(mut entity) => {
-                if dto.name.is_some() {
-                    entity.name = dto.name.unwrap().to_string();
-                }
+                let previous = entity.clone();
+                apply(&mut entity);
+                let result = self.stub_entity_use_case.update(&entity, txn.as_ref()).await;
+                let entity = commit_or_rollback(txn, result).await?;
+                self.publish_updated(&previous, &entity).await?;
+                Ok(Some(entity))
+            }
+            None => {
+                txn.rollback().await?;
+                Ok(None)
+            }
+        }
+    }
 
-                if dto.value.is_some() {
-                    entity.value = dto.value.unwrap().to_domain();
+    /// `BatchMode::AllOrNothing` applies every item in one transaction and rolls it back on the
+    /// first failure; `BatchMode::BestEffort` gives each item its own transaction
+    #[instrument(skip(self, items), err)]
+    pub async fn update_batch(
+        &self,
+        items: Vec<(usize, i32, StubEntityUpdateDto)>,
+        mode: BatchMode,
+    ) -> Result<Vec<BatchItemResult>> {
+        match mode {
+            BatchMode::AllOrNothing => self.update_all_or_nothing(items).await,
+            BatchMode::BestEffort => {
+                let mut results = Vec::with_capacity(items.len());
+                for (index, id, dto) in items {
+                    results.push(match self.update(id, dto).await {
+                        Ok(Some(entity)) => {
+                            BatchItemResult::succeeded(index, BatchItemStatus::Updated, entity)
+                        }
+                        Ok(None) => not_found(index),
+                        Err(err) => batch_item_failure(index, &err),
+                    });
                 }
+                Ok(results)
+            }
+        }
+    }
 
-                if dto.auto_ref.is_some() {
-                    entity.auto_ref = dto.auto_ref;
-                }
+    async fn update_all_or_nothing(
+        &self,
+        items: Vec<(usize, i32, StubEntityUpdateDto)>,
+    ) -> Result<Vec<BatchItemResult>> {
+        let txn = Transaction::begin(&self.database_connection).await?;
 
-                match self.stub_entity_use_case.update(&entity, &txn).await {
-                    Ok(_) => {
-                        txn.commit().await?;
-                        Ok(Some(entity))
-                    }
-                    Err(e) => {
-                        txn.rollback().await?;
-                        Err(e)
+        let mut results = Vec::with_capacity(items.len());
+        let mut events = Vec::with_capacity(items.len());
+        let mut items = items.into_iter();
+        for (index, id, dto) in items.by_ref() {
+            let result = match self.stub_entity_use_case.get(id, false, Some(txn.as_ref())).await {
+                Ok(Some(mut entity)) => {
+                    let previous = entity.clone();
+                    dto.apply_to(&mut entity);
+                    let result = self.stub_entity_use_case.update(&entity, txn.as_ref()).await;
+                    if let Ok(entity) = &result {
+                        events.extend(StubEntityEvent::updated(&previous, entity.clone()));
                     }
+                    result
+                }
+                Ok(None) => {
+                    results.push(not_found(index));
+                    break;
+                }
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(entity) => {
+                    results.push(BatchItemResult::succeeded(index, BatchItemStatus::Updated, entity))
+                }
+                Err(err) if err.is::<DomainError>() => {
+                    results.push(BatchItemResult::from_error(index, &err));
+                    break;
+                }
+                Err(err) => {
+                    txn.rollback().await?;
+                    return Err(err);
                 }
             }
-            None => {
-                txn.rollback().await?;
-                Ok(None)
+        }
+
+        if results.iter().all(BatchItemResult::is_success) {
+            txn.commit().await?;
+            self.stub_entity_use_case.publish(events).await?;
+            return Ok(results);
+        }
+
+        txn.rollback().await?;
+        // Items applied before the failure were undone along with it
+        Ok(results
+            .into_iter()
+            .map(|result| {
+                if result.is_success() {
+                    BatchItemResult::skipped(result.index)
+                } else {
+                    result
+                }
+            })
+            .chain(items.map(|(index, _, _)| BatchItemResult::skipped(index)))
+            .collect())
+    }
+
+    #[instrument(skip(self, id), err)]
+    pub async fn delete(&self, id: i32) -> Result<Option<StubEntity>> {
+        let txn = Transaction::begin(&self.database_connection).await?;
+        let result = self.stub_entity_use_case.delete(id, txn.as_ref()).await;
+        let entity = commit_or_rollback(txn, result).await?;
+        if let Some(entity) = &entity {
+            self.stub_entity_use_case
+                .publish(vec![StubEntityEvent::Deleted(entity.clone())])
+                .await?;
+        }
+        Ok(entity)
+    }
+
+    /// Published as an update clearing `deleted_at`
+    #[instrument(skip(self, id), err)]
+    pub async fn restore(&self, id: i32) -> Result<Option<StubEntity>> {
+        let txn = Transaction::begin(&self.database_connection).await?;
+        let result = async {
+            match self.stub_entity_use_case.get(id, true, Some(txn.as_ref())).await? {
+                Some(previous) => Ok(self
+                    .stub_entity_use_case
+                    .restore(id, txn.as_ref())
+                    .await?
+                    .map(|entity| (previous, entity))),
+                None => Ok(None),
+            }
+        }
+        .await;
+        match commit_or_rollback(txn, result).await? {
+            Some((previous, entity)) => {
+                self.publish_updated(&previous, &entity).await?;
+                Ok(Some(entity))
             }
+            None => Ok(None),
+        }
+    }
+
+    async fn publish_updated(&self, previous: &StubEntity, entity: &StubEntity) -> Result<()> {
+        let events = StubEntityEvent::updated(previous, entity.clone()).into_iter().collect();
+        self.stub_entity_use_case.publish(events).await
+    }
+}
+
+fn not_found(index: usize) -> BatchItemResult {
+    BatchItemResult::failed(
+        index,
+        BatchItemStatus::NotFound,
+        json!({ "message": "Stub entity not found" }),
+    )
+}
+
+pub(crate) async fn commit_or_rollback<T>(txn: Box<dyn TransactionPort>, result: Result<T>) -> Result<T> {
+    match result {
+        Ok(value) => {
+            txn.commit().await?;
+            Ok(value)
+        }
+        Err(e) => {
+            txn.rollback().await?;
+            Err(e)
         }
     }
 }
@@ -0,0 +1,112 @@
+use core::fmt;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use domain::{
+    entities::{
+        api_key_domain_entity::ApiKey,
+        principal_domain_entity::{AuthenticationMethod, Principal},
+    },
+    ports::repositories::api_key_repository_port::ApiKeyRepositoryPort,
+};
+use infrastructure::logging::logging_task_local::current_tenant_id;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+const API_KEY_PREFIX: &str = "sk";
+
+pub struct ApiKeyUseCase {
+    repository: Arc<dyn ApiKeyRepositoryPort>,
+}
+
+impl ApiKeyUseCase {
+    pub fn new(repository: Arc<dyn ApiKeyRepositoryPort>) -> Self {
+        Self { repository }
+    }
+
+    /// Returns the stored key together with the plain-text key, which is only available here.
+    /// The key is bound to the tenant of the request creating it.
+    pub async fn add(
+        &self,
+        name: String,
+        scopes: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(ApiKey, String)> {
+        let prefix = Uuid::new_v4().simple().to_string()[..16].to_string();
+        let secret = Uuid::new_v4().simple().to_string();
+        let key = format!("{}_{}_{}", API_KEY_PREFIX, prefix, secret);
+
+        let api_key = ApiKey {
+            id: None,
+            name,
+            tenant_id: current_tenant_id(),
+            prefix,
+            key_hash: hash_key(&key),
+            scopes,
+            expires_at,
+            revoked_at: None,
+            created_at: Utc::now(),
+        };
+
+        let inserted = self.repository.add(&api_key).await?;
+        Ok((inserted, key))
+    }
+
+    pub async fn list(&self) -> Result<Vec<ApiKey>> {
+        self.repository.get_all().await
+    }
+
+    pub async fn revoke(&self, id: i32) -> Result<Option<ApiKey>> {
+        self.repository.revoke(id).await
+    }
+
+    pub async fn authenticate(&self, key: &str) -> Result<Option<Principal>> {
+        let Some(prefix) = parse_prefix(key) else {
+            return Ok(None);
+        };
+
+        let api_key = match self.repository.get_by_prefix(prefix).await? {
+            Some(api_key) => api_key,
+            None => return Ok(None),
+        };
+
+        let matches: bool = hash_key(key)
+            .as_bytes()
+            .ct_eq(api_key.key_hash.as_bytes())
+            .into();
+        if !matches || !api_key.is_active(Utc::now()) {
+            return Ok(None);
+        }
+
+        Ok(Some(Principal {
+            tenant_id: Some(api_key.tenant_id),
+            ..Principal::new(
+                api_key.name,
+                api_key.scopes.into_iter().collect::<HashSet<String>>(),
+                AuthenticationMethod::ApiKey,
+            )
+        }))
+    }
+}
+
+fn parse_prefix(key: &str) -> Option<&str> {
+    let (kind, rest) = key.split_once('_')?;
+    if kind != API_KEY_PREFIX {
+        return None;
+    }
+    let (prefix, _) = rest.split_once('_')?;
+    Some(prefix)
+}
+
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+impl fmt::Debug for ApiKeyUseCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyUseCase").finish()
+    }
+}
@@ -0,0 +1,58 @@
+use core::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use domain::{
+    entities::{
+        page_domain_entity::Page, stub_domain_entity::StubEntity,
+        stub_history_domain_entity::{StubEntityChangeCursor, StubEntityHistory},
+    },
+    ports::repositories::stub_entity_history_repository_port::StubEntityHistoryRepositoryPort,
+};
+
+pub struct StubEntityHistoryUseCase {
+    repository: Arc<dyn StubEntityHistoryRepositoryPort>,
+}
+
+impl StubEntityHistoryUseCase {
+    pub fn new(repository: Arc<dyn StubEntityHistoryRepositoryPort>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn list(
+        &self,
+        entity_id: i32,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Page<StubEntityHistory>> {
+        self.repository.get_history(entity_id, page, page_size).await
+    }
+
+    pub async fn get_as_of(
+        &self,
+        entity_id: i32,
+        as_of: DateTime<Utc>,
+        include_deleted: bool,
+    ) -> Result<Option<StubEntity>> {
+        self.repository.get_as_of(entity_id, as_of, include_deleted).await
+    }
+
+    pub async fn changes_since(
+        &self,
+        after: StubEntityChangeCursor,
+        limit: u64,
+    ) -> Result<Vec<StubEntityHistory>> {
+        self.repository.get_changes_since(after, limit).await
+    }
+
+    pub async fn current_change_cursor(&self) -> Result<StubEntityChangeCursor> {
+        self.repository.get_current_cursor().await
+    }
+}
+
+impl fmt::Debug for StubEntityHistoryUseCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StubEntityHistoryUseCase").finish()
+    }
+}
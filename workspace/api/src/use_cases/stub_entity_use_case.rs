@@ -1,23 +1,59 @@
 use core::fmt;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
 use domain::{
-    entities::stub_domain_entity::StubEntity,
+    entities::{
+        page_domain_entity::Page,
+        publish_failure_policy_domain_entity::PublishFailurePolicy,
+        stub_batch_domain_entity::{BatchItemResult, BatchItemStatus, BatchMode},
+        stub_domain_entity::{KeyValue, StubEntity, StubEntityFilter},
+        stub_enrichment_domain_entity::{
+            EnrichmentPolicy, KeyValueDraft, PendingEnrichment, StubEntityDraft,
+        },
+        stub_event_domain_entity::StubEntityEvent,
+        stub_search_domain_entity::{StubEntitySearch, StubEntitySearchHit},
+        stub_tree_domain_entity::StubEntityNode,
+    },
+    errors::domain_errors::DomainError,
     ports::{
-        messaging::messaging_service_port::MessagingServicePort,
+        messaging::messaging_service_port::{
+            MessagingServicePort, OutboundMessage, SendError, SendReceipt,
+        },
         repositories::{
             mockserver_http_service_port::MockserverHttpServicePort,
+            outbound_message_repository_port::{
+                OutboundMessageRepositoryPort, QueuedOutboundMessage,
+            },
             stub_entity_repository_port::StubEntityRepositoryPort,
             transaction_port::TransactionPort,
         },
     },
 };
+use futures::{stream, stream::BoxStream, StreamExt};
+use infrastructure::log_with_span;
+use infrastructure::logging::logging_task_local::{RequestData, REQUEST_DATA};
+use opentelemetry::trace::TraceContextExt;
+use serde_json::json;
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Mockserver calls made at once while enriching a batch
+const ENRICHMENT_CONCURRENCY: usize = 8;
+
+/// Caps the exponential backoff between retries of a queued message at 2^10 times the base
+const MAX_RETRY_BACKOFF_EXPONENT: i32 = 10;
 
 pub struct StubEntityUseCase {
     repository: Arc<dyn StubEntityRepositoryPort>,
     mockserver_http_service: Arc<dyn MockserverHttpServicePort>,
     messaging_service: Arc<dyn MessagingServicePort>,
+    outbound_message_repository: Arc<dyn OutboundMessageRepositoryPort>,
+    enrichment_policy: EnrichmentPolicy,
+    enrichment_degraded_mode: bool,
+    publish_failure_policy: PublishFailurePolicy,
 }
 
 impl StubEntityUseCase {
@@ -25,36 +61,256 @@ impl StubEntityUseCase {
         repository: Arc<dyn StubEntityRepositoryPort>,
         mockserver_http_service: Arc<dyn MockserverHttpServicePort>,
         messaging_service: Arc<dyn MessagingServicePort>,
+        outbound_message_repository: Arc<dyn OutboundMessageRepositoryPort>,
+        enrichment_policy: EnrichmentPolicy,
+        enrichment_degraded_mode: bool,
+        publish_failure_policy: PublishFailurePolicy,
     ) -> Self {
         Self {
             repository,
             mockserver_http_service,
             messaging_service,
+            outbound_message_repository,
+            enrichment_policy,
+            enrichment_degraded_mode,
+            publish_failure_policy,
         }
     }
 
-    pub async fn list(&self) -> Result<Vec<StubEntity>> {
-        self.repository.get_all().await
+    pub async fn list(&self, filter: &StubEntityFilter) -> Result<Vec<StubEntity>> {
+        self.repository.get_all(filter).await
     }
 
-    pub async fn add(&self, entity: &mut StubEntity) -> Result<StubEntity> {
-        let key_value = self.mockserver_http_service.execute_call().await?;
-        entity.value = key_value;
-        let entity = self.repository.add(entity).await?;
-        self.messaging_service
-            .send_message(
-                entity.id.unwrap().to_string(),
-                format!("{}${}${}", "created", "stub-entity", entity.id.unwrap()),
-                serde_json::to_string(&entity)?,
-            )
-            .await?;
+    pub async fn search(&self, search: &StubEntitySearch) -> Result<Page<StubEntitySearchHit>> {
+        self.repository.search(search).await
+    }
+
+    pub async fn export(
+        &self,
+        filter: &StubEntityFilter,
+    ) -> Result<BoxStream<'static, Result<StubEntity>>> {
+        self.repository.stream_all(filter).await
+    }
+
+    pub async fn add(
+        &self,
+        draft: StubEntityDraft,
+        policy: Option<EnrichmentPolicy>,
+    ) -> Result<StubEntity> {
+        let entity = self.enrich(draft, policy).await?;
+        let entity = self.repository.add(&entity).await?;
+        self.publish(vec![StubEntityEvent::Created(entity.clone())]).await?;
         Ok(entity)
     }
 
+    /// Inserts an already resolved entity; the caller publishes its `Created` event once `txn` commits
+    pub async fn add_within_transaction(
+        &self,
+        entity: &StubEntity,
+        txn: &dyn TransactionPort,
+    ) -> Result<StubEntity> {
+        self.repository.add_within_transaction(entity, txn).await
+    }
+
+    /// Enriches, inserts (in a single statement) and publishes the given items.
+    /// With `BatchMode::AllOrNothing` nothing is inserted once an item fails.
+    pub async fn add_batch(
+        &self,
+        items: Vec<(usize, StubEntityDraft)>,
+        mode: BatchMode,
+        policy: Option<EnrichmentPolicy>,
+    ) -> Result<Vec<BatchItemResult>> {
+        let enriched: Vec<_> = stream::iter(items)
+            .map(|(index, draft)| async move { (index, self.enrich(draft, policy).await) })
+            .buffered(ENRICHMENT_CONCURRENCY)
+            .collect()
+            .await;
+
+        let auto_refs: Vec<i32> = enriched
+            .iter()
+            .filter_map(|(_, entity)| entity.as_ref().ok().and_then(|entity| entity.auto_ref))
+            .collect();
+        let existing_auto_refs = self.repository.get_existing_ids(&auto_refs).await?;
+
+        let mut results = Vec::with_capacity(enriched.len());
+        let mut accepted = Vec::with_capacity(enriched.len());
+        for (index, entity) in enriched {
+            match entity {
+                Err(err) => results.push(batch_item_failure(index, &err)),
+                Ok(entity)
+                    if entity
+                        .auto_ref
+                        .is_some_and(|auto_ref| !existing_auto_refs.contains(&auto_ref)) =>
+                {
+                    results.push(BatchItemResult::failed(
+                        index,
+                        BatchItemStatus::Invalid,
+                        json!({ "message": "auto_ref does not exist" }),
+                    ))
+                }
+                Ok(entity) => accepted.push((index, entity)),
+            }
+        }
+
+        if mode == BatchMode::AllOrNothing && !results.is_empty() {
+            results.extend(accepted.iter().map(|(index, _)| BatchItemResult::skipped(*index)));
+            return Ok(results);
+        }
+
+        let (indexes, entities): (Vec<usize>, Vec<StubEntity>) = accepted.into_iter().unzip();
+        let inserted_entities = match self.repository.add_many(&entities).await {
+            Ok(inserted_entities) => inserted_entities,
+            Err(err) if err.is::<DomainError>() => {
+                results.extend(indexes.iter().map(|index| batch_item_failure(*index, &err)));
+                return Ok(results);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let messages = inserted_entities
+            .iter()
+            .map(|entity| OutboundMessage::from_event(StubEntityEvent::Created(entity.clone())))
+            .collect::<Result<Vec<_>>>()?;
+        let published = self.send(messages).await?;
+
+        for ((index, entity), published) in indexes.into_iter().zip(inserted_entities).zip(published) {
+            let mut result = BatchItemResult::succeeded(index, BatchItemStatus::Created, entity);
+            result.published = Some(published);
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Resolves the value of a draft according to `policy`, or the configured policy.
+    /// In degraded mode a complete client value is kept when the key-value service fails,
+    /// and the entity is marked for `StubEntityUpdateService::retry_pending_enrichments`.
+    async fn enrich(
+        &self,
+        draft: StubEntityDraft,
+        policy: Option<EnrichmentPolicy>,
+    ) -> Result<StubEntity> {
+        let policy = policy.unwrap_or(self.enrichment_policy);
+        let client_value = draft.value.as_ref();
+        if !policy.needs_enrichment(client_value) {
+            let value = policy.client_value(client_value)?;
+            return Ok(draft.into_entity(value, None));
+        }
+
+        match self.mockserver_http_service.execute_call().await {
+            Ok(enriched) => {
+                let value = policy.apply(client_value, enriched);
+                Ok(draft.into_entity(value, None))
+            }
+            Err(err) => match client_value.and_then(KeyValueDraft::complete) {
+                Some(value) if self.enrichment_degraded_mode => {
+                    log_with_span!(
+                        Level::WARN,
+                        "Enrichment unavailable, keeping client value: {}",
+                        err
+                    );
+                    Ok(draft.into_entity(value, Some(Utc::now())))
+                }
+                _ => Err(err),
+            },
+        }
+    }
+
+    pub async fn fetch_enrichment(&self) -> Result<KeyValue> {
+        self.mockserver_http_service.execute_call().await
+    }
+
+    pub async fn pending_enrichments(&self, batch_size: u64) -> Result<Vec<PendingEnrichment>> {
+        self.repository.get_pending_enrichments(batch_size).await
+    }
+
+    /// Publishes events of committed changes. Failures cannot undo the change anymore and
+    /// are handled according to the publish failure policy.
+    pub async fn publish(&self, events: Vec<StubEntityEvent>) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let messages = events
+            .into_iter()
+            .map(OutboundMessage::from_event)
+            .collect::<Result<Vec<_>>>()?;
+
+        self.send(messages).await.map(|_| ())
+    }
+
+    /// Returns whether each message was published
+    async fn send(&self, messages: Vec<OutboundMessage>) -> Result<Vec<bool>> {
+        send_in_partition_order(
+            self.messaging_service.as_ref(),
+            self.outbound_message_repository.as_ref(),
+            self.publish_failure_policy,
+            messages,
+        )
+        .await
+    }
+
+    /// Sends due queued messages again, each in the tenant it was published in. A message
+    /// stays queued without a next attempt once it fails permanently or `max_attempts` times.
+    /// Once a message fails, later messages of its partition wait for the next run. Messages
+    /// are claimed for `lease`, so other instances do not send them at the same time.
+    pub async fn retry_outbound_messages(
+        &self,
+        batch_size: u64,
+        max_attempts: i32,
+        backoff: Duration,
+        lease: Duration,
+    ) -> Result<u64> {
+        let now = Utc::now();
+        let queued = self
+            .outbound_message_repository
+            .claim_due(now, now + lease, batch_size)
+            .await?;
+
+        let mut sent = 0;
+        let mut held_partitions = HashSet::new();
+        for QueuedOutboundMessage { id, tenant_id, message, attempts } in queued {
+            let partition = (tenant_id.clone(), message.partition_id.clone());
+            if held_partitions.contains(&partition) {
+                continue;
+            }
+            let request_data = RequestData::new(format!("outbound-retry-{}", id), None, Some(tenant_id));
+            let outcome = REQUEST_DATA
+                .scope(request_data, self.messaging_service.send_message(message))
+                .await;
+
+            match outcome {
+                Ok(_) => {
+                    self.outbound_message_repository.delete(id).await?;
+                    sent += 1;
+                }
+                Err(err) => {
+                    let exponent = (attempts - 1).clamp(0, MAX_RETRY_BACKOFF_EXPONENT) as u32;
+                    let next_attempt_at = (err.is_retryable() && attempts + 1 < max_attempts)
+                        .then(|| now + backoff * 2_i32.pow(exponent));
+                    if next_attempt_at.is_none() {
+                        log_with_span!(
+                            Level::ERROR,
+                            "Outbound message {} given up after {} attempts: {}",
+                            id,
+                            attempts + 1,
+                            err
+                        );
+                    }
+                    self.outbound_message_repository
+                        .record_failure(id, &err.to_string(), next_attempt_at)
+                        .await?;
+                    if next_attempt_at.is_some() {
+                        held_partitions.insert(partition);
+                    }
+                }
+            }
+        }
+        Ok(sent)
+    }
+
     pub async fn update(
         &self,
         entity: &StubEntity,
-        txn: &Box<dyn TransactionPort>,
+        txn: &dyn TransactionPort,
     ) -> Result<StubEntity> {
         self.repository.update_within_transaction(entity, txn).await
     }
@@ -62,13 +318,142 @@ impl StubEntityUseCase {
     pub async fn get(
         &self,
         id: i32,
-        txn: Option<&Box<dyn TransactionPort>>,
+        include_deleted: bool,
+        txn: Option<&dyn TransactionPort>,
     ) -> Result<Option<StubEntity>> {
         match txn {
-            Some(txn) => self.repository.get_within_transaction(id, txn).await,
-            None => self.repository.get(id).await,
+            Some(txn) => {
+                self.repository
+                    .get_within_transaction(id, include_deleted, txn)
+                    .await
+            }
+            None => self.repository.get(id, include_deleted).await,
         }
     }
+
+    pub async fn delete(
+        &self,
+        id: i32,
+        txn: &dyn TransactionPort,
+    ) -> Result<Option<StubEntity>> {
+        self.repository.delete_within_transaction(id, txn).await
+    }
+
+    pub async fn restore(
+        &self,
+        id: i32,
+        txn: &dyn TransactionPort,
+    ) -> Result<Option<StubEntity>> {
+        self.repository.restore_within_transaction(id, txn).await
+    }
+
+    pub async fn children(&self, id: i32) -> Result<Vec<StubEntityNode>> {
+        self.repository.get_children(id).await
+    }
+
+    pub async fn descendants(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>> {
+        self.repository.get_descendants(id, max_depth).await
+    }
+
+    pub async fn ancestors(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>> {
+        self.repository.get_ancestors(id, max_depth).await
+    }
+
+    pub async fn purge_deleted(
+        &self,
+        deleted_before: DateTime<Utc>,
+        batch_size: u64,
+    ) -> Result<u64> {
+        self.repository.purge_deleted(deleted_before, batch_size).await
+    }
+}
+
+/// Sends messages in order within each partition. A failure queued for retry holds back the
+/// later messages of its partition, which are queued unsent behind it, as are the messages of
+/// partitions that already have queued messages.
+async fn send_in_partition_order(
+    messaging_service: &dyn MessagingServicePort,
+    outbound_message_repository: &dyn OutboundMessageRepositoryPort,
+    policy: PublishFailurePolicy,
+    messages: Vec<OutboundMessage>,
+) -> Result<Vec<bool>> {
+    let partition_ids: Vec<_> = messages.iter().map(|message| message.partition_id.clone()).collect();
+    let mut held_partitions = outbound_message_repository
+        .get_queued_partition_ids(&partition_ids)
+        .await?;
+
+    // Each batch holds at most one message per partition, so a failure is known before
+    // the next message of its partition is sent
+    let mut outcomes: Vec<Option<Result<SendReceipt, SendError>>> = vec![None; messages.len()];
+    let mut unsent: Vec<usize> = (0..messages.len()).collect();
+    loop {
+        let mut batch_partitions = HashSet::new();
+        let (batch, later): (Vec<usize>, Vec<usize>) = unsent
+            .into_iter()
+            .filter(|index| !held_partitions.contains(&messages[*index].partition_id))
+            .partition(|index| batch_partitions.insert(&messages[*index].partition_id));
+        if batch.is_empty() {
+            break;
+        }
+
+        let batch_messages = batch.iter().map(|index| messages[*index].clone()).collect();
+        let batch_outcomes = messaging_service.send_message_batch(batch_messages).await;
+        for (index, outcome) in batch.into_iter().zip(batch_outcomes) {
+            if matches!(&outcome, Err(err) if policy == PublishFailurePolicy::Retry && err.is_retryable()) {
+                held_partitions.insert(messages[index].partition_id.clone());
+            }
+            outcomes[index] = Some(outcome);
+        }
+        unsent = later;
+    }
+
+    let mut published = Vec::with_capacity(messages.len());
+    let mut queued = Vec::new();
+    let mut first_error = None;
+    for (message, outcome) in messages.into_iter().zip(outcomes) {
+        published.push(matches!(outcome, Some(Ok(_))));
+        match outcome {
+            Some(Ok(_)) => {}
+            Some(Err(err)) => {
+                log_with_span!(
+                    Level::ERROR,
+                    "Stub entity {} event {} not published: {}",
+                    message.partition_id,
+                    message.deduplication_id,
+                    err
+                );
+                match policy {
+                    // Permanent failures would fail again, so they are only logged
+                    PublishFailurePolicy::Retry if err.is_retryable() => queued.push((message, Some(err))),
+                    PublishFailurePolicy::FailRequest if first_error.is_none() => first_error = Some(err),
+                    _ => {}
+                }
+            }
+            None => {
+                log_with_span!(
+                    Level::WARN,
+                    "Stub entity {} event {} queued behind earlier events",
+                    message.partition_id,
+                    message.deduplication_id
+                );
+                queued.push((message, None));
+            }
+        }
+    }
+
+    outbound_message_repository.enqueue(&queued).await?;
+    match first_error {
+        Some(err) => Err(err.into()),
+        None => Ok(published),
+    }
+}
+
+/// Unexpected errors are logged here since the batch response only carries a generic message
+pub(crate) fn batch_item_failure(index: usize, err: &anyhow::Error) -> BatchItemResult {
+    if !err.is::<DomainError>() {
+        log_with_span!(Level::ERROR, "Batch item {} failed: {:?}", index, err);
+    }
+    BatchItemResult::from_error(index, err)
 }
 
 impl fmt::Debug for StubEntityUseCase {
@@ -76,3 +461,184 @@ impl fmt::Debug for StubEntityUseCase {
         f.debug_struct("StubEntityUseCase").finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// Fails the messages whose deduplication id has an error and records every batch
+    #[derive(Default)]
+    struct FakeMessagingService {
+        errors: HashMap<String, SendError>,
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl MessagingServicePort for FakeMessagingService {
+        async fn send_message(&self, message: OutboundMessage) -> Result<SendReceipt, SendError> {
+            self.send_message_batch(vec![message]).await.remove(0)
+        }
+
+        async fn send_message_batch(
+            &self,
+            messages: Vec<OutboundMessage>,
+        ) -> Vec<Result<SendReceipt, SendError>> {
+            self.batches.lock().unwrap().push(
+                messages.iter().map(|message| message.deduplication_id.clone()).collect(),
+            );
+            messages
+                .into_iter()
+                .map(|message| match self.errors.get(&message.deduplication_id) {
+                    Some(err) => Err(err.clone()),
+                    None => Ok(SendReceipt {
+                        message_id: message.deduplication_id,
+                        sequence_number: None,
+                    }),
+                })
+                .collect()
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeOutboundMessageRepository {
+        queued_partition_ids: HashSet<String>,
+        enqueued: Mutex<Vec<(String, Option<SendError>)>>,
+    }
+
+    #[async_trait]
+    impl OutboundMessageRepositoryPort for FakeOutboundMessageRepository {
+        async fn enqueue(&self, messages: &[(OutboundMessage, Option<SendError>)]) -> Result<()> {
+            self.enqueued.lock().unwrap().extend(
+                messages
+                    .iter()
+                    .map(|(message, err)| (message.deduplication_id.clone(), err.clone())),
+            );
+            Ok(())
+        }
+
+        async fn get_queued_partition_ids(&self, partition_ids: &[String]) -> Result<HashSet<String>> {
+            Ok(partition_ids
+                .iter()
+                .filter(|partition_id| self.queued_partition_ids.contains(*partition_id))
+                .cloned()
+                .collect())
+        }
+
+        async fn claim_due(
+            &self,
+            _: DateTime<Utc>,
+            _: DateTime<Utc>,
+            _: u64,
+        ) -> Result<Vec<QueuedOutboundMessage>> {
+            Ok(Vec::new())
+        }
+
+        async fn delete(&self, _: i64) -> Result<()> {
+            Ok(())
+        }
+
+        async fn record_failure(&self, _: i64, _: &str, _: Option<DateTime<Utc>>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn message(partition_id: &str, action: &str) -> OutboundMessage {
+        OutboundMessage {
+            partition_id: partition_id.to_string(),
+            deduplication_id: format!("{}-{}", partition_id, action),
+            body: "{}".to_string(),
+        }
+    }
+
+    /// Entity 1 is throttled, entity 2 rejected, and entity 3 already has queued messages
+    async fn send(
+        policy: PublishFailurePolicy,
+    ) -> (Result<Vec<bool>>, Vec<Vec<String>>, Vec<(String, Option<SendError>)>) {
+        let throttled = SendError::Throttled("slow down".to_string());
+        let rejected = SendError::Permanent("too large".to_string());
+        let messaging_service = FakeMessagingService {
+            errors: HashMap::from([
+                ("1-created".to_string(), throttled),
+                ("2-created".to_string(), rejected),
+            ]),
+            ..Default::default()
+        };
+        let outbound_message_repository = FakeOutboundMessageRepository {
+            queued_partition_ids: HashSet::from(["3".to_string()]),
+            ..Default::default()
+        };
+        let messages = vec![
+            message("1", "created"),
+            message("2", "created"),
+            message("1", "updated"),
+            message("2", "updated"),
+            message("3", "updated"),
+        ];
+
+        let published = send_in_partition_order(
+            &messaging_service,
+            &outbound_message_repository,
+            policy,
+            messages,
+        )
+        .await;
+        (
+            published,
+            messaging_service.batches.into_inner().unwrap(),
+            outbound_message_repository.enqueued.into_inner().unwrap(),
+        )
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_retry_queues_later_events_behind_a_queued_failure() {
+        let (published, batches, enqueued) = send(PublishFailurePolicy::Retry).await;
+
+        assert_eq!(published.unwrap(), [false, false, false, true, false]);
+        assert_eq!(batches, [ids(&["1-created", "2-created"]), ids(&["2-updated"])]);
+        assert_eq!(
+            enqueued,
+            [
+                ("1-created".to_string(), Some(SendError::Throttled("slow down".to_string()))),
+                ("1-updated".to_string(), None),
+                ("3-updated".to_string(), None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fail_request_returns_the_first_failure() {
+        let (published, batches, enqueued) = send(PublishFailurePolicy::FailRequest).await;
+
+        let err = published.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SendError>(),
+            Some(&SendError::Throttled("slow down".to_string()))
+        );
+        assert_eq!(
+            batches,
+            [ids(&["1-created", "2-created"]), ids(&["1-updated", "2-updated"])]
+        );
+        assert_eq!(enqueued, [("3-updated".to_string(), None)]);
+    }
+
+    #[tokio::test]
+    async fn test_log_only_queues_events_behind_queued_messages() {
+        let (published, batches, enqueued) = send(PublishFailurePolicy::Log).await;
+
+        assert_eq!(published.unwrap(), [false, false, true, true, false]);
+        assert_eq!(
+            batches,
+            [ids(&["1-created", "2-created"]), ids(&["1-updated", "2-updated"])]
+        );
+        assert_eq!(enqueued, [("3-updated".to_string(), None)]);
+    }
+}
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use aws_sdk_sqs::types::{DeleteMessageBatchRequestEntry, Message, MessageSystemAttributeName};
+use aws_sdk_sqs::Client;
+use infrastructure::messaging::{
+    aws_sqs_messaging_service::TENANT_ID_MESSAGE_ATTRIBUTE,
+    handlers::handler_registry::{ConsumedMessage, HandlerRegistry},
+};
+use tokio::sync::{watch, Semaphore};
+use tokio::task::{JoinError, JoinSet};
+use tokio::time::sleep;
+use tracing::{error, info, info_span, warn, Instrument};
+
+use crate::consumer_metrics::{
+    record_oldest_message_age, MESSAGES_FAILED, MESSAGES_PROCESSED, MESSAGES_RECEIVED,
+};
+use crate::health_server::ConsumerHealth;
+use crate::sqs::{group_id, message_id, release, SQS_BATCH_SIZE};
+
+/// Long polling returns as soon as messages arrive, so an idle queue needs no extra sleep
+const RECEIVE_WAIT_TIME_SECONDS: i32 = 20;
+/// Pause after a failed receive so an unreachable queue is not polled in a tight loop
+const RECEIVE_ERROR_BACKOFF: Duration = Duration::from_secs(5);
+/// Label of messages whose body is not a CloudEvent
+const UNKNOWN_EVENT_TYPE: &str = "unknown";
+
+#[derive(Clone)]
+struct Worker {
+    client: Client,
+    queue_url: Arc<str>,
+    registry: Arc<HandlerRegistry>,
+    stopping: watch::Receiver<bool>,
+}
+
+/// Processes up to `concurrency` message groups at a time, the messages of each group in
+/// order. FIFO queues hold back a group while one of its messages is in flight, so groups
+/// received in different batches never overlap. Once `stopping` is set the consumer stops
+/// polling, finishes the messages being handled and releases the ones not started yet.
+pub async fn run(
+    client: Client,
+    queue_url: &str,
+    registry: HandlerRegistry,
+    concurrency: usize,
+    mut stopping: watch::Receiver<bool>,
+    health: ConsumerHealth,
+) -> Result<()> {
+    let worker = Worker {
+        client,
+        queue_url: Arc::from(queue_url),
+        registry: Arc::new(registry),
+        stopping: stopping.clone(),
+    };
+    let permits = Arc::new(Semaphore::new(concurrency));
+    let mut groups = JoinSet::new();
+
+    while !*stopping.borrow() {
+        // Backpressure: only poll for as many messages as there are idle workers
+        tokio::select! {
+            permit = permits.acquire() => drop(permit?),
+            _ = stopping.changed() => break,
+        }
+        while let Some(result) = groups.try_join_next() {
+            log_group_result(result);
+        }
+        let capacity = permits.available_permits().min(SQS_BATCH_SIZE);
+
+        // Not cancelled on shutdown: messages it returns are released below instead of
+        // staying invisible until their visibility timeout
+        let receive_result = worker.client.receive_message()
+            .queue_url(queue_url)
+            .max_number_of_messages(capacity as i32)
+            .message_system_attribute_names(MessageSystemAttributeName::MessageGroupId)
+            .message_system_attribute_names(MessageSystemAttributeName::SentTimestamp)
+            .message_attribute_names(TENANT_ID_MESSAGE_ATTRIBUTE)
+            .wait_time_seconds(RECEIVE_WAIT_TIME_SECONDS)
+            .send().await;
+
+        match receive_result {
+            Ok(output) => {
+                health.set_receiving(true);
+                let messages = output.messages.unwrap_or_default();
+                record_oldest_message_age(&messages);
+                if *stopping.borrow() {
+                    release(&worker.client, queue_url, &messages.iter().collect::<Vec<_>>()).await?;
+                    break;
+                }
+
+                for group in group_messages(messages) {
+                    let permit = permits.clone().acquire_owned().await?;
+                    let worker = worker.clone();
+                    groups.spawn(async move {
+                        worker.process_group(group).await;
+                        drop(permit);
+                    });
+                }
+            }
+            Err(err) => {
+                health.set_receiving(false);
+                error!(error = ?err, "Error receiving messages");
+                tokio::select! {
+                    _ = sleep(RECEIVE_ERROR_BACKOFF) => {}
+                    _ = stopping.changed() => {}
+                }
+            }
+        }
+    }
+
+    info!(in_flight = groups.len(), "Stopped polling, waiting for message groups in flight");
+    while let Some(result) = groups.join_next().await {
+        log_group_result(result);
+    }
+    Ok(())
+}
+
+fn log_group_result(result: Result<(), JoinError>) {
+    if let Err(err) = result {
+        error!(error = ?err, "Message group task failed");
+    }
+}
+
+impl Worker {
+    /// A failed message is left on the queue together with the rest of its group, so the
+    /// group is redelivered in order once its visibility timeout expires
+    async fn process_group(&self, group: Vec<Message>) {
+        let mut processed = Vec::with_capacity(group.len());
+        let mut unstarted = Vec::new();
+
+        let mut messages = group.into_iter();
+        while let Some(message) = messages.next() {
+            if *self.stopping.borrow() {
+                unstarted.push(message);
+                unstarted.extend(messages);
+                break;
+            }
+
+            match process(&self.registry, &message).await {
+                Ok(()) => processed.push(message),
+                Err(err) => {
+                    warn!(message_id = message_id(&message), error = ?err, "Error processing message");
+                    break;
+                }
+            }
+        }
+
+        if let Err(err) = self.acknowledge(&processed).await {
+            error!(error = ?err, "Error deleting processed messages");
+        }
+        if let Err(err) = release(&self.client, &self.queue_url, &unstarted.iter().collect::<Vec<_>>()).await {
+            error!(error = ?err, "Error releasing unprocessed messages");
+        }
+    }
+
+    async fn acknowledge(&self, messages: &[Message]) -> Result<()> {
+        for chunk in messages.chunks(SQS_BATCH_SIZE) {
+            let entries = chunk
+                .iter()
+                .enumerate()
+                .filter_map(|(index, message)| {
+                    message.receipt_handle().map(|receipt_handle| {
+                        DeleteMessageBatchRequestEntry::builder()
+                            .id(index.to_string())
+                            .receipt_handle(receipt_handle)
+                            .build()
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if entries.is_empty() {
+                continue;
+            }
+
+            let output = self.client
+                .delete_message_batch()
+                .queue_url(self.queue_url.as_ref())
+                .set_entries(Some(entries))
+                .send()
+                .await?;
+            for failure in output.failed() {
+                let message = &chunk[failure.id().parse::<usize>()?];
+                error!(
+                    message_id = message_id(message),
+                    code = failure.code(),
+                    reason = failure.message().unwrap_or_default(),
+                    "Processed message could not be deleted"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs the registered handlers in the tenant the message was published for. Messages
+/// without a handler are acknowledged; failed ones stay on the queue for redelivery and,
+/// once the redrive policy gives up, end up in the dead-letter queue.
+async fn process(registry: &HandlerRegistry, message: &Message) -> Result<()> {
+    let tenant_id = message
+        .message_attributes()
+        .and_then(|attributes| attributes.get(TENANT_ID_MESSAGE_ATTRIBUTE))
+        .and_then(|attribute| attribute.string_value())
+        .map(str::to_string);
+    let consumed = message
+        .body()
+        .context("message has no body")
+        .and_then(|body| ConsumedMessage::parse(body, tenant_id));
+    let event_type = consumed
+        .as_ref()
+        .map_or(UNKNOWN_EVENT_TYPE, |consumed| consumed.event_type.as_str())
+        .to_string();
+    metrics::counter!(MESSAGES_RECEIVED, "event_type" => event_type.clone()).increment(1);
+
+    let result = match consumed {
+        Ok(consumed) => dispatch(registry, &consumed)
+            .instrument(info_span!("process_message", message_id = %consumed.id, event_type = %event_type))
+            .await,
+        Err(err) => Err(err),
+    };
+
+    let outcome = if result.is_ok() { MESSAGES_PROCESSED } else { MESSAGES_FAILED };
+    metrics::counter!(outcome, "event_type" => event_type).increment(1);
+    result
+}
+
+async fn dispatch(registry: &HandlerRegistry, consumed: &ConsumedMessage) -> Result<()> {
+    let handled = registry.dispatch(consumed).await?;
+    if handled == 0 {
+        info!("No handler for the event type");
+    }
+    Ok(())
+}
+
+/// Splits a batch by message group, keeping the order of each group
+fn group_messages(messages: Vec<Message>) -> Vec<Vec<Message>> {
+    let mut groups: Vec<(String, Vec<Message>)> = Vec::new();
+    for message in messages {
+        let id = group_id(&message).to_string();
+        match groups.iter_mut().find(|(group_id, _)| *group_id == id) {
+            Some((_, group)) => group.push(message),
+            None => groups.push((id, vec![message])),
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use aws_sdk_sqs::types::{Message, MessageSystemAttributeName, QueueAttributeName};
+use aws_sdk_sqs::Client;
+use infrastructure::env_var::env_var_util::get_vec_env_var;
+use infrastructure::messaging::handlers::handler_registry::HANDLER_DURATION;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use tracing::warn;
+
+use crate::sqs::system_attribute;
+
+pub const MESSAGES_RECEIVED: &str = "sqs_consumer_messages_received_total";
+pub const MESSAGES_PROCESSED: &str = "sqs_consumer_messages_processed_total";
+pub const MESSAGES_FAILED: &str = "sqs_consumer_messages_failed_total";
+/// Age of the oldest message of the last poll; 0 when the poll came back empty
+const OLDEST_MESSAGE_AGE: &str = "sqs_consumer_oldest_message_age_seconds";
+
+const QUEUE_ATTRIBUTE_GAUGES: [(QueueAttributeName, &str); 3] = [
+    (QueueAttributeName::ApproximateNumberOfMessages, "sqs_queue_approximate_messages"),
+    (
+        QueueAttributeName::ApproximateNumberOfMessagesNotVisible,
+        "sqs_queue_approximate_messages_not_visible",
+    ),
+    (
+        QueueAttributeName::ApproximateNumberOfMessagesDelayed,
+        "sqs_queue_approximate_messages_delayed",
+    ),
+];
+
+pub fn setup_metrics_recorder() -> PrometheusHandle {
+    let exponential_seconds = get_vec_env_var(
+        "CONSUMER_HANDLER_METRICS_EXPONENTIAL_SECONDS",
+        vec![0.01, 0.05, 0.1, 0.5, 1.0],
+    )
+    .unwrap();
+
+    PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full(HANDLER_DURATION.to_string()),
+            &exponential_seconds,
+        )
+        .unwrap()
+        .install_recorder()
+        .unwrap()
+}
+
+/// Messages must have been received with their `SentTimestamp` system attribute
+pub fn record_oldest_message_age(messages: &[Message]) {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let oldest_age = messages
+        .iter()
+        .filter_map(|message| system_attribute(message, MessageSystemAttributeName::SentTimestamp))
+        .filter_map(|sent_timestamp| sent_timestamp.parse::<u64>().ok())
+        .map(|sent_millis| now.saturating_sub(Duration::from_millis(sent_millis)))
+        .max()
+        .unwrap_or_default();
+
+    metrics::gauge!(OLDEST_MESSAGE_AGE).set(oldest_age.as_secs_f64());
+}
+
+/// Refreshes the approximate queue depth gauges from the queue attributes every `interval`
+pub fn spawn_queue_depth_poller(client: Client, queue_url: String, interval: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(err) = record_queue_depth(&client, &queue_url).await {
+                warn!(error = ?err, "Failed to read the queue attributes");
+            }
+        }
+    });
+}
+
+async fn record_queue_depth(client: &Client, queue_url: &str) -> Result<()> {
+    let output = QUEUE_ATTRIBUTE_GAUGES
+        .iter()
+        .fold(client.get_queue_attributes().queue_url(queue_url), |request, (name, _)| {
+            request.attribute_names(name.clone())
+        })
+        .send()
+        .await?;
+
+    let Some(attributes) = output.attributes() else {
+        return Ok(());
+    };
+    for (name, gauge) in QUEUE_ATTRIBUTE_GAUGES {
+        if let Some(value) = attributes.get(&name) {
+            metrics::gauge!(gauge).set(value.parse::<f64>()?);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,406 @@
+use std::collections::{hash_map::Entry, HashMap};
+use std::io::{self, BufRead, Write};
+
+use anyhow::{bail, Result};
+use aws_sdk_sqs::types::{
+    DeleteMessageBatchRequestEntry, Message, MessageSystemAttributeName, QueueAttributeName,
+    SendMessageBatchRequestEntry,
+};
+use aws_sdk_sqs::Client;
+use clap::Subcommand;
+
+use crate::sqs::{group_id, message_id, release, system_attribute, SQS_BATCH_SIZE};
+
+/// Messages are hidden from consumers while being inspected, and released afterwards
+const SCAN_VISIBILITY_TIMEOUT_SECONDS: i32 = 60;
+
+#[derive(Subcommand)]
+pub enum DlqCommand {
+    /// Lists messages with their attributes and receive counts
+    List {
+        /// Also prints the decoded bodies
+        #[arg(long)]
+        bodies: bool,
+
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+    /// Prints the decoded bodies of the given messages
+    Show {
+        #[arg(required = true)]
+        message_ids: Vec<String>,
+    },
+    /// Sends messages back to the source queue in their original group, then deletes them
+    Redrive {
+        #[arg(required_unless_present = "all")]
+        message_ids: Vec<String>,
+
+        /// Redrives every message instead of the given ones
+        #[arg(long, conflicts_with = "message_ids")]
+        all: bool,
+
+        /// Prints what would be redriven without sending or deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Deletes every message of the dead-letter queue
+    Purge {
+        /// Skips the confirmation prompt
+        #[arg(long)]
+        yes: bool,
+
+        /// Prints how many messages would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub async fn run(client: &Client, dlq_url: &str, queue_url: &str, command: DlqCommand) -> Result<()> {
+    match command {
+        DlqCommand::List { bodies, limit } => {
+            let messages = receive_all(client, dlq_url, limit).await?;
+            let unreceived = approximate_message_count(client, dlq_url).await?;
+            for message in &messages {
+                print_message(message);
+                if bodies {
+                    println!("{}", decode_body(message));
+                }
+            }
+            println!("{} message(s)", messages.len());
+            report_unreceived(unreceived, "listed");
+            release(client, dlq_url, &messages.iter().collect::<Vec<_>>()).await
+        }
+        DlqCommand::Show { message_ids } => {
+            let messages = receive_all(client, dlq_url, usize::MAX).await?;
+            let unreceived = approximate_message_count(client, dlq_url).await?;
+            let (selected, _) = select(&messages, &message_ids);
+            for message in selected {
+                print_message(message);
+                println!("{}", decode_body(message));
+            }
+            report_missing(&messages, &message_ids);
+            report_unreceived(unreceived, "searched");
+            release(client, dlq_url, &messages.iter().collect::<Vec<_>>()).await
+        }
+        DlqCommand::Redrive { message_ids, all, dry_run } => {
+            let messages = receive_all(client, dlq_url, usize::MAX).await?;
+            let unreceived = approximate_message_count(client, dlq_url).await?;
+            let (selected, others) = if all {
+                (messages.iter().collect(), Vec::new())
+            } else {
+                select(&messages, &message_ids)
+            };
+            if !all {
+                report_missing(&messages, &message_ids);
+            }
+
+            if dry_run {
+                for message in &selected {
+                    println!(
+                        "Would redrive {} to {} in group {}",
+                        message_id(message),
+                        queue_url,
+                        group_id(message)
+                    );
+                }
+                report_unreceived(unreceived, "redriven");
+                return release(client, dlq_url, &messages.iter().collect::<Vec<_>>()).await;
+            }
+
+            release(client, dlq_url, &others).await?;
+            let redriven = redrive(client, dlq_url, queue_url, &selected).await?;
+            println!("Redrove {} of {} message(s)", redriven, selected.len());
+            report_unreceived(unreceived, "redriven");
+            Ok(())
+        }
+        DlqCommand::Purge { yes, dry_run } => {
+            let count = approximate_message_count(client, dlq_url).await?;
+            if dry_run {
+                println!("Would purge about {} message(s) from {}", count, dlq_url);
+                return Ok(());
+            }
+            if !yes && !confirm(&format!("Purge about {} message(s) from {}?", count, dlq_url))? {
+                println!("Purge cancelled");
+                return Ok(());
+            }
+            client.purge_queue().queue_url(dlq_url).send().await?;
+            println!("Purged {}", dlq_url);
+            Ok(())
+        }
+    }
+}
+
+/// Receives until the queue looks empty or `limit` is reached. Every received message stays
+/// invisible until released or until a long scan outlasts its visibility timeout.
+async fn receive_all(client: &Client, dlq_url: &str, limit: usize) -> Result<Vec<Message>> {
+    let mut messages = Vec::new();
+    let mut positions = HashMap::new();
+
+    while messages.len() < limit {
+        let output = client
+            .receive_message()
+            .queue_url(dlq_url)
+            .max_number_of_messages(SQS_BATCH_SIZE as i32)
+            .visibility_timeout(SCAN_VISIBILITY_TIMEOUT_SECONDS)
+            .wait_time_seconds(1)
+            .message_system_attribute_names(MessageSystemAttributeName::All)
+            .message_attribute_names("All")
+            .send()
+            .await?;
+
+        let received = output.messages.unwrap_or_default();
+        if received.is_empty() {
+            break;
+        }
+        merge_received(&mut messages, &mut positions, received);
+    }
+
+    Ok(messages)
+}
+
+/// `unreceived` is the queue's message count taken before releasing: received messages are
+/// invisible until then, so it counts those the scan missed, e.g. because it stopped early
+fn report_unreceived(unreceived: u64, action: &str) {
+    if unreceived > 0 {
+        eprintln!(
+            "About {} more message(s) were not received by this scan and not {}; run the command again",
+            unreceived, action
+        );
+    }
+}
+
+/// A message received again replaces the earlier copy, whose receipt handle no longer works
+fn merge_received(messages: &mut Vec<Message>, positions: &mut HashMap<String, usize>, received: Vec<Message>) {
+    for message in received {
+        match positions.entry(message_id(&message).to_string()) {
+            Entry::Occupied(entry) => messages[*entry.get()] = message,
+            Entry::Vacant(entry) => {
+                entry.insert(messages.len());
+                messages.push(message);
+            }
+        }
+    }
+}
+
+/// Messages are deleted from the dead-letter queue only once the source queue accepted them
+async fn redrive(client: &Client, dlq_url: &str, queue_url: &str, messages: &[&Message]) -> Result<usize> {
+    let mut redriven = 0;
+
+    for chunk in messages.chunks(SQS_BATCH_SIZE) {
+        let entries = chunk
+            .iter()
+            .enumerate()
+            .map(|(index, message)| redrive_entry(index, message))
+            .collect::<Result<Vec<_>>>()?;
+        let output = client
+            .send_message_batch()
+            .queue_url(queue_url)
+            .set_entries(Some(entries))
+            .send()
+            .await?;
+
+        for failure in output.failed() {
+            let message = &chunk[failure.id().parse::<usize>()?];
+            eprintln!(
+                "Failed to redrive {}: {} {}",
+                message_id(message),
+                failure.code(),
+                failure.message().unwrap_or_default()
+            );
+        }
+
+        let sent = output
+            .successful()
+            .iter()
+            .map(|entry| entry.id().parse::<usize>().map(|index| chunk[index]))
+            .collect::<Result<Vec<_>, _>>()?;
+        if sent.is_empty() {
+            continue;
+        }
+
+        let entries = sent
+            .iter()
+            .enumerate()
+            .map(|(index, message)| {
+                DeleteMessageBatchRequestEntry::builder()
+                    .id(index.to_string())
+                    .receipt_handle(message.receipt_handle().unwrap_or_default())
+                    .build()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let output = client
+            .delete_message_batch()
+            .queue_url(dlq_url)
+            .set_entries(Some(entries))
+            .send()
+            .await?;
+        for failure in output.failed() {
+            let message = sent[failure.id().parse::<usize>()?];
+            eprintln!(
+                "Redrove {} but failed to delete it: {} {}",
+                message_id(message),
+                failure.code(),
+                failure.message().unwrap_or_default()
+            );
+        }
+        redriven += sent.len();
+    }
+
+    Ok(redriven)
+}
+
+/// The deduplication id is derived from the dead-letter message so that redriving the same
+/// message twice within the deduplication interval delivers it once
+fn redrive_entry(index: usize, message: &Message) -> Result<SendMessageBatchRequestEntry> {
+    let mut entry = SendMessageBatchRequestEntry::builder()
+        .id(index.to_string())
+        .message_body(message.body().unwrap_or_default())
+        .message_group_id(group_id(message))
+        .message_deduplication_id(format!("redrive-{}", message_id(message)));
+    if let Some(attributes) = message.message_attributes() {
+        for (name, value) in attributes {
+            entry = entry.message_attributes(name, value.clone());
+        }
+    }
+    Ok(entry.build()?)
+}
+
+fn select<'a>(messages: &'a [Message], message_ids: &[String]) -> (Vec<&'a Message>, Vec<&'a Message>) {
+    messages
+        .iter()
+        .partition(|message| message_ids.iter().any(|id| id == message_id(message)))
+}
+
+fn report_missing(messages: &[Message], message_ids: &[String]) {
+    for id in message_ids {
+        if !messages.iter().any(|message| message_id(message) == id) {
+            eprintln!("Message {} not found", id);
+        }
+    }
+}
+
+fn print_message(message: &Message) {
+    println!(
+        "{} receives={} group={} sent_timestamp={}",
+        message_id(message),
+        system_attribute(message, MessageSystemAttributeName::ApproximateReceiveCount).unwrap_or("-"),
+        group_id(message),
+        system_attribute(message, MessageSystemAttributeName::SentTimestamp).unwrap_or("-"),
+    );
+    if let Some(attributes) = message.message_attributes() {
+        let mut attributes: Vec<_> = attributes.iter().collect();
+        attributes.sort_by_key(|(name, _)| name.as_str());
+        for (name, value) in attributes {
+            println!("  {}={}", name, value.string_value().unwrap_or("<binary>"));
+        }
+    }
+}
+
+/// Bodies are JSON documents; anything else is printed as is
+fn decode_body(message: &Message) -> String {
+    let body = message.body().unwrap_or_default();
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(value) => serde_json::to_string_pretty(&value).unwrap_or_else(|_| body.to_string()),
+        Err(_) => body.to_string(),
+    }
+}
+
+async fn approximate_message_count(client: &Client, dlq_url: &str) -> Result<u64> {
+    let output = client
+        .get_queue_attributes()
+        .queue_url(dlq_url)
+        .attribute_names(QueueAttributeName::ApproximateNumberOfMessages)
+        .send()
+        .await?;
+    match output
+        .attributes()
+        .and_then(|attributes| attributes.get(&QueueAttributeName::ApproximateNumberOfMessages))
+    {
+        Some(count) => Ok(count.parse()?),
+        None => bail!("{} did not report its message count", dlq_url),
+    }
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} Type 'purge' to confirm: ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim() == "purge")
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_sqs::types::MessageAttributeValue;
+
+    use super::*;
+
+    fn message(id: &str, receipt_handle: &str) -> Message {
+        Message::builder()
+            .message_id(id)
+            .receipt_handle(receipt_handle)
+            .body(r#"{"id":1}"#)
+            .build()
+    }
+
+    fn ids<'a>(messages: &[&'a Message]) -> Vec<&'a str> {
+        messages.iter().map(|message| message_id(message)).collect()
+    }
+
+    #[test]
+    fn test_merge_received_keeps_latest_receipt_handle() {
+        let mut messages = Vec::new();
+        let mut positions = HashMap::new();
+
+        merge_received(&mut messages, &mut positions, vec![message("a", "a-1"), message("b", "b-1")]);
+        merge_received(&mut messages, &mut positions, vec![message("a", "a-2")]);
+
+        let receipt_handles: Vec<_> = messages.iter().map(|message| message.receipt_handle()).collect();
+        assert_eq!(receipt_handles, vec![Some("a-2"), Some("b-1")]);
+    }
+
+    #[test]
+    fn test_select_partitions_by_message_id() {
+        let messages = vec![message("a", "a-1"), message("b", "b-1"), message("c", "c-1")];
+
+        let (selected, others) = select(&messages, &["c".to_string(), "a".to_string(), "x".to_string()]);
+
+        assert_eq!(ids(&selected), vec!["a", "c"]);
+        assert_eq!(ids(&others), vec!["b"]);
+    }
+
+    #[test]
+    fn test_redrive_entry_keeps_group_and_derives_deduplication_id() {
+        let tenant_id = MessageAttributeValue::builder()
+            .data_type("String")
+            .string_value("tenant-a")
+            .build()
+            .unwrap();
+        let grouped = Message::builder()
+            .message_id("a")
+            .body("body")
+            .attributes(MessageSystemAttributeName::MessageGroupId, "42")
+            .message_attributes("tenant_id", tenant_id)
+            .build();
+
+        let entry = redrive_entry(3, &grouped).unwrap();
+        assert_eq!(entry.id(), "3");
+        assert_eq!(entry.message_body(), "body");
+        assert_eq!(entry.message_group_id(), Some("42"));
+        assert_eq!(entry.message_deduplication_id(), Some("redrive-a"));
+        let attributes = entry.message_attributes().unwrap();
+        assert_eq!(attributes["tenant_id"].string_value(), Some("tenant-a"));
+
+        let ungrouped = redrive_entry(0, &message("b", "b-1")).unwrap();
+        assert_eq!(ungrouped.message_group_id(), Some("b"));
+    }
+
+    #[test]
+    fn test_decode_body_pretty_prints_json_only() {
+        assert_eq!(decode_body(&message("a", "a-1")), "{\n  \"id\": 1\n}");
+
+        let text = Message::builder().message_id("b").body("not json").build();
+        assert_eq!(decode_body(&text), "not json");
+        assert_eq!(decode_body(&Message::builder().build()), "");
+    }
+}
@@ -0,0 +1,54 @@
+use std::future::ready;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{http::StatusCode, routing::get, Router};
+use metrics_exporter_prometheus::PrometheusHandle;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+
+/// Ready while the last poll of the queue succeeded and no shutdown is in progress
+#[derive(Clone)]
+pub struct ConsumerHealth {
+    receiving: Arc<AtomicBool>,
+    stopping: watch::Receiver<bool>,
+}
+
+impl ConsumerHealth {
+    pub fn new(stopping: watch::Receiver<bool>) -> Self {
+        Self {
+            receiving: Arc::new(AtomicBool::new(true)),
+            stopping,
+        }
+    }
+
+    pub fn set_receiving(&self, receiving: bool) {
+        self.receiving.store(receiving, Ordering::Relaxed);
+    }
+
+    fn is_ready(&self) -> bool {
+        self.receiving.load(Ordering::Relaxed) && !*self.stopping.borrow()
+    }
+}
+
+/// Serves `/_/metrics`, `/_/health/live` and `/_/health/ready` until the process exits
+pub async fn serve(port: u16, recorder_handle: PrometheusHandle, health: ConsumerHealth) -> Result<()> {
+    let app = Router::new()
+        .route("/_/metrics", get(move || ready(recorder_handle.render())))
+        .route("/_/health/live", get(|| ready(StatusCode::OK)))
+        .route(
+            "/_/health/ready",
+            get(move || {
+                ready(if health.is_ready() {
+                    StatusCode::OK
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE
+                })
+            }),
+        );
+
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
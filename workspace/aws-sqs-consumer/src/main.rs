@@ -1,48 +1,140 @@
-use aws_sdk_sqs as sqs;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use infrastructure::database::repositories::{
+    database_data::DatabaseConnection,
+    stub_entity_projection_sea_orm_postgres_repository::StubEntityProjectionSeaOrmPostgresRepository,
+};
+use infrastructure::messaging::handlers::{
+    handler_registry::HandlerRegistry, stub_entity_projection_handler::StubEntityProjectionHandler,
+};
+use infrastructure::tracing::tracing_configuration;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
+use tracing::{error, info};
+
+mod consumer;
+mod consumer_metrics;
+mod dlq;
+mod health_server;
+mod sqs;
+
+const DEFAULT_QUEUE_URL: &str =
+    "http://sqs.us-east-1.localhost.localstack.cloud:4566/000000000000/rust-test-sqs-queue.fifo";
+const DEFAULT_DLQ_URL: &str =
+    "http://sqs.us-east-1.localhost.localstack.cloud:4566/000000000000/rust-test-sqs-dlq";
+
+#[derive(Parser)]
+#[command(about = "Consumes the stub entity queue and manages its dead-letter queue")]
+struct Cli {
+    /// Source FIFO queue
+    #[arg(long, global = true, env = "SQS_QUEUE_URL", default_value = DEFAULT_QUEUE_URL)]
+    queue_url: String,
+
+    /// Dead-letter queue of the source queue
+    #[arg(long, global = true, env = "SQS_DLQ_URL", default_value = DEFAULT_DLQ_URL)]
+    dlq_url: String,
+
+    /// Message groups processed in parallel by `consume`
+    #[arg(
+        long,
+        env = "CONSUMER_CONCURRENCY",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    concurrency: u16,
+
+    /// Port of the `/_/metrics` and `/_/health` endpoints of `consume`
+    #[arg(long, env = "CONSUMER_HTTP_PORT", default_value_t = 3001)]
+    http_port: u16,
+
+    /// How often `consume` refreshes the queue depth gauges
+    #[arg(
+        long,
+        env = "CONSUMER_QUEUE_METRICS_INTERVAL_SECONDS",
+        default_value_t = 15,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    queue_metrics_interval_seconds: u64,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Dispatches messages of the source queue to their handlers (the default)
+    Consume,
+    /// Inspects, redrives or purges the dead-letter queue
+    Dlq {
+        #[command(subcommand)]
+        command: dlq::DlqCommand,
+    },
+}
 
 #[::tokio::main]
-async fn main() -> Result<(), sqs::Error> {
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     let config = aws_config::load_from_env().await;
     let client = aws_sdk_sqs::Client::new(&config);
 
-    let queue_url = "http://sqs.us-east-1.localhost.localstack.cloud:4566/000000000000/rust-test-sqs-queue.fifo";
-
-    loop {
-        let receive_result = client.receive_message()
-            .queue_url(queue_url)
-            .max_number_of_messages(10)
-            .wait_time_seconds(20)
-            .send().await;
-
-        match receive_result {
-            Ok(output) => {
-                if let Some(messages) = output.messages {
-                    for message in messages {
-                        if let Some(body) = message.body {
-                            println!("Received message: {}", body);
-
-                            // Process the message here
-
-                            // Delete the message after processing
-                            if let Some(receipt_handle) = message.receipt_handle {
-                                client.delete_message()
-                                    .queue_url(queue_url)
-                                    .receipt_handle(receipt_handle)
-                                    .send()
-                                    .await?;
-                            }
-                        }
-                    }
+    match cli.command.unwrap_or(Command::Consume) {
+        Command::Consume => {
+            tracing_configuration::configure_tracing(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+            let recorder_handle = consumer_metrics::setup_metrics_recorder();
+
+            let registry = build_handler_registry().await?;
+            let (stop, stopping) = watch::channel(false);
+            tokio::spawn(async move {
+                shutdown_signal().await;
+                info!("Shutting down");
+                let _ = stop.send(true);
+            });
+
+            let health = health_server::ConsumerHealth::new(stopping.clone());
+            let http_port = cli.http_port;
+            let server_health = health.clone();
+            tokio::spawn(async move {
+                if let Err(err) = health_server::serve(http_port, recorder_handle, server_health).await {
+                    error!(error = ?err, "Metrics and health server stopped");
                 }
-            }
-            Err(err) => {
-                eprintln!("Error receiving messages: {:?}", err);
-            }
+            });
+            consumer_metrics::spawn_queue_depth_poller(
+                client.clone(),
+                cli.queue_url.clone(),
+                Duration::from_secs(cli.queue_metrics_interval_seconds),
+            );
+            info!(
+                app.name = %env!("CARGO_PKG_NAME"),
+                app.version = %env!("CARGO_PKG_VERSION"),
+                "Consuming {}, metrics and health on port {}",
+                cli.queue_url,
+                http_port);
+
+            consumer::run(client, &cli.queue_url, registry, cli.concurrency.into(), stopping, health).await
         }
+        Command::Dlq { command } => dlq::run(&client, &cli.dlq_url, &cli.queue_url, command).await,
+    }
+}
+
+/// Tables are created by the API's migrations, which must have run against the same database
+async fn build_handler_registry() -> Result<HandlerRegistry> {
+    let db = DatabaseConnection::new().await?;
+    let projection_handler = StubEntityProjectionHandler::new(Arc::new(
+        StubEntityProjectionSeaOrmPostgresRepository::new(db),
+    ));
+
+    Ok(projection_handler.register(HandlerRegistry::default()))
+}
 
-        // Sleep for a short duration before polling again
-        sleep(Duration::from_secs(5)).await;
+/// Ctrl+C, or SIGTERM as sent by container runtimes
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("failed to install the SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
     }
-}
\ No newline at end of file
+}
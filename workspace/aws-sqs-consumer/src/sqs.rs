@@ -0,0 +1,49 @@
+use anyhow::Result;
+use aws_sdk_sqs::types::{ChangeMessageVisibilityBatchRequestEntry, Message, MessageSystemAttributeName};
+use aws_sdk_sqs::Client;
+
+/// Largest batch accepted by the SQS batch APIs
+pub const SQS_BATCH_SIZE: usize = 10;
+
+/// Makes messages visible again right away instead of after their visibility timeout
+pub async fn release(client: &Client, queue_url: &str, messages: &[&Message]) -> Result<()> {
+    for chunk in messages.chunks(SQS_BATCH_SIZE) {
+        let entries = chunk
+            .iter()
+            .enumerate()
+            .filter_map(|(index, message)| {
+                message.receipt_handle().map(|receipt_handle| {
+                    ChangeMessageVisibilityBatchRequestEntry::builder()
+                        .id(index.to_string())
+                        .receipt_handle(receipt_handle)
+                        .visibility_timeout(0)
+                        .build()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        client
+            .change_message_visibility_batch()
+            .queue_url(queue_url)
+            .set_entries(Some(entries))
+            .send()
+            .await?;
+    }
+    Ok(())
+}
+
+pub fn message_id(message: &Message) -> &str {
+    message.message_id().unwrap_or_default()
+}
+
+/// Messages that came from a standard queue have no group and get one of their own
+pub fn group_id(message: &Message) -> &str {
+    system_attribute(message, MessageSystemAttributeName::MessageGroupId)
+        .unwrap_or_else(|| message_id(message))
+}
+
+pub fn system_attribute(message: &Message, name: MessageSystemAttributeName) -> Option<&str> {
+    message
+        .attributes()
+        .and_then(|attributes| attributes.get(&name))
+        .map(String::as_str)
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiKey {
+    pub id: Option<i32>,
+    pub name: String,
+    /// Tenant every request authenticated with the key is served for
+    pub tenant_id: String,
+    /// Public lookup part of the key; the secret itself is never stored
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn api_key() -> ApiKey {
+        ApiKey {
+            id: Some(1),
+            name: "batch-job".to_string(),
+            tenant_id: "default".to_string(),
+            prefix: "abcd1234".to_string(),
+            key_hash: "hash".to_string(),
+            scopes: vec!["stub-entity:read".to_string()],
+            expires_at: None,
+            revoked_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_is_active() {
+        let now = Utc::now();
+        assert!(api_key().is_active(now));
+
+        let expired = ApiKey {
+            expires_at: Some(now - Duration::seconds(1)),
+            ..api_key()
+        };
+        assert!(!expired.is_active(now));
+
+        let revoked = ApiKey {
+            revoked_at: Some(now),
+            ..api_key()
+        };
+        assert!(!revoked.is_active(now));
+    }
+}
@@ -0,0 +1,190 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportFormat {
+    Ndjson,
+    Csv,
+}
+
+impl ImportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ndjson => "NDJSON",
+            Self::Csv => "CSV",
+        }
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "NDJSON" => Ok(Self::Ndjson),
+            "CSV" => Ok(Self::Csv),
+            _ => bail!("Unknown import format {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ImportJobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Running => "RUNNING",
+            Self::Completed => "COMPLETED",
+            Self::Failed => "FAILED",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+impl FromStr for ImportJobStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "PENDING" => Ok(Self::Pending),
+            "RUNNING" => Ok(Self::Running),
+            "COMPLETED" => Ok(Self::Completed),
+            "FAILED" => Ok(Self::Failed),
+            _ => bail!("Unknown import job status {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportLineStatus {
+    Pending,
+    Created,
+    Failed,
+}
+
+impl ImportLineStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Created => "CREATED",
+            Self::Failed => "FAILED",
+        }
+    }
+}
+
+impl FromStr for ImportLineStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "PENDING" => Ok(Self::Pending),
+            "CREATED" => Ok(Self::Created),
+            "FAILED" => Ok(Self::Failed),
+            _ => bail!("Unknown import line status {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJob {
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub tenant_id: String,
+    pub format: ImportFormat,
+    pub status: ImportJobStatus,
+    pub actor: Option<String>,
+    /// Why the job stopped before processing every line
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// One record of the uploaded file; `content` is absent when the record could not be parsed
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportLine {
+    pub line_number: i32,
+    #[serde(skip_serializing)]
+    pub content: Option<Value>,
+    pub status: ImportLineStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ImportLine {
+    pub fn parsed(line_number: i32, content: Value) -> Self {
+        Self {
+            line_number,
+            content: Some(content),
+            status: ImportLineStatus::Pending,
+            entity_id: None,
+            error: None,
+        }
+    }
+
+    pub fn unparsable(line_number: i32, error: String) -> Self {
+        Self {
+            line_number,
+            content: None,
+            status: ImportLineStatus::Failed,
+            entity_id: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportJobCounts {
+    pub total: u64,
+    pub pending: u64,
+    pub created: u64,
+    pub failed: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJobReport {
+    #[serde(flatten)]
+    pub job: ImportJob,
+    pub counts: ImportJobCounts,
+    /// Failed lines, capped; `counts.failed` has the full number
+    pub errors: Vec<ImportLine>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            ImportJobStatus::Pending,
+            ImportJobStatus::Running,
+            ImportJobStatus::Completed,
+            ImportJobStatus::Failed,
+        ] {
+            assert_eq!(ImportJobStatus::from_str(status.as_str()).unwrap(), status);
+        }
+        assert!(ImportLineStatus::from_str("UNKNOWN").is_err());
+        assert!(ImportJobStatus::Failed.is_finished());
+        assert!(!ImportJobStatus::Running.is_finished());
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthenticationMethod {
+    Jwt,
+    ApiKey,
+    /// Authentication is turned off (local development); every scope is granted.
+    Disabled,
+}
+
+impl AuthenticationMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthenticationMethod::Jwt => "jwt",
+            AuthenticationMethod::ApiKey => "api_key",
+            AuthenticationMethod::Disabled => "disabled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Principal {
+    pub subject: String,
+    pub scopes: HashSet<String>,
+    pub method: AuthenticationMethod,
+    /// Tenant asserted by the credential itself (e.g. a token claim)
+    pub tenant_id: Option<String>,
+}
+
+impl Principal {
+    pub fn new(subject: String, scopes: HashSet<String>, method: AuthenticationMethod) -> Self {
+        Self {
+            subject,
+            scopes,
+            method,
+            tenant_id: None,
+        }
+    }
+
+    pub fn authentication_disabled() -> Self {
+        Self::new(
+            String::from("anonymous"),
+            HashSet::new(),
+            AuthenticationMethod::Disabled,
+        )
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.method != AuthenticationMethod::Disabled
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        !self.is_authenticated() || self.scopes.contains(scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_scope() {
+        let principal = Principal::new(
+            String::from("batch-job"),
+            HashSet::from([String::from("stub-entity:read")]),
+            AuthenticationMethod::Jwt,
+        );
+
+        assert!(principal.has_scope("stub-entity:read"));
+        assert!(!principal.has_scope("stub-entity:write"));
+    }
+
+    #[test]
+    fn test_authentication_disabled_grants_every_scope() {
+        let principal = Principal::authentication_disabled();
+
+        assert!(!principal.is_authenticated());
+        assert!(principal.has_scope("stub-entity:admin"));
+    }
+}
@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+/// What a change does with an event the messaging service failed to publish.
+/// The change itself is committed either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishFailurePolicy {
+    /// The request fails, so the client knows the event is missing
+    FailRequest,
+    /// Retryable failures are queued and sent again in the background
+    #[default]
+    Retry,
+    /// The failure is logged and the event is lost
+    Log,
+}
+
+impl PublishFailurePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FailRequest => "fail_request",
+            Self::Retry => "retry",
+            Self::Log => "log",
+        }
+    }
+}
+
+impl FromStr for PublishFailurePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "fail_request" => Ok(Self::FailRequest),
+            "retry" => Ok(Self::Retry),
+            "log" => Ok(Self::Log),
+            _ => bail!("Unknown publish failure policy {}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        for policy in [
+            PublishFailurePolicy::FailRequest,
+            PublishFailurePolicy::Retry,
+            PublishFailurePolicy::Log,
+        ] {
+            assert_eq!(PublishFailurePolicy::from_str(policy.as_str()).unwrap(), policy);
+        }
+        assert!(PublishFailurePolicy::from_str("drop").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::errors::domain_errors::DomainError;
+
+use super::stub_domain_entity::StubEntity;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// Any invalid or failing item aborts the whole batch
+    #[default]
+    AllOrNothing,
+    /// Valid items are applied, failing ones are reported
+    BestEffort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Created,
+    Updated,
+    Invalid,
+    NotFound,
+    Failed,
+    /// Not applied because another item aborted an all-or-nothing batch
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub status: BatchItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<StubEntity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+}
+
+impl BatchItemResult {
+    pub fn succeeded(index: usize, status: BatchItemStatus, entity: StubEntity) -> Self {
+        Self {
+            index,
+            status,
+            entity: Some(entity),
+            error: None,
+            published: None,
+        }
+    }
+
+    pub fn failed(index: usize, status: BatchItemStatus, error: Value) -> Self {
+        Self {
+            index,
+            status,
+            entity: None,
+            error: Some(error),
+            published: None,
+        }
+    }
+
+    /// Domain rule violations are reported back to the caller, anything else stays opaque
+    pub fn from_error(index: usize, err: &anyhow::Error) -> Self {
+        match err.downcast_ref::<DomainError>() {
+            Some(DomainError::CycleDetected(cycle)) => Self::failed(
+                index,
+                BatchItemStatus::Invalid,
+                json!({ "message": err.to_string(), "cycle": cycle }),
+            ),
+            Some(domain_error) => Self::failed(
+                index,
+                BatchItemStatus::Invalid,
+                json!({ "message": domain_error.to_string() }),
+            ),
+            None => Self::failed(
+                index,
+                BatchItemStatus::Failed,
+                json!({ "message": "Unexpected error" }),
+            ),
+        }
+    }
+
+    pub fn skipped(index: usize) -> Self {
+        Self {
+            index,
+            status: BatchItemStatus::Skipped,
+            entity: None,
+            error: None,
+            published: None,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self.status,
+            BatchItemStatus::Created | BatchItemStatus::Updated
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_error_exposes_domain_errors_only() {
+        let invalid = BatchItemResult::from_error(
+            0,
+            &anyhow::Error::new(DomainError::CycleDetected(vec![1, 2, 1])),
+        );
+        assert_eq!(invalid.status, BatchItemStatus::Invalid);
+        assert_eq!(invalid.error.unwrap()["cycle"], json!([1, 2, 1]));
+
+        let failed = BatchItemResult::from_error(1, &anyhow::anyhow!("connection reset"));
+        assert_eq!(failed.status, BatchItemStatus::Failed);
+        assert!(!failed.is_success());
+        assert_eq!(failed.error.unwrap()["message"], "Unexpected error");
+    }
+}
@@ -1,15 +1,30 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StubEntity {
     pub id: Option<i32>,
     pub name: String,
     pub value: KeyValue,
     pub auto_ref: Option<i32>,
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Set when the entity was stored with its client value because enrichment was unavailable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrichment_pending_since: Option<DateTime<Utc>>,
+    /// Incremented by the database on every write, so it follows commit order; 0 until stored.
+    /// Published as the `entityversion` event attribute rather than as part of the entity.
+    #[serde(skip)]
+    pub version: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyValue {
     pub id: i32,
     pub name: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StubEntityFilter {
+    pub include_deleted: bool,
+}
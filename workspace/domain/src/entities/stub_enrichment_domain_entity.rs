@@ -0,0 +1,171 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::errors::domain_errors::DomainError;
+
+use super::stub_domain_entity::{KeyValue, StubEntity};
+
+/// How the key-value service contributes to the `value` of a created entity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichmentPolicy {
+    /// The client value is stored as sent and must be complete
+    Client,
+    /// The enriched value replaces the client value
+    #[default]
+    Always,
+    /// The client value when complete, the enriched value otherwise
+    WhenAbsent,
+    /// The enriched value with the fields sent by the client on top
+    Merge,
+}
+
+impl EnrichmentPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Always => "always",
+            Self::WhenAbsent => "when_absent",
+            Self::Merge => "merge",
+        }
+    }
+
+    pub fn needs_enrichment(&self, draft: Option<&KeyValueDraft>) -> bool {
+        match self {
+            Self::Client => false,
+            Self::Always => true,
+            Self::WhenAbsent | Self::Merge => draft.and_then(KeyValueDraft::complete).is_none(),
+        }
+    }
+
+    /// Value of an entity that needs no enrichment
+    pub fn client_value(&self, draft: Option<&KeyValueDraft>) -> Result<KeyValue, DomainError> {
+        draft.and_then(KeyValueDraft::complete).ok_or_else(|| {
+            DomainError::UnprocessableEntity(format!(
+                "value with id and name is required with {} enrichment",
+                self.as_str()
+            ))
+        })
+    }
+
+    pub fn apply(&self, draft: Option<&KeyValueDraft>, enriched: KeyValue) -> KeyValue {
+        match (self, draft) {
+            (Self::Merge, Some(draft)) => KeyValue {
+                id: draft.id.unwrap_or(enriched.id),
+                name: draft.name.clone().unwrap_or(enriched.name),
+            },
+            _ => enriched,
+        }
+    }
+}
+
+impl FromStr for EnrichmentPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "client" => Ok(Self::Client),
+            "always" => Ok(Self::Always),
+            "when_absent" => Ok(Self::WhenAbsent),
+            "merge" => Ok(Self::Merge),
+            _ => bail!("Unknown enrichment policy {}", value),
+        }
+    }
+}
+
+/// Value as sent by a client, possibly partial
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyValueDraft {
+    pub id: Option<i32>,
+    pub name: Option<String>,
+}
+
+impl KeyValueDraft {
+    pub fn complete(&self) -> Option<KeyValue> {
+        match (self.id, &self.name) {
+            (Some(id), Some(name)) => Some(KeyValue {
+                id,
+                name: name.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Entity to create, before its value is resolved
+#[derive(Debug, Clone)]
+pub struct StubEntityDraft {
+    pub name: String,
+    pub value: Option<KeyValueDraft>,
+    pub auto_ref: Option<i32>,
+}
+
+impl StubEntityDraft {
+    pub fn into_entity(self, value: KeyValue, enrichment_pending_since: Option<DateTime<Utc>>) -> StubEntity {
+        StubEntity {
+            id: None,
+            name: self.name,
+            value,
+            auto_ref: self.auto_ref,
+            deleted_at: None,
+            enrichment_pending_since,
+            version: 0,
+        }
+    }
+}
+
+/// Entity stored with its client value while the key-value service was unavailable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingEnrichment {
+    pub tenant_id: String,
+    pub entity_id: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enriched() -> KeyValue {
+        KeyValue {
+            id: 7,
+            name: "enriched".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_needs_enrichment() {
+        let partial = KeyValueDraft {
+            id: Some(1),
+            name: None,
+        };
+        let complete = KeyValueDraft {
+            id: Some(1),
+            name: Some("client".to_string()),
+        };
+
+        assert!(!EnrichmentPolicy::Client.needs_enrichment(None));
+        assert!(EnrichmentPolicy::Always.needs_enrichment(Some(&complete)));
+        assert!(EnrichmentPolicy::WhenAbsent.needs_enrichment(Some(&partial)));
+        assert!(!EnrichmentPolicy::WhenAbsent.needs_enrichment(Some(&complete)));
+        assert!(!EnrichmentPolicy::Merge.needs_enrichment(Some(&complete)));
+        assert!(EnrichmentPolicy::Client.client_value(Some(&partial)).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let partial = KeyValueDraft {
+            id: None,
+            name: Some("client".to_string()),
+        };
+
+        let merged = EnrichmentPolicy::Merge.apply(Some(&partial), enriched());
+        assert_eq!((merged.id, merged.name.as_str()), (7, "client"));
+
+        let replaced = EnrichmentPolicy::Always.apply(Some(&partial), enriched());
+        assert_eq!(replaced.name, "enriched");
+        assert_eq!(EnrichmentPolicy::from_str("when_absent").unwrap(), EnrichmentPolicy::WhenAbsent);
+    }
+}
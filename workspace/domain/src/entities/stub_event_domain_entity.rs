@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::stub_domain_entity::StubEntity;
+
+pub const CLOUD_EVENTS_SPEC_VERSION: &str = "1.0";
+/// Version of the `data` schema; bumped on breaking changes to `StubEntity` serialization
+pub const STUB_ENTITY_EVENT_SCHEMA_VERSION: &str = "1";
+pub const STUB_ENTITY_EVENT_SOURCE: &str = "/rust-api/stub-entity";
+pub const STUB_ENTITY_CREATED_EVENT_TYPE: &str = "rust-api.stub-entity.created";
+pub const STUB_ENTITY_UPDATED_EVENT_TYPE: &str = "rust-api.stub-entity.updated";
+pub const STUB_ENTITY_DELETED_EVENT_TYPE: &str = "rust-api.stub-entity.deleted";
+
+#[derive(Debug, Clone)]
+pub enum StubEntityEvent {
+    Created(StubEntity),
+    Updated {
+        entity: StubEntity,
+        changes: BTreeMap<String, FieldChange>,
+    },
+    Deleted(StubEntity),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub previous: Value,
+    pub current: Value,
+}
+
+/// `data` of an event: the entity as committed, plus the changed fields of an update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StubEntityEventData {
+    #[serde(flatten)]
+    pub entity: StubEntity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changes: Option<BTreeMap<String, FieldChange>>,
+}
+
+impl StubEntityEvent {
+    /// `None` when the update left every field as it was
+    pub fn updated(previous: &StubEntity, entity: StubEntity) -> Option<Self> {
+        let previous = serde_json::to_value(previous).unwrap_or_default();
+        let current = serde_json::to_value(&entity).unwrap_or_default();
+        let (Value::Object(previous), Value::Object(current)) = (previous, current) else {
+            return None;
+        };
+
+        let fields: BTreeSet<&String> = previous.keys().chain(current.keys()).collect();
+        let changes: BTreeMap<String, FieldChange> = fields
+            .into_iter()
+            .filter_map(|field| {
+                let previous = previous.get(field).cloned().unwrap_or_default();
+                let current = current.get(field).cloned().unwrap_or_default();
+                (previous != current).then(|| (field.clone(), FieldChange { previous, current }))
+            })
+            .collect();
+
+        (!changes.is_empty()).then_some(Self::Updated { entity, changes })
+    }
+
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::Created(_) => "created",
+            Self::Updated { .. } => "updated",
+            Self::Deleted(_) => "deleted",
+        }
+    }
+
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::Created(_) => STUB_ENTITY_CREATED_EVENT_TYPE,
+            Self::Updated { .. } => STUB_ENTITY_UPDATED_EVENT_TYPE,
+            Self::Deleted(_) => STUB_ENTITY_DELETED_EVENT_TYPE,
+        }
+    }
+
+    pub fn entity(&self) -> &StubEntity {
+        match self {
+            Self::Created(entity) | Self::Updated { entity, .. } | Self::Deleted(entity) => entity,
+        }
+    }
+
+    pub fn into_cloud_event(self, time: DateTime<Utc>) -> CloudEvent<StubEntityEventData> {
+        let subject = self.entity().id.unwrap_or_default().to_string();
+        let entityversion = self.entity().version;
+        CloudEvent {
+            specversion: CLOUD_EVENTS_SPEC_VERSION.to_string(),
+            id: format!("{}-{}-{}", subject, self.action(), entityversion),
+            event_type: self.event_type().to_string(),
+            source: STUB_ENTITY_EVENT_SOURCE.to_string(),
+            time,
+            subject,
+            datacontenttype: "application/json".to_string(),
+            schemaversion: STUB_ENTITY_EVENT_SCHEMA_VERSION.to_string(),
+            entityversion,
+            data: match self {
+                Self::Created(entity) | Self::Deleted(entity) => StubEntityEventData {
+                    entity,
+                    changes: None,
+                },
+                Self::Updated { entity, changes } => StubEntityEventData {
+                    entity,
+                    changes: Some(changes),
+                },
+            },
+        }
+    }
+}
+
+/// CloudEvents 1.0 envelope in structured JSON mode. `schemaversion` and `entityversion` are extension attributes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudEvent<T> {
+    pub specversion: String,
+    /// Unique per event; also the deduplication id of the published message
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub source: String,
+    pub time: DateTime<Utc>,
+    /// Id of the entity the event is about
+    pub subject: String,
+    pub datacontenttype: String,
+    pub schemaversion: String,
+    /// Version of the entity after the change; events of one entity are ordered by it
+    #[serde(default)]
+    pub entityversion: i32,
+    pub data: T,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::entities::stub_domain_entity::KeyValue;
+
+    fn entity() -> StubEntity {
+        StubEntity {
+            id: Some(42),
+            name: "name".to_string(),
+            value: KeyValue {
+                id: 1,
+                name: "value".to_string(),
+            },
+            auto_ref: None,
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 2,
+        }
+    }
+
+    #[test]
+    fn test_into_cloud_event() {
+        let time = Utc.with_ymd_and_hms(2024, 12, 1, 10, 0, 0).unwrap();
+
+        let event = StubEntityEvent::Created(entity()).into_cloud_event(time);
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "specversion": "1.0",
+                "id": "42-created-2",
+                "type": "rust-api.stub-entity.created",
+                "source": "/rust-api/stub-entity",
+                "time": "2024-12-01T10:00:00Z",
+                "subject": "42",
+                "datacontenttype": "application/json",
+                "schemaversion": "1",
+                "entityversion": 2,
+                "data": {
+                    "id": 42,
+                    "name": "name",
+                    "value": { "id": 1, "name": "value" },
+                    "auto_ref": null,
+                    "deleted_at": null
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_updated_lists_changed_fields() {
+        let previous = entity();
+        let mut current = entity();
+        current.name = "renamed".to_string();
+        current.auto_ref = Some(7);
+
+        let Some(StubEntityEvent::Updated { changes, .. }) =
+            StubEntityEvent::updated(&previous, current)
+        else {
+            panic!("expected an updated event");
+        };
+
+        assert_eq!(changes.keys().collect::<Vec<_>>(), ["auto_ref", "name"]);
+        assert_eq!(
+            changes["name"],
+            FieldChange {
+                previous: json!("name"),
+                current: json!("renamed"),
+            }
+        );
+        assert!(StubEntityEvent::updated(&previous, entity()).is_none());
+    }
+}
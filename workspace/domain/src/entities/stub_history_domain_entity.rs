@@ -0,0 +1,170 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StubEntityHistoryOperation {
+    Insert,
+    Update,
+    Delete,
+    Restore,
+    Purge,
+}
+
+impl StubEntityHistoryOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "INSERT",
+            Self::Update => "UPDATE",
+            Self::Delete => "DELETE",
+            Self::Restore => "RESTORE",
+            Self::Purge => "PURGE",
+        }
+    }
+}
+
+impl FromStr for StubEntityHistoryOperation {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "INSERT" => Ok(Self::Insert),
+            "UPDATE" => Ok(Self::Update),
+            "DELETE" => Ok(Self::Delete),
+            "RESTORE" => Ok(Self::Restore),
+            "PURGE" => Ok(Self::Purge),
+            _ => bail!("Unknown stub entity history operation {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StubEntityHistory {
+    pub id: i64,
+    /// Id of the transaction that wrote the row; history ids are taken at insert, so only this follows commit order
+    #[serde(skip)]
+    pub transaction_id: i64,
+    pub entity_id: i32,
+    pub operation: StubEntityHistoryOperation,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub changed_fields: Vec<String>,
+    pub actor: Option<String>,
+    pub correlation_id: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl StubEntityHistory {
+    pub fn cursor(&self) -> StubEntityChangeCursor {
+        StubEntityChangeCursor {
+            transaction_id: self.transaction_id,
+            id: self.id,
+        }
+    }
+}
+
+/// Position in a change feed, ordered by writing transaction and then by history id.
+/// Formatted as `<transaction_id>-<id>` for the SSE event id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StubEntityChangeCursor {
+    pub transaction_id: i64,
+    pub id: i64,
+}
+
+impl fmt::Display for StubEntityChangeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.transaction_id, self.id)
+    }
+}
+
+impl FromStr for StubEntityChangeCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let Some((transaction_id, id)) = value.split_once('-') else {
+            bail!("Invalid stub entity change cursor {}", value);
+        };
+        Ok(Self {
+            transaction_id: transaction_id.parse()?,
+            id: id.parse()?,
+        })
+    }
+}
+
+/// Narrows a change feed to one entity and/or to the children of one entity
+#[derive(Debug, Clone, Default)]
+pub struct StubEntityChangeFilter {
+    pub entity_id: Option<i32>,
+    pub auto_ref: Option<i32>,
+}
+
+impl StubEntityChangeFilter {
+    /// A change matches `auto_ref` when either side of it points there,
+    /// so moving an entity away from a parent is still reported to that parent's subscribers
+    pub fn matches(&self, change: &StubEntityHistory) -> bool {
+        let entity_id_matches = self
+            .entity_id
+            .is_none_or(|entity_id| entity_id == change.entity_id);
+        let auto_ref_matches = self.auto_ref.is_none_or(|auto_ref| {
+            [&change.before, &change.after]
+                .into_iter()
+                .flatten()
+                .any(|state| state.get("auto_ref").and_then(Value::as_i64) == Some(auto_ref.into()))
+        });
+        entity_id_matches && auto_ref_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn change(entity_id: i32, before: Option<Value>, after: Option<Value>) -> StubEntityHistory {
+        StubEntityHistory {
+            id: 1,
+            transaction_id: 1,
+            entity_id,
+            operation: StubEntityHistoryOperation::Update,
+            before,
+            after,
+            changed_fields: vec![],
+            actor: None,
+            correlation_id: None,
+            changed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_change_filter_matches_auto_ref_on_either_side() {
+        let filter = StubEntityChangeFilter {
+            entity_id: None,
+            auto_ref: Some(7),
+        };
+
+        let moved_away = change(1, Some(json!({ "auto_ref": 7 })), Some(json!({ "auto_ref": 8 })));
+        let unrelated = change(2, Some(json!({ "auto_ref": null })), Some(json!({ "auto_ref": 8 })));
+
+        assert!(filter.matches(&moved_away));
+        assert!(!filter.matches(&unrelated));
+        assert!(StubEntityChangeFilter::default().matches(&unrelated));
+    }
+
+    #[test]
+    fn test_change_cursor_round_trip() {
+        let cursor = StubEntityChangeCursor {
+            transaction_id: 754,
+            id: 12,
+        };
+
+        assert_eq!(cursor.to_string(), "754-12");
+        assert_eq!("754-12".parse::<StubEntityChangeCursor>().unwrap(), cursor);
+        assert!("12".parse::<StubEntityChangeCursor>().is_err());
+        assert!("754-x".parse::<StubEntityChangeCursor>().is_err());
+    }
+}
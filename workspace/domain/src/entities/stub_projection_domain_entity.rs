@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{
+    stub_domain_entity::KeyValue,
+    stub_event_domain_entity::{CloudEvent, StubEntityEventData},
+};
+
+/// Latest known state of a stub entity, materialised from its published events
+#[derive(Debug, Clone, Serialize)]
+pub struct StubEntityProjection {
+    pub entity_id: i32,
+    pub name: String,
+    pub value: KeyValue,
+    pub auto_ref: Option<i32>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// `time` of the event the projection was built from
+    pub event_time: DateTime<Utc>,
+    /// `entityversion` of the event the projection was built from; older versions never overwrite it
+    pub entity_version: i32,
+}
+
+impl StubEntityProjection {
+    pub fn from_event(event: CloudEvent<StubEntityEventData>) -> Option<Self> {
+        let entity = event.data.entity;
+        let entity_id = entity.id.or_else(|| event.subject.parse().ok())?;
+
+        Some(Self {
+            entity_id,
+            name: entity.name,
+            value: entity.value,
+            auto_ref: entity.auto_ref,
+            deleted_at: entity.deleted_at,
+            event_time: event.time,
+            entity_version: event.entityversion,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::entities::{stub_domain_entity::StubEntity, stub_event_domain_entity::StubEntityEvent};
+
+    #[test]
+    fn test_from_event() {
+        let time = Utc.with_ymd_and_hms(2024, 12, 1, 10, 0, 0).unwrap();
+        let entity = StubEntity {
+            id: Some(42),
+            name: "name".to_string(),
+            value: KeyValue {
+                id: 1,
+                name: "value".to_string(),
+            },
+            auto_ref: Some(7),
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 4,
+        };
+
+        let projection =
+            StubEntityProjection::from_event(StubEntityEvent::Deleted(entity).into_cloud_event(time))
+                .unwrap();
+
+        assert_eq!(projection.entity_id, 42);
+        assert_eq!(projection.auto_ref, Some(7));
+        assert_eq!(projection.event_time, time);
+        assert_eq!(projection.entity_version, 4);
+    }
+}
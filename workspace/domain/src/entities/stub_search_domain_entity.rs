@@ -0,0 +1,67 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::stub_domain_entity::StubEntity;
+
+#[derive(Debug, Clone, Default)]
+pub struct StubEntitySearch {
+    /// Words matched as prefixes against `name` and the string fields of `value`
+    pub text: Option<String>,
+    /// JSON object the `value` column must contain
+    pub value: Option<Value>,
+    pub include_deleted: bool,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+impl StubEntitySearch {
+    /// Builds a `to_tsquery` expression requiring every word as a prefix, e.g. `"ali sm"` becomes
+    /// `"ali:* & sm:*"`. Only alphanumeric characters are kept, so user input cannot inject
+    /// tsquery operators.
+    pub fn prefix_query(&self) -> Option<String> {
+        let terms: Vec<String> = self
+            .text
+            .as_deref()
+            .unwrap_or_default()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|term| !term.is_empty())
+            .map(|term| format!("{}:*", term.to_lowercase()))
+            .collect();
+
+        if terms.is_empty() {
+            None
+        } else {
+            Some(terms.join(" & "))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StubEntitySearchHit {
+    #[serde(flatten)]
+    pub entity: StubEntity,
+    pub rank: f32,
+    /// `name` with the matched words wrapped in `<mark>` tags
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(text: &str) -> StubEntitySearch {
+        StubEntitySearch {
+            text: Some(text.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_prefix_query() {
+        assert_eq!(search("Ali sm").prefix_query().as_deref(), Some("ali:* & sm:*"));
+        assert_eq!(search("a&b | !c:*").prefix_query().as_deref(), Some("a:* & b:* & c:*"));
+        assert_eq!(search(" !& ").prefix_query(), None);
+        assert_eq!(StubEntitySearch::default().prefix_query(), None);
+    }
+}
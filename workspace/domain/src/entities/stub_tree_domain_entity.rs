@@ -0,0 +1,106 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::stub_domain_entity::StubEntity;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StubEntityNode {
+    pub depth: i32,
+    #[serde(flatten)]
+    pub entity: StubEntity,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StubEntityTree {
+    #[serde(flatten)]
+    pub entity: StubEntity,
+    pub children: Vec<StubEntityTree>,
+}
+
+impl StubEntityTree {
+    pub fn build(root: StubEntity, descendants: Vec<StubEntityNode>) -> Self {
+        let mut children_by_parent: HashMap<i32, Vec<StubEntity>> = HashMap::new();
+        for node in descendants {
+            if let Some(parent_id) = node.entity.auto_ref {
+                children_by_parent
+                    .entry(parent_id)
+                    .or_default()
+                    .push(node.entity);
+            }
+        }
+
+        let mut visited = HashSet::new();
+        Self::build_node(root, &mut children_by_parent, &mut visited)
+    }
+
+    fn build_node(
+        entity: StubEntity,
+        children_by_parent: &mut HashMap<i32, Vec<StubEntity>>,
+        visited: &mut HashSet<i32>,
+    ) -> Self {
+        let children = match entity.id {
+            Some(id) if visited.insert(id) => children_by_parent
+                .remove(&id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|child| Self::build_node(child, children_by_parent, visited))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Self { entity, children }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::stub_domain_entity::KeyValue;
+
+    fn entity(id: i32, auto_ref: Option<i32>) -> StubEntity {
+        StubEntity {
+            id: Some(id),
+            name: format!("entity-{}", id),
+            value: KeyValue {
+                id: 1,
+                name: "value".to_string(),
+            },
+            auto_ref,
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let descendants = vec![
+            StubEntityNode { depth: 1, entity: entity(2, Some(1)) },
+            StubEntityNode { depth: 1, entity: entity(3, Some(1)) },
+            StubEntityNode { depth: 2, entity: entity(4, Some(2)) },
+        ];
+
+        let tree = StubEntityTree::build(entity(1, None), descendants);
+
+        assert_eq!(tree.entity.id, Some(1));
+        assert_eq!(tree.children.len(), 2);
+        assert_eq!(tree.children[0].entity.id, Some(2));
+        assert_eq!(tree.children[0].children[0].entity.id, Some(4));
+        assert!(tree.children[1].children.is_empty());
+    }
+
+    #[test]
+    fn test_build_tree_stops_on_cycle() {
+        let descendants = vec![
+            StubEntityNode { depth: 1, entity: entity(2, Some(1)) },
+            StubEntityNode { depth: 2, entity: entity(1, Some(2)) },
+        ];
+
+        let tree = StubEntityTree::build(entity(1, Some(2)), descendants);
+
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].children.len(), 1);
+        assert!(tree.children[0].children[0].children.is_empty());
+    }
+}
@@ -0,0 +1,21 @@
+use core::fmt;
+
+#[derive(Debug)]
+pub enum DomainError {
+    UnprocessableEntity(String),
+    CycleDetected(Vec<i32>),
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::UnprocessableEntity(message) => write!(f, "{}", message),
+            DomainError::CycleDetected(path) => {
+                let path: Vec<String> = path.iter().map(|id| id.to_string()).collect();
+                write!(f, "auto_ref would create a cycle: {}", path.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for DomainError {}
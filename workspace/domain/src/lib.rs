@@ -1,10 +1,31 @@
 pub mod entities {
+    pub mod api_key_domain_entity;
+    pub mod import_job_domain_entity;
     pub mod stub_domain_entity;
+    pub mod stub_history_domain_entity;
+    pub mod stub_tree_domain_entity;
+    pub mod stub_search_domain_entity;
+    pub mod stub_enrichment_domain_entity;
+    pub mod stub_batch_domain_entity;
+    pub mod stub_event_domain_entity;
+    pub mod stub_projection_domain_entity;
+    pub mod publish_failure_policy_domain_entity;
+    pub mod page_domain_entity;
+    pub mod principal_domain_entity;
+}
+
+pub mod errors {
+    pub mod domain_errors;
 }
 
 pub mod ports {
     pub mod repositories {
         pub mod stub_entity_repository_port;
+        pub mod stub_entity_history_repository_port;
+        pub mod api_key_repository_port;
+        pub mod import_job_repository_port;
+        pub mod stub_entity_projection_repository_port;
+        pub mod outbound_message_repository_port;
         pub mod transaction_port;
         pub mod database_connection_port;
         pub mod mockserver_http_service_port;
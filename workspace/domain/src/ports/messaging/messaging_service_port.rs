@@ -1,11 +1,73 @@
+use core::fmt;
+
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::entities::stub_event_domain_entity::StubEntityEvent;
+
+#[derive(Debug, Clone)]
+pub struct OutboundMessage {
+    pub partition_id: String,
+    pub deduplication_id: String,
+    pub body: String,
+}
+
+impl OutboundMessage {
+    /// Events of one entity share a partition, and retries of one event share a deduplication id
+    pub fn from_event(event: StubEntityEvent) -> Result<Self> {
+        let cloud_event = event.into_cloud_event(Utc::now());
+        Ok(Self {
+            partition_id: cloud_event.subject.clone(),
+            deduplication_id: cloud_event.id.clone(),
+            body: serde_json::to_string(&cloud_event)?,
+        })
+    }
+}
+
+/// Identifiers the broker assigned to a published message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendReceipt {
+    pub message_id: String,
+    /// Position of the message within its partition, for brokers that order messages
+    pub sequence_number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendError {
+    /// The broker is shedding load; the same message can be sent again later
+    Throttled(String),
+    /// The broker rejected the message itself; sending it again would fail the same way
+    Permanent(String),
+    /// The message may or may not have been published, e.g. after a timeout
+    Unknown(String),
+}
+
+impl SendError {
+    /// Deduplication ids make retrying safe when the first attempt did go through
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, SendError::Permanent(_))
+    }
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Throttled(message) => write!(f, "throttled: {}", message),
+            SendError::Permanent(message) => write!(f, "rejected: {}", message),
+            SendError::Unknown(message) => write!(f, "failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
 
 #[async_trait]
 pub trait MessagingServicePort : Send + Sync {
-    async fn send_message(
-        &self, 
-        partition_id: String,
-        deduplication_id: String,
-        body: String) -> Result<()>;
-}
\ No newline at end of file
+    async fn send_message(&self, message: OutboundMessage) -> Result<SendReceipt, SendError>;
+
+    /// Returns one outcome per message, in the order the messages were given
+    async fn send_message_batch(
+        &self,
+        messages: Vec<OutboundMessage>) -> Vec<Result<SendReceipt, SendError>>;
+}
@@ -0,0 +1,12 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::entities::api_key_domain_entity::ApiKey;
+
+#[async_trait]
+pub trait ApiKeyRepositoryPort: Send + Sync {
+    async fn add(&self, api_key: &ApiKey) -> Result<ApiKey>;
+    async fn get_all(&self) -> Result<Vec<ApiKey>>;
+    async fn get_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>>;
+    async fn revoke(&self, id: i32) -> Result<Option<ApiKey>>;
+}
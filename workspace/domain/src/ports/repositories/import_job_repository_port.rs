@@ -0,0 +1,41 @@
+use crate::entities::import_job_domain_entity::{
+    ImportFormat, ImportJob, ImportJobCounts, ImportJobStatus, ImportLine,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::transaction_port::TransactionPort;
+
+#[async_trait]
+pub trait ImportJobRepositoryPort: Send + Sync {
+    async fn add(&self, format: ImportFormat, lines: &[ImportLine]) -> Result<ImportJob>;
+    async fn get(&self, id: i32) -> Result<Option<ImportJob>>;
+    /// Pending jobs of every tenant and running ones whose lease lapsed, oldest first
+    async fn get_unfinished(&self) -> Result<Vec<ImportJob>>;
+    /// Marks the job running for `worker_id` until `lease_until` unless another worker
+    /// holds its lease, and returns it when claimed
+    async fn claim(&self, job_id: i32, worker_id: &str, lease_until: DateTime<Utc>) -> Result<Option<ImportJob>>;
+    /// Returns false once the job is no longer claimed by `worker_id`
+    async fn renew_lease(&self, job_id: i32, worker_id: &str, lease_until: DateTime<Utc>) -> Result<bool>;
+    async fn get_lines(&self, job_id: i32) -> Result<Vec<ImportLine>>;
+    async fn get_failed_lines(&self, job_id: i32, limit: u64) -> Result<Vec<ImportLine>>;
+    async fn get_counts(&self, job_id: i32) -> Result<ImportJobCounts>;
+    /// Returns false, leaving the job as is, once it is no longer claimed by `worker_id`
+    async fn finish(
+        &self,
+        job_id: i32,
+        worker_id: &str,
+        status: ImportJobStatus,
+        error: Option<String>,
+    ) -> Result<bool>;
+    async fn fail_line(&self, job_id: i32, line_number: i32, error: String) -> Result<()>;
+    /// Fails once the line is no longer pending, e.g. created by another worker
+    async fn complete_line_within_transaction(
+        &self,
+        job_id: i32,
+        line_number: i32,
+        entity_id: i32,
+        txn: &dyn TransactionPort,
+    ) -> Result<()>;
+}
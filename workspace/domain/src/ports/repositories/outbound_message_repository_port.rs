@@ -0,0 +1,42 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::ports::messaging::messaging_service_port::{OutboundMessage, SendError};
+
+/// Message waiting to be sent again, with the tenant it was published in
+#[derive(Debug, Clone)]
+pub struct QueuedOutboundMessage {
+    pub id: i64,
+    pub tenant_id: String,
+    pub message: OutboundMessage,
+    pub attempts: i32,
+}
+
+#[async_trait]
+pub trait OutboundMessageRepositoryPort: Send + Sync {
+    /// Queues messages of the current tenant, in the given order, that failed on their first
+    /// attempt or, without an error, were held back behind queued messages of their partition
+    async fn enqueue(&self, messages: &[(OutboundMessage, Option<SendError>)]) -> Result<()>;
+    /// Those of `partition_ids` where the current tenant has messages still to be sent again
+    async fn get_queued_partition_ids(&self, partition_ids: &[String]) -> Result<HashSet<String>>;
+    /// Messages of every tenant due for another attempt, oldest first, leaving out those
+    /// queued behind a message of their partition that is not due yet. They are not due
+    /// again before `lease_until`, so other instances leave them alone while they are sent.
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        batch_size: u64,
+    ) -> Result<Vec<QueuedOutboundMessage>>;
+    async fn delete(&self, id: i64) -> Result<()>;
+    /// Without `next_attempt_at` the message stays queued but is never sent again
+    async fn record_failure(
+        &self,
+        id: i64,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<()>;
+}
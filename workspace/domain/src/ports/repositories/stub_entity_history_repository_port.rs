@@ -0,0 +1,33 @@
+use crate::entities::{
+    page_domain_entity::Page, stub_domain_entity::StubEntity,
+    stub_history_domain_entity::{StubEntityChangeCursor, StubEntityHistory},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+#[async_trait]
+pub trait StubEntityHistoryRepositoryPort: Send + Sync {
+    async fn get_history(
+        &self,
+        entity_id: i32,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Page<StubEntityHistory>>;
+    /// A state recorded by a deletion is only returned with `include_deleted`
+    async fn get_as_of(
+        &self,
+        entity_id: i32,
+        as_of: DateTime<Utc>,
+        include_deleted: bool,
+    ) -> Result<Option<StubEntity>>;
+    /// History rows of every entity past `after`, in commit order. Rows are held back while
+    /// an older transaction is still open, since it could yet commit a row before them.
+    async fn get_changes_since(
+        &self,
+        after: StubEntityChangeCursor,
+        limit: u64,
+    ) -> Result<Vec<StubEntityHistory>>;
+    /// A cursor past every change committed so far
+    async fn get_current_cursor(&self) -> Result<StubEntityChangeCursor>;
+}
@@ -0,0 +1,18 @@
+use crate::entities::stub_projection_domain_entity::StubEntityProjection;
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait StubEntityProjectionRepositoryPort: Send + Sync {
+    /// Applies `projection` unless `handler` already processed `message_id`, in which
+    /// case nothing changes and `false` is returned
+    async fn apply(
+        &self,
+        handler: &str,
+        message_id: &str,
+        projection: &StubEntityProjection,
+    ) -> Result<bool>;
+    async fn get(&self, entity_id: i32) -> Result<Option<StubEntityProjection>>;
+    /// Live (not deleted) projections whose `auto_ref` is `auto_ref`
+    async fn get_child_count(&self, auto_ref: i32) -> Result<i64>;
+}
@@ -1,14 +1,38 @@
-use crate::entities::stub_domain_entity::StubEntity;
+use crate::entities::{
+    page_domain_entity::Page,
+    stub_domain_entity::{StubEntity, StubEntityFilter},
+    stub_enrichment_domain_entity::PendingEnrichment,
+    stub_search_domain_entity::{StubEntitySearch, StubEntitySearchHit},
+    stub_tree_domain_entity::StubEntityNode,
+};
+use std::collections::HashSet;
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
 
 use super::transaction_port::TransactionPort;
 
 #[async_trait]
 pub trait StubEntityRepositoryPort: Send + Sync {
     async fn add(&self, entity: &StubEntity) -> Result<StubEntity>;
-    async fn get(&self, id: i32) -> Result<Option<StubEntity>>;
-    async fn get_within_transaction(&self, id: i32, txn: &Box<dyn TransactionPort>) -> Result<Option<StubEntity>>;
-    async fn update_within_transaction(&self, entity: &StubEntity, txn: &Box<dyn TransactionPort>) -> Result<StubEntity>;
-    async fn get_all(&self) -> Result<Vec<StubEntity>>;
+    async fn add_within_transaction(&self, entity: &StubEntity, txn: &dyn TransactionPort) -> Result<StubEntity>;
+    async fn add_many(&self, entities: &[StubEntity]) -> Result<Vec<StubEntity>>;
+    async fn get_existing_ids(&self, ids: &[i32]) -> Result<HashSet<i32>>;
+    async fn get(&self, id: i32, include_deleted: bool) -> Result<Option<StubEntity>>;
+    async fn get_within_transaction(&self, id: i32, include_deleted: bool, txn: &dyn TransactionPort) -> Result<Option<StubEntity>>;
+    async fn update_within_transaction(&self, entity: &StubEntity, txn: &dyn TransactionPort) -> Result<StubEntity>;
+    async fn delete_within_transaction(&self, id: i32, txn: &dyn TransactionPort) -> Result<Option<StubEntity>>;
+    async fn restore_within_transaction(&self, id: i32, txn: &dyn TransactionPort) -> Result<Option<StubEntity>>;
+    async fn get_all(&self, filter: &StubEntityFilter) -> Result<Vec<StubEntity>>;
+    /// Same rows as `get_all`, ordered by id and read incrementally instead of all at once
+    async fn stream_all(&self, filter: &StubEntityFilter) -> Result<BoxStream<'static, Result<StubEntity>>>;
+    /// Most relevant matches first; without `text` every match ranks equally and comes in id order
+    async fn search(&self, search: &StubEntitySearch) -> Result<Page<StubEntitySearchHit>>;
+    async fn get_children(&self, id: i32) -> Result<Vec<StubEntityNode>>;
+    async fn get_descendants(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>>;
+    async fn get_ancestors(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>>;
+    async fn purge_deleted(&self, deleted_before: DateTime<Utc>, batch_size: u64) -> Result<u64>;
+    /// Live entities of every tenant still waiting for enrichment, longest waiting first
+    async fn get_pending_enrichments(&self, batch_size: u64) -> Result<Vec<PendingEnrichment>>;
 }
@@ -0,0 +1,247 @@
+use std::{
+    collections::HashSet,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    entities::{
+        page_domain_entity::Page,
+        stub_domain_entity::{StubEntity, StubEntityFilter},
+        stub_enrichment_domain_entity::PendingEnrichment,
+        stub_search_domain_entity::{StubEntitySearch, StubEntitySearchHit},
+        stub_tree_domain_entity::StubEntityNode,
+    },
+    ports::repositories::{
+        stub_entity_repository_port::StubEntityRepositoryPort, transaction_port::TransactionPort,
+    },
+};
+use futures::stream::BoxStream;
+use lru::LruCache;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::database::notifications::stub_entity_change_listener::StubEntityChangeNotification;
+use crate::logging::logging_task_local::current_tenant_id;
+
+type CacheKey = (String, i32);
+
+struct CachedEntity {
+    entity: StubEntity,
+    cached_at: Instant,
+}
+
+/// Bounded LRU of live entities, each kept for at most `ttl`
+struct StubEntityCache {
+    entries: Mutex<LruCache<CacheKey, CachedEntity>>,
+    ttl: Duration,
+    /// Bumped on every invalidation, so a read that started before one does not cache what it read
+    generation: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl StubEntityCache {
+    fn get(&self, key: &CacheKey) -> Option<StubEntity> {
+        let entity = {
+            let mut entries = self.entries.lock().unwrap();
+            match entries.get(key) {
+                Some(cached) if cached.cached_at.elapsed() < self.ttl => Some(cached.entity.clone()),
+                Some(_) => {
+                    entries.pop(key);
+                    None
+                }
+                None => None,
+            }
+        };
+        self.record(entity.is_some());
+        entity
+    }
+
+    fn put(&self, key: CacheKey, entity: StubEntity, generation: u64) {
+        let mut entries = self.entries.lock().unwrap();
+        if self.generation.load(Ordering::SeqCst) == generation {
+            entries.put(
+                key,
+                CachedEntity {
+                    entity,
+                    cached_at: Instant::now(),
+                },
+            );
+        }
+    }
+
+    fn invalidate(&self, key: &CacheKey) {
+        let mut entries = self.entries.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        entries.pop(key);
+    }
+
+    fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        entries.clear();
+    }
+
+    fn record(&self, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        metrics::counter!("stub_entity_cache_requests_total", "result" => result).increment(1);
+
+        let hits = if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed) + 1
+        } else {
+            self.hits.load(Ordering::Relaxed)
+        };
+        let misses = if hit {
+            self.misses.load(Ordering::Relaxed)
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed) + 1
+        };
+        metrics::gauge!("stub_entity_cache_hit_ratio").set(hits as f64 / (hits + misses) as f64);
+    }
+}
+
+/// Caches `get` of live entities in front of another `StubEntityRepositoryPort`.
+/// Writes through this instance evict their entity right away; writes committed by any instance
+/// evict it again once their change notification arrives, which also covers reads that cached
+/// the old row between the write and its commit.
+pub struct StubEntityCachingRepository {
+    inner: Arc<dyn StubEntityRepositoryPort>,
+    cache: Arc<StubEntityCache>,
+}
+
+impl StubEntityCachingRepository {
+    pub fn new(
+        inner: Arc<dyn StubEntityRepositoryPort>,
+        capacity: NonZeroUsize,
+        ttl: Duration,
+        changes: broadcast::Receiver<StubEntityChangeNotification>,
+    ) -> Self {
+        let cache = Arc::new(StubEntityCache {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        });
+
+        tokio::spawn(invalidate_on_change(cache.clone(), changes));
+
+        Self { inner, cache }
+    }
+
+    fn evict(&self, id: Option<i32>) {
+        if let Some(id) = id {
+            self.cache.invalidate(&(current_tenant_id(), id));
+        }
+    }
+}
+
+async fn invalidate_on_change(
+    cache: Arc<StubEntityCache>,
+    mut changes: broadcast::Receiver<StubEntityChangeNotification>,
+) {
+    loop {
+        match changes.recv().await {
+            Ok(change) => cache.invalidate(&(change.tenant_id, change.entity_id)),
+            // Missed notifications could be for any entity
+            Err(RecvError::Lagged(_)) => cache.clear(),
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+#[async_trait]
+impl StubEntityRepositoryPort for StubEntityCachingRepository {
+    async fn add(&self, entity: &StubEntity) -> Result<StubEntity> {
+        self.inner.add(entity).await
+    }
+
+    async fn add_within_transaction(&self, entity: &StubEntity, txn: &dyn TransactionPort) -> Result<StubEntity> {
+        self.inner.add_within_transaction(entity, txn).await
+    }
+
+    async fn add_many(&self, entities: &[StubEntity]) -> Result<Vec<StubEntity>> {
+        self.inner.add_many(entities).await
+    }
+
+    async fn get_existing_ids(&self, ids: &[i32]) -> Result<HashSet<i32>> {
+        self.inner.get_existing_ids(ids).await
+    }
+
+    async fn get(&self, id: i32, include_deleted: bool) -> Result<Option<StubEntity>> {
+        if include_deleted {
+            return self.inner.get(id, include_deleted).await;
+        }
+
+        let key = (current_tenant_id(), id);
+        if let Some(entity) = self.cache.get(&key) {
+            return Ok(Some(entity));
+        }
+
+        let generation = self.cache.generation.load(Ordering::SeqCst);
+        let entity = self.inner.get(id, include_deleted).await?;
+        if let Some(entity) = &entity {
+            self.cache.put(key, entity.clone(), generation);
+        }
+        Ok(entity)
+    }
+
+    async fn get_within_transaction(&self, id: i32, include_deleted: bool, txn: &dyn TransactionPort) -> Result<Option<StubEntity>> {
+        self.inner.get_within_transaction(id, include_deleted, txn).await
+    }
+
+    async fn update_within_transaction(&self, entity: &StubEntity, txn: &dyn TransactionPort) -> Result<StubEntity> {
+        self.evict(entity.id);
+        self.inner.update_within_transaction(entity, txn).await
+    }
+
+    async fn delete_within_transaction(&self, id: i32, txn: &dyn TransactionPort) -> Result<Option<StubEntity>> {
+        self.evict(Some(id));
+        self.inner.delete_within_transaction(id, txn).await
+    }
+
+    async fn restore_within_transaction(&self, id: i32, txn: &dyn TransactionPort) -> Result<Option<StubEntity>> {
+        self.evict(Some(id));
+        self.inner.restore_within_transaction(id, txn).await
+    }
+
+    async fn get_all(&self, filter: &StubEntityFilter) -> Result<Vec<StubEntity>> {
+        self.inner.get_all(filter).await
+    }
+
+    async fn stream_all(&self, filter: &StubEntityFilter) -> Result<BoxStream<'static, Result<StubEntity>>> {
+        self.inner.stream_all(filter).await
+    }
+
+    async fn search(&self, search: &StubEntitySearch) -> Result<Page<StubEntitySearchHit>> {
+        self.inner.search(search).await
+    }
+
+    async fn get_children(&self, id: i32) -> Result<Vec<StubEntityNode>> {
+        self.inner.get_children(id).await
+    }
+
+    async fn get_descendants(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>> {
+        self.inner.get_descendants(id, max_depth).await
+    }
+
+    async fn get_ancestors(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>> {
+        self.inner.get_ancestors(id, max_depth).await
+    }
+
+    // Only deleted entities are purged and those are never cached
+    async fn purge_deleted(&self, deleted_before: DateTime<Utc>, batch_size: u64) -> Result<u64> {
+        self.inner.purge_deleted(deleted_before, batch_size).await
+    }
+
+    async fn get_pending_enrichments(&self, batch_size: u64) -> Result<Vec<PendingEnrichment>> {
+        self.inner.get_pending_enrichments(batch_size).await
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use domain::entities::api_key_domain_entity::ApiKey;
+use sea_orm::{
+    prelude::{async_trait::async_trait, DateTimeUtc, Json},
+    ActiveModelBehavior, ActiveValue, DeriveEntityModel, DerivePrimaryKey, DeriveRelation,
+    EnumIter, PrimaryKeyTrait,
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "api_key")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub name: String,
+    pub tenant_id: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Json,
+    pub expires_at: Option<DateTimeUtc>,
+    pub revoked_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+impl Model {
+    pub fn to_domain(&self) -> Result<ApiKey> {
+        let scopes: Vec<String> = serde_json::from_value(self.scopes.clone())?;
+
+        Ok(ApiKey {
+            id: Some(self.id),
+            name: self.name.clone(),
+            tenant_id: self.tenant_id.clone(),
+            prefix: self.prefix.clone(),
+            key_hash: self.key_hash.clone(),
+            scopes,
+            expires_at: self.expires_at,
+            revoked_at: self.revoked_at,
+            created_at: self.created_at,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    pub fn from_domain(api_key: &ApiKey) -> Self {
+        ActiveModel {
+            id: ActiveValue::NotSet,
+            name: ActiveValue::Set(api_key.name.clone()),
+            tenant_id: ActiveValue::Set(api_key.tenant_id.clone()),
+            prefix: ActiveValue::Set(api_key.prefix.clone()),
+            key_hash: ActiveValue::Set(api_key.key_hash.clone()),
+            scopes: ActiveValue::Set(serde_json::json!(api_key.scopes)),
+            expires_at: ActiveValue::Set(api_key.expires_at),
+            revoked_at: ActiveValue::Set(api_key.revoked_at),
+            created_at: ActiveValue::Set(api_key.created_at),
+        }
+    }
+}
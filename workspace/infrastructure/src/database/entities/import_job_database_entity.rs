@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use domain::entities::import_job_domain_entity::{ImportFormat, ImportJob, ImportJobStatus};
+use sea_orm::{
+    prelude::{async_trait::async_trait, DateTimeUtc},
+    ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EnumIter,
+    PrimaryKeyTrait,
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "import_job")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub tenant_id: String,
+    pub format: String,
+    pub status: String,
+    pub actor: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+    pub finished_at: Option<DateTimeUtc>,
+    pub claimed_by: Option<String>,
+    pub lease_until: Option<DateTimeUtc>,
+}
+
+impl Model {
+    pub fn to_domain(&self) -> Result<ImportJob> {
+        Ok(ImportJob {
+            id: self.id,
+            tenant_id: self.tenant_id.clone(),
+            format: ImportFormat::from_str(&self.format)?,
+            status: ImportJobStatus::from_str(&self.status)?,
+            actor: self.actor.clone(),
+            error: self.error.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            finished_at: self.finished_at,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,53 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use domain::entities::import_job_domain_entity::{ImportLine, ImportLineStatus};
+use sea_orm::{
+    prelude::{async_trait::async_trait, Json},
+    ActiveModelBehavior, ActiveValue, DeriveEntityModel, DerivePrimaryKey, DeriveRelation,
+    EnumIter, PrimaryKeyTrait,
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "import_job_line")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub job_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub line_number: i32,
+    pub content: Option<Json>,
+    pub status: String,
+    pub entity_id: Option<i32>,
+    pub error: Option<String>,
+}
+
+impl Model {
+    pub fn to_domain(&self) -> Result<ImportLine> {
+        Ok(ImportLine {
+            line_number: self.line_number,
+            content: self.content.clone(),
+            status: ImportLineStatus::from_str(&self.status)?,
+            entity_id: self.entity_id,
+            error: self.error.clone(),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    pub fn from_domain(job_id: i32, line: &ImportLine) -> Self {
+        ActiveModel {
+            job_id: ActiveValue::Set(job_id),
+            line_number: ActiveValue::Set(line.line_number),
+            content: ActiveValue::Set(line.content.clone()),
+            status: ActiveValue::Set(line.status.as_str().to_string()),
+            entity_id: ActiveValue::Set(line.entity_id),
+            error: ActiveValue::Set(line.error.clone()),
+        }
+    }
+}
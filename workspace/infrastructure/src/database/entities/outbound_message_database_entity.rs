@@ -0,0 +1,45 @@
+use domain::ports::{
+    messaging::messaging_service_port::OutboundMessage,
+    repositories::outbound_message_repository_port::QueuedOutboundMessage,
+};
+use sea_orm::{
+    prelude::{async_trait::async_trait, DateTimeUtc},
+    ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EnumIter,
+    PrimaryKeyTrait,
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "outbound_message")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i64,
+    pub tenant_id: String,
+    pub partition_id: String,
+    pub deduplication_id: String,
+    pub body: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+impl Model {
+    pub fn to_domain(&self) -> QueuedOutboundMessage {
+        QueuedOutboundMessage {
+            id: self.id,
+            tenant_id: self.tenant_id.clone(),
+            message: OutboundMessage {
+                partition_id: self.partition_id.clone(),
+                deduplication_id: self.deduplication_id.clone(),
+                body: self.body.clone(),
+            },
+            attempts: self.attempts,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {}
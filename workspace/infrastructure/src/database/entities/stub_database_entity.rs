@@ -1,6 +1,7 @@
 use domain::entities::stub_domain_entity::StubEntity;
 use sea_orm::{
-    prelude::async_trait::async_trait, ActiveModelBehavior, ActiveValue, DeriveEntityModel,
+    prelude::{async_trait::async_trait, DateTimeUtc},
+    ActiveModelBehavior, ActiveValue, DeriveEntityModel,
     DerivePrimaryKey, EntityTrait, EnumIter, FromJsonQueryResult, PrimaryKeyTrait, Related,
     RelationDef, RelationTrait,
 };
@@ -14,6 +15,10 @@ pub struct Model {
     pub name: String,
     pub value: KeyValue,
     pub auto_ref: Option<i32>,
+    pub deleted_at: Option<DateTimeUtc>,
+    pub tenant_id: String,
+    pub enrichment_pending_since: Option<DateTimeUtc>,
+    pub version: i32,
 }
 
 impl Model {
@@ -26,6 +31,9 @@ impl Model {
                 name: self.value.name.clone(),
             },
             auto_ref: self.auto_ref,
+            deleted_at: self.deleted_at,
+            enrichment_pending_since: self.enrichment_pending_since,
+            version: self.version,
         }
     }
 }
@@ -59,7 +67,7 @@ pub struct KeyValue {
 impl ActiveModelBehavior for ActiveModel {}
 
 impl ActiveModel {
-    pub fn from_domain(entity: &StubEntity, set_id: bool) -> Self {
+    pub fn from_domain(entity: &StubEntity, set_id: bool, tenant_id: &str) -> Self {
         ActiveModel {
             id: if set_id {
                 ActiveValue::Set(entity.id.unwrap_or_default())
@@ -72,6 +80,10 @@ impl ActiveModel {
                 name: entity.value.name.clone(),
             }),
             auto_ref: ActiveValue::Set(entity.auto_ref),
+            deleted_at: ActiveValue::Set(entity.deleted_at),
+            tenant_id: ActiveValue::Set(tenant_id.to_string()),
+            enrichment_pending_since: ActiveValue::Set(entity.enrichment_pending_since),
+            version: ActiveValue::NotSet,
         }
     }
 }
@@ -87,6 +99,10 @@ mod tests {
             name: "Test".to_string(),
             value: KeyValue { id: 1, name: "Value".to_string() },
             auto_ref: Some(2),
+            deleted_at: None,
+            tenant_id: "default".to_string(),
+            enrichment_pending_since: None,
+            version: 3,
         };
 
         let domain_entity = model.to_domain();
@@ -96,6 +112,8 @@ mod tests {
         assert_eq!(domain_entity.value.id, 1);
         assert_eq!(domain_entity.value.name, "Value");
         assert_eq!(domain_entity.auto_ref, Some(2));
+        assert_eq!(domain_entity.deleted_at, None);
+        assert_eq!(domain_entity.version, 3);
     }
 
     #[test]
@@ -105,14 +123,19 @@ mod tests {
             name: "Test".to_string(),
             value: domain::entities::stub_domain_entity::KeyValue { id: 1, name: "Value".to_string() },
             auto_ref: Some(2),
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 0,
         };
 
-        let active_model = ActiveModel::from_domain(&domain_entity, true);
+        let active_model = ActiveModel::from_domain(&domain_entity, true, "tenant-a");
 
         assert_eq!(active_model.id, ActiveValue::Set(1));
         assert_eq!(active_model.name, ActiveValue::Set("Test".to_string()));
         assert_eq!(active_model.value, ActiveValue::Set(KeyValue { id: 1, name: "Value".to_string() }));
         assert_eq!(active_model.auto_ref, ActiveValue::Set(Some(2)));
+        assert_eq!(active_model.deleted_at, ActiveValue::Set(None));
+        assert_eq!(active_model.tenant_id, ActiveValue::Set("tenant-a".to_string()));
     }
 
     #[test]
@@ -122,13 +145,18 @@ mod tests {
             name: "Test".to_string(),
             value: domain::entities::stub_domain_entity::KeyValue { id: 1, name: "Value".to_string() },
             auto_ref: Some(2),
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 0,
         };
 
-        let active_model = ActiveModel::from_domain(&domain_entity, false);
+        let active_model = ActiveModel::from_domain(&domain_entity, false, "tenant-a");
 
         assert_eq!(active_model.id, ActiveValue::NotSet);
         assert_eq!(active_model.name, ActiveValue::Set("Test".to_string()));
         assert_eq!(active_model.value, ActiveValue::Set(KeyValue { id: 1, name: "Value".to_string() }));
         assert_eq!(active_model.auto_ref, ActiveValue::Set(Some(2)));
+        assert_eq!(active_model.deleted_at, ActiveValue::Set(None));
+        assert_eq!(active_model.tenant_id, ActiveValue::Set("tenant-a".to_string()));
     }
 }
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use domain::entities::stub_history_domain_entity::{
+    StubEntityHistory, StubEntityHistoryOperation,
+};
+use sea_orm::{
+    prelude::{async_trait::async_trait, DateTimeUtc, Json},
+    ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation,
+    EnumIter, PrimaryKeyTrait,
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "stub_entity_history")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i64,
+    pub entity_id: i32,
+    pub operation: String,
+    pub before: Option<Json>,
+    pub after: Option<Json>,
+    pub changed_fields: Json,
+    pub actor: Option<String>,
+    pub correlation_id: Option<String>,
+    pub changed_at: DateTimeUtc,
+    pub tenant_id: String,
+    pub transaction_id: i64,
+}
+
+impl Model {
+    pub fn to_domain(&self) -> Result<StubEntityHistory> {
+        let changed_fields: Vec<String> = serde_json::from_value(self.changed_fields.clone())?;
+
+        Ok(StubEntityHistory {
+            id: self.id,
+            transaction_id: self.transaction_id,
+            entity_id: self.entity_id,
+            operation: StubEntityHistoryOperation::from_str(&self.operation)?,
+            before: self.before.clone(),
+            after: self.after.clone(),
+            changed_fields,
+            actor: self.actor.clone(),
+            correlation_id: self.correlation_id.clone(),
+            changed_at: self.changed_at,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_model_to_domain() {
+        let changed_at = Utc::now();
+        let model = Model {
+            id: 10,
+            entity_id: 1,
+            operation: "UPDATE".to_string(),
+            before: Some(json!({ "name": "before" })),
+            after: Some(json!({ "name": "after" })),
+            changed_fields: json!(["name"]),
+            actor: Some("actor".to_string()),
+            correlation_id: Some("correlation".to_string()),
+            changed_at,
+            tenant_id: "default".to_string(),
+            transaction_id: 1,
+        };
+
+        let history = model.to_domain().unwrap();
+
+        assert_eq!(history.id, 10);
+        assert_eq!(history.entity_id, 1);
+        assert_eq!(history.operation, StubEntityHistoryOperation::Update);
+        assert_eq!(history.changed_fields, vec!["name".to_string()]);
+        assert_eq!(history.actor, Some("actor".to_string()));
+        assert_eq!(history.changed_at, changed_at);
+    }
+
+    #[test]
+    fn test_model_to_domain_with_unknown_operation() {
+        let model = Model {
+            id: 10,
+            entity_id: 1,
+            operation: "UNKNOWN".to_string(),
+            before: None,
+            after: None,
+            changed_fields: json!([]),
+            actor: None,
+            correlation_id: None,
+            changed_at: Utc::now(),
+            tenant_id: "default".to_string(),
+            transaction_id: 1,
+        };
+
+        assert!(model.to_domain().is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use domain::entities::stub_projection_domain_entity::StubEntityProjection;
+use sea_orm::{
+    prelude::{async_trait::async_trait, DateTimeUtc},
+    ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation,
+    EnumIter, PrimaryKeyTrait,
+};
+
+use super::stub_database_entity::KeyValue;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "stub_entity_projection")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub tenant_id: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub entity_id: i32,
+    pub name: String,
+    pub value: KeyValue,
+    pub auto_ref: Option<i32>,
+    pub deleted_at: Option<DateTimeUtc>,
+    pub event_time: DateTimeUtc,
+    pub entity_version: i32,
+}
+
+impl Model {
+    pub fn to_domain(&self) -> StubEntityProjection {
+        StubEntityProjection {
+            entity_id: self.entity_id,
+            name: self.name.clone(),
+            value: domain::entities::stub_domain_entity::KeyValue {
+                id: self.value.id,
+                name: self.value.name.clone(),
+            },
+            auto_ref: self.auto_ref,
+            deleted_at: self.deleted_at,
+            event_time: self.event_time,
+            entity_version: self.entity_version,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241203_000001_create_stub_entity_history_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StubEntityHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StubEntityHistory::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StubEntityHistory::EntityId).integer().not_null())
+                    .col(ColumnDef::new(StubEntityHistory::Operation).string_len(16).not_null())
+                    .col(ColumnDef::new(StubEntityHistory::Before).json_binary())
+                    .col(ColumnDef::new(StubEntityHistory::After).json_binary())
+                    .col(
+                        ColumnDef::new(StubEntityHistory::ChangedFields)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StubEntityHistory::Actor).string())
+                    .col(ColumnDef::new(StubEntityHistory::CorrelationId).string())
+                    .col(
+                        ColumnDef::new(StubEntityHistory::ChangedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-stub-entity-history-entity-changed-at")
+                    .table(StubEntityHistory::Table)
+                    .col(StubEntityHistory::EntityId)
+                    .col(StubEntityHistory::ChangedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StubEntityHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum StubEntityHistory {
+    Table,
+    Id,
+    EntityId,
+    Operation,
+    Before,
+    After,
+    ChangedFields,
+    Actor,
+    CorrelationId,
+    ChangedAt,
+}
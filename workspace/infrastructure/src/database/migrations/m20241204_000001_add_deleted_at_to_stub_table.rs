@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241204_000001_add_deleted_at_to_stub_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StubEntity::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(StubEntity::DeletedAt).timestamp_with_time_zone(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-stub-table-deleted-at")
+                    .table(StubEntity::Table)
+                    .col(StubEntity::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-stub-table-deleted-at")
+                    .table(StubEntity::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StubEntity::Table)
+                    .drop_column(StubEntity::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum StubEntity {
+    Table,
+    DeletedAt,
+}
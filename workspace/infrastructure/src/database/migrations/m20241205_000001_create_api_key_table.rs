@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241205_000001_create_api_key_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiKey::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiKey::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApiKey::Name).string().not_null().unique_key())
+                    .col(
+                        ColumnDef::new(ApiKey::Prefix)
+                            .string_len(16)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ApiKey::KeyHash).string_len(64).not_null())
+                    .col(ColumnDef::new(ApiKey::Scopes).json_binary().not_null())
+                    .col(ColumnDef::new(ApiKey::ExpiresAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(ApiKey::RevokedAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(ApiKey::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiKey::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ApiKey {
+    Table,
+    Id,
+    Name,
+    Prefix,
+    KeyHash,
+    Scopes,
+    ExpiresAt,
+    RevokedAt,
+    CreatedAt,
+}
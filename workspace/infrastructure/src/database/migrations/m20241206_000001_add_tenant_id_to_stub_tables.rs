@@ -0,0 +1,117 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241206_000001_add_tenant_id_to_stub_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StubEntity::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(StubEntity::TenantId)
+                            .string_len(64)
+                            .not_null()
+                            .default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StubEntityHistory::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(StubEntityHistory::TenantId)
+                            .string_len(64)
+                            .not_null()
+                            .default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-stub-table-tenant-auto-ref")
+                    .table(StubEntity::Table)
+                    .col(StubEntity::TenantId)
+                    .col(StubEntity::AutoRef)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-stub-entity-history-tenant-entity")
+                    .table(StubEntityHistory::Table)
+                    .col(StubEntityHistory::TenantId)
+                    .col(StubEntityHistory::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-stub-entity-history-tenant-entity")
+                    .table(StubEntityHistory::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-stub-table-tenant-auto-ref")
+                    .table(StubEntity::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StubEntityHistory::Table)
+                    .drop_column(StubEntityHistory::TenantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StubEntity::Table)
+                    .drop_column(StubEntity::TenantId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum StubEntity {
+    Table,
+    TenantId,
+    AutoRef,
+}
+
+#[derive(Iden)]
+pub enum StubEntityHistory {
+    Table,
+    TenantId,
+    EntityId,
+}
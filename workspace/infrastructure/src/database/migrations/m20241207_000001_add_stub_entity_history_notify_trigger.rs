@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241207_000001_add_stub_entity_history_notify_trigger"
+    }
+}
+
+/// Notifications are only delivered once the transaction that wrote the history row commits,
+/// and Postgres folds identical payloads raised within one transaction into a single one
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION notify_stub_entity_change() RETURNS trigger AS $$
+                BEGIN
+                    PERFORM pg_notify('stub_entity_changes', NEW.tenant_id);
+                    RETURN NEW;
+                END;
+                $$ LANGUAGE plpgsql;
+
+                CREATE TRIGGER "trg-stub-entity-history-notify"
+                AFTER INSERT ON stub_entity_history
+                FOR EACH ROW EXECUTE FUNCTION notify_stub_entity_change();
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DROP TRIGGER IF EXISTS "trg-stub-entity-history-notify" ON stub_entity_history;
+                DROP FUNCTION IF EXISTS notify_stub_entity_change();
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
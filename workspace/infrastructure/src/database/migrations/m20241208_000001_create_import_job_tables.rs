@@ -0,0 +1,119 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241208_000001_create_import_job_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportJob::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportJob::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImportJob::TenantId).string_len(64).not_null())
+                    .col(ColumnDef::new(ImportJob::Format).string_len(16).not_null())
+                    .col(ColumnDef::new(ImportJob::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(ImportJob::Actor).string())
+                    .col(ColumnDef::new(ImportJob::Error).text())
+                    .col(
+                        ColumnDef::new(ImportJob::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImportJob::FinishedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportJobLine::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ImportJobLine::JobId).integer().not_null())
+                    .col(ColumnDef::new(ImportJobLine::LineNumber).integer().not_null())
+                    .col(ColumnDef::new(ImportJobLine::Content).json_binary())
+                    .col(ColumnDef::new(ImportJobLine::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(ImportJobLine::EntityId).integer())
+                    .col(ColumnDef::new(ImportJobLine::Error).text())
+                    .primary_key(
+                        Index::create()
+                            .col(ImportJobLine::JobId)
+                            .col(ImportJobLine::LineNumber),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-import-job-line-job")
+                            .from(ImportJobLine::Table, ImportJobLine::JobId)
+                            .to(ImportJob::Table, ImportJob::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-import-job-status")
+                    .table(ImportJob::Table)
+                    .col(ImportJob::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportJobLine::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ImportJob::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ImportJob {
+    Table,
+    Id,
+    TenantId,
+    Format,
+    Status,
+    Actor,
+    Error,
+    CreatedAt,
+    UpdatedAt,
+    FinishedAt,
+}
+
+#[derive(Iden)]
+pub enum ImportJobLine {
+    Table,
+    JobId,
+    LineNumber,
+    Content,
+    Status,
+    EntityId,
+    Error,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241209_000001_add_stub_entity_search"
+    }
+}
+
+/// The `simple` configuration keeps words unstemmed, so prefix queries match what users typed.
+/// Matches in `name` rank above matches in the string fields of `value`.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                ALTER TABLE stub_entity ADD COLUMN IF NOT EXISTS search_vector tsvector
+                GENERATED ALWAYS AS (
+                    setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+                    setweight(jsonb_to_tsvector('simple', coalesce(value, '{}'::jsonb), '["string"]'), 'B')
+                ) STORED;
+
+                CREATE INDEX IF NOT EXISTS "idx-stub-table-search-vector"
+                ON stub_entity USING GIN (search_vector);
+
+                CREATE INDEX IF NOT EXISTS "idx-stub-table-value"
+                ON stub_entity USING GIN (value jsonb_path_ops);
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DROP INDEX IF EXISTS "idx-stub-table-value";
+                DROP INDEX IF EXISTS "idx-stub-table-search-vector";
+                ALTER TABLE stub_entity DROP COLUMN IF EXISTS search_vector;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
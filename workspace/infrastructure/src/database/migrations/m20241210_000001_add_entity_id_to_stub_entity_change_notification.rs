@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241210_000001_add_entity_id_to_stub_entity_change_notification"
+    }
+}
+
+/// Carries the entity id next to the tenant so listeners can act on a single entity
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION notify_stub_entity_change() RETURNS trigger AS $$
+                BEGIN
+                    PERFORM pg_notify(
+                        'stub_entity_changes',
+                        json_build_object('tenant_id', NEW.tenant_id, 'entity_id', NEW.entity_id)::text
+                    );
+                    RETURN NEW;
+                END;
+                $$ LANGUAGE plpgsql;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION notify_stub_entity_change() RETURNS trigger AS $$
+                BEGIN
+                    PERFORM pg_notify('stub_entity_changes', NEW.tenant_id);
+                    RETURN NEW;
+                END;
+                $$ LANGUAGE plpgsql;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
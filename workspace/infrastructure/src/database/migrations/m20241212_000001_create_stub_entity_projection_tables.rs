@@ -0,0 +1,134 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241212_000001_create_stub_entity_projection_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProcessedMessage::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ProcessedMessage::TenantId).string_len(64).not_null())
+                    .col(ColumnDef::new(ProcessedMessage::Handler).string_len(64).not_null())
+                    .col(ColumnDef::new(ProcessedMessage::MessageId).string().not_null())
+                    .col(
+                        ColumnDef::new(ProcessedMessage::ProcessedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ProcessedMessage::TenantId)
+                            .col(ProcessedMessage::Handler)
+                            .col(ProcessedMessage::MessageId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(StubEntityProjection::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(StubEntityProjection::TenantId).string_len(64).not_null())
+                    .col(ColumnDef::new(StubEntityProjection::EntityId).integer().not_null())
+                    .col(ColumnDef::new(StubEntityProjection::Name).string().not_null())
+                    .col(ColumnDef::new(StubEntityProjection::Value).json_binary().not_null())
+                    .col(ColumnDef::new(StubEntityProjection::AutoRef).integer())
+                    .col(ColumnDef::new(StubEntityProjection::DeletedAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(StubEntityProjection::EventTime)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(StubEntityProjection::TenantId)
+                            .col(StubEntityProjection::EntityId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-stub-entity-projection-auto-ref")
+                    .table(StubEntityProjection::Table)
+                    .col(StubEntityProjection::TenantId)
+                    .col(StubEntityProjection::AutoRef)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(StubEntityChildCount::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(StubEntityChildCount::TenantId).string_len(64).not_null())
+                    .col(ColumnDef::new(StubEntityChildCount::AutoRef).integer().not_null())
+                    .col(ColumnDef::new(StubEntityChildCount::Children).big_integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(StubEntityChildCount::TenantId)
+                            .col(StubEntityChildCount::AutoRef),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StubEntityChildCount::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(StubEntityProjection::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ProcessedMessage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ProcessedMessage {
+    Table,
+    TenantId,
+    Handler,
+    MessageId,
+    ProcessedAt,
+}
+
+#[derive(Iden)]
+pub enum StubEntityProjection {
+    Table,
+    TenantId,
+    EntityId,
+    Name,
+    Value,
+    AutoRef,
+    DeletedAt,
+    EventTime,
+}
+
+#[derive(Iden)]
+pub enum StubEntityChildCount {
+    Table,
+    TenantId,
+    AutoRef,
+    Children,
+}
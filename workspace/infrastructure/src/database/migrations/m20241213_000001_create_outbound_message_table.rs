@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241213_000001_create_outbound_message_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OutboundMessage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OutboundMessage::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OutboundMessage::TenantId).string_len(64).not_null())
+                    .col(ColumnDef::new(OutboundMessage::PartitionId).string().not_null())
+                    .col(ColumnDef::new(OutboundMessage::DeduplicationId).string().not_null())
+                    .col(ColumnDef::new(OutboundMessage::Body).text().not_null())
+                    .col(
+                        ColumnDef::new(OutboundMessage::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(OutboundMessage::LastError).text())
+                    .col(ColumnDef::new(OutboundMessage::NextAttemptAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(OutboundMessage::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-outbound-message-next-attempt-at")
+                    .table(OutboundMessage::Table)
+                    .col(OutboundMessage::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OutboundMessage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum OutboundMessage {
+    Table,
+    Id,
+    TenantId,
+    PartitionId,
+    DeduplicationId,
+    Body,
+    Attempts,
+    LastError,
+    NextAttemptAt,
+    CreatedAt,
+}
@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241214_000001_add_tenant_id_to_api_key_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ApiKey::TenantId)
+                            .string_len(64)
+                            .not_null()
+                            .default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .drop_column(ApiKey::TenantId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ApiKey {
+    Table,
+    TenantId,
+}
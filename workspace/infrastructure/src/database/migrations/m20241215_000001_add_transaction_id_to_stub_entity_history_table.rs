@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241215_000001_add_transaction_id_to_stub_entity_history_table"
+    }
+}
+
+/// Records the writing transaction so change feeds can page in commit order.
+/// The default is volatile, so existing rows all get the migration's own transaction id and keep their id order.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                ALTER TABLE stub_entity_history
+                ADD COLUMN IF NOT EXISTS transaction_id BIGINT NOT NULL
+                DEFAULT pg_current_xact_id()::text::bigint;
+
+                CREATE INDEX IF NOT EXISTS "idx-stub-entity-history-tenant-transaction-id"
+                ON stub_entity_history (tenant_id, transaction_id, id);
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DROP INDEX IF EXISTS "idx-stub-entity-history-tenant-transaction-id";
+                ALTER TABLE stub_entity_history DROP COLUMN IF EXISTS transaction_id;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
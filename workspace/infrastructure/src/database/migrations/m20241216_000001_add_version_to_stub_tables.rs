@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241216_000001_add_version_to_stub_tables"
+    }
+}
+
+/// The trigger increments the version after the row lock is taken, so concurrent writes of an
+/// entity get versions in commit order. Projections keep the version of the event they were built from.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                ALTER TABLE stub_entity ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
+
+                CREATE OR REPLACE FUNCTION increment_stub_entity_version() RETURNS trigger AS $$
+                BEGIN
+                    NEW.version := OLD.version + 1;
+                    RETURN NEW;
+                END;
+                $$ LANGUAGE plpgsql;
+
+                DROP TRIGGER IF EXISTS "trg-stub-entity-version" ON stub_entity;
+                CREATE TRIGGER "trg-stub-entity-version"
+                BEFORE UPDATE ON stub_entity
+                FOR EACH ROW EXECUTE FUNCTION increment_stub_entity_version();
+
+                ALTER TABLE stub_entity_projection
+                ADD COLUMN IF NOT EXISTS entity_version INTEGER NOT NULL DEFAULT 0;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                ALTER TABLE stub_entity_projection DROP COLUMN IF EXISTS entity_version;
+                DROP TRIGGER IF EXISTS "trg-stub-entity-version" ON stub_entity;
+                DROP FUNCTION IF EXISTS increment_stub_entity_version();
+                ALTER TABLE stub_entity DROP COLUMN IF EXISTS version;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241217_000001_add_partition_index_to_outbound_message_table"
+    }
+}
+
+/// Publishing looks up queued messages of the partitions it sends to, and the retry job
+/// looks for earlier messages of the partition of each due one
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-outbound-message-tenant-id-partition-id-id")
+                    .table(OutboundMessage::Table)
+                    .col(OutboundMessage::TenantId)
+                    .col(OutboundMessage::PartitionId)
+                    .col(OutboundMessage::Id)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-outbound-message-tenant-id-partition-id-id")
+                    .table(OutboundMessage::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum OutboundMessage {
+    Table,
+    Id,
+    TenantId,
+    PartitionId,
+}
@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241218_000001_add_lease_to_import_job_table"
+    }
+}
+
+/// A worker claims a job until `lease_until` and renews the lease while it runs, so jobs
+/// whose worker stopped are taken over once their lease lapses
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ImportJob::Table)
+                    .add_column_if_not_exists(ColumnDef::new(ImportJob::ClaimedBy).string())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ImportJob::LeaseUntil).timestamp_with_time_zone(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ImportJob::Table)
+                    .drop_column(ImportJob::ClaimedBy)
+                    .drop_column(ImportJob::LeaseUntil)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ImportJob {
+    Table,
+    ClaimedBy,
+    LeaseUntil,
+}
@@ -2,14 +2,47 @@ use anyhow::{bail, Result};
 use sea_orm::DatabaseConnection;
 use sea_orm_migration::prelude::*;
 
-use super::m20241126_000001_create_stub_table;
+use super::{
+    m20241126_000001_create_stub_table, m20241203_000001_create_stub_entity_history_table,
+    m20241204_000001_add_deleted_at_to_stub_table, m20241205_000001_create_api_key_table,
+    m20241206_000001_add_tenant_id_to_stub_tables,
+    m20241207_000001_add_stub_entity_history_notify_trigger,
+    m20241208_000001_create_import_job_tables, m20241209_000001_add_stub_entity_search,
+    m20241210_000001_add_entity_id_to_stub_entity_change_notification,
+    m20241211_000001_add_enrichment_pending_since_to_stub_table,
+    m20241212_000001_create_stub_entity_projection_tables,
+    m20241213_000001_create_outbound_message_table,
+    m20241214_000001_add_tenant_id_to_api_key_table,
+    m20241215_000001_add_transaction_id_to_stub_entity_history_table,
+    m20241216_000001_add_version_to_stub_tables,
+    m20241217_000001_add_partition_index_to_outbound_message_table,
+    m20241218_000001_add_lease_to_import_job_table,
+};
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20241126_000001_create_stub_table::Migration)]
+        vec![
+            Box::new(m20241126_000001_create_stub_table::Migration),
+            Box::new(m20241203_000001_create_stub_entity_history_table::Migration),
+            Box::new(m20241204_000001_add_deleted_at_to_stub_table::Migration),
+            Box::new(m20241205_000001_create_api_key_table::Migration),
+            Box::new(m20241206_000001_add_tenant_id_to_stub_tables::Migration),
+            Box::new(m20241207_000001_add_stub_entity_history_notify_trigger::Migration),
+            Box::new(m20241208_000001_create_import_job_tables::Migration),
+            Box::new(m20241209_000001_add_stub_entity_search::Migration),
+            Box::new(m20241210_000001_add_entity_id_to_stub_entity_change_notification::Migration),
+            Box::new(m20241211_000001_add_enrichment_pending_since_to_stub_table::Migration),
+            Box::new(m20241212_000001_create_stub_entity_projection_tables::Migration),
+            Box::new(m20241213_000001_create_outbound_message_table::Migration),
+            Box::new(m20241214_000001_add_tenant_id_to_api_key_table::Migration),
+            Box::new(m20241215_000001_add_transaction_id_to_stub_entity_history_table::Migration),
+            Box::new(m20241216_000001_add_version_to_stub_tables::Migration),
+            Box::new(m20241217_000001_add_partition_index_to_outbound_message_table::Migration),
+            Box::new(m20241218_000001_add_lease_to_import_job_table::Migration),
+        ]
     }
 }
 
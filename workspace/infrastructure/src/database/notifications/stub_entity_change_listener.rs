@@ -0,0 +1,76 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use serde::Deserialize;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use tracing::error;
+
+use crate::database::repositories::database_data::DatabaseConnection;
+
+/// Raised by the `stub_entity_history` insert trigger, with a JSON `StubEntityChangeNotification` as payload
+pub const STUB_ENTITY_CHANGES_CHANNEL: &str = "stub_entity_changes";
+
+const SUBSCRIBER_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StubEntityChangeNotification {
+    pub tenant_id: String,
+    pub entity_id: i32,
+}
+
+/// Holds a single `LISTEN` connection per process and fans its notifications out to subscribers.
+/// Notifications only say which entity has new history rows; subscribers read the rows themselves,
+/// so a lagging subscriber or a notification lost during a reconnect only delays delivery.
+#[derive(Debug)]
+pub struct StubEntityChangeListener {
+    sender: broadcast::Sender<StubEntityChangeNotification>,
+}
+
+impl StubEntityChangeListener {
+    pub async fn start(db: &Arc<DatabaseConnection<sea_orm::DatabaseConnection>>) -> Result<Arc<Self>> {
+        let mut listener = PgListener::connect_with(db.conn.get_postgres_connection_pool()).await?;
+        listener.listen(STUB_ENTITY_CHANGES_CHANNEL).await?;
+
+        let (sender, _) = broadcast::channel(SUBSCRIBER_CAPACITY);
+        let task_sender = sender.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => {
+                        match serde_json::from_str::<StubEntityChangeNotification>(
+                            notification.payload(),
+                        ) {
+                            // No subscribers is not an error, the notification is just not needed
+                            Ok(change) => {
+                                let _ = task_sender.send(change);
+                            }
+                            Err(err) => error!(
+                                app.name = %env!("CARGO_PKG_NAME"),
+                                app.version = %env!("CARGO_PKG_VERSION"),
+                                error_message = %err,
+                                "Unexpected stub entity change notification"
+                            ),
+                        }
+                    }
+                    Err(err) => {
+                        error!(
+                            app.name = %env!("CARGO_PKG_NAME"),
+                            app.version = %env!("CARGO_PKG_VERSION"),
+                            error_message = %err,
+                            "Stub entity change listener failed"
+                        );
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Arc::new(Self { sender }))
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StubEntityChangeNotification> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::database::entities::api_key_database_entity::*;
+use crate::logging::logging_task_local::current_tenant_id;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::{
+    entities::api_key_domain_entity::ApiKey, errors::domain_errors::DomainError,
+    ports::repositories::api_key_repository_port::ApiKeyRepositoryPort,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, SqlErr,
+};
+
+use super::database_data::DatabaseConnection;
+
+#[derive(Debug)]
+pub struct ApiKeySeaOrmPostgresRepository {
+    db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+}
+
+impl ApiKeySeaOrmPostgresRepository {
+    pub fn new(db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepositoryPort for ApiKeySeaOrmPostgresRepository {
+    #[tracing::instrument(skip_all, err)]
+    async fn add(&self, api_key: &ApiKey) -> Result<ApiKey> {
+        let active_model = ActiveModel::from_domain(api_key);
+
+        match active_model.insert(&self.db.conn).await {
+            Ok(inserted) => inserted.to_domain(),
+            Err(err) => match err.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => bail!(
+                    DomainError::UnprocessableEntity("API key name already exists".to_string())
+                ),
+                _ => bail!(err),
+            },
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_all(&self) -> Result<Vec<ApiKey>> {
+        let models = match Entity::find()
+            .filter(Column::TenantId.eq(current_tenant_id()))
+            .order_by_asc(Column::Id)
+            .all(&self.db.conn)
+            .await
+        {
+            Ok(models) => models,
+            Err(err) => bail!(err),
+        };
+
+        models.iter().map(|model| model.to_domain()).collect()
+    }
+
+    /// Not scoped to the current tenant, since the tenant is only known once the key is found
+    #[tracing::instrument(skip_all, err)]
+    async fn get_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>> {
+        let model = Entity::find()
+            .filter(Column::Prefix.eq(prefix))
+            .one(&self.db.conn)
+            .await;
+
+        match model {
+            Ok(Some(model)) => Ok(Some(model.to_domain()?)),
+            Ok(None) => Ok(None),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn revoke(&self, id: i32) -> Result<Option<ApiKey>> {
+        let model = match Entity::find_by_id(id)
+            .filter(Column::TenantId.eq(current_tenant_id()))
+            .filter(Column::RevokedAt.is_null())
+            .one(&self.db.conn)
+            .await
+        {
+            Ok(Some(model)) => model,
+            Ok(None) => return Ok(None),
+            Err(err) => bail!(err),
+        };
+
+        let mut active_model: ActiveModel = model.into();
+        active_model.revoked_at = ActiveValue::Set(Some(Utc::now()));
+
+        match active_model.update(&self.db.conn).await {
+            Ok(revoked) => Ok(Some(revoked.to_domain()?)),
+            Err(err) => bail!(err),
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::{str::FromStr, sync::Arc};
+
+use crate::database::entities::{import_job_database_entity, import_job_line_database_entity};
+use crate::logging::logging_task_local::{current_tenant_id, REQUEST_DATA};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    entities::import_job_domain_entity::{
+        ImportFormat, ImportJob, ImportJobCounts, ImportJobStatus, ImportLine, ImportLineStatus,
+    },
+    ports::repositories::{
+        import_job_repository_port::ImportJobRepositoryPort, transaction_port::TransactionPort,
+    },
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue, ColumnTrait, Condition, DatabaseTransaction,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+
+use super::database_data::{DatabaseConnection, Transaction};
+
+/// Keeps each multi-row insert well below the Postgres bind parameter limit
+const LINE_INSERT_CHUNK_SIZE: usize = 1000;
+
+/// Pending jobs, and running ones whose worker let the lease lapse
+fn claimable(now: DateTime<Utc>) -> Condition {
+    Condition::any()
+        .add(import_job_database_entity::Column::Status.eq(ImportJobStatus::Pending.as_str()))
+        .add(
+            Condition::all()
+                .add(import_job_database_entity::Column::Status.eq(ImportJobStatus::Running.as_str()))
+                .add(
+                    Condition::any()
+                        .add(import_job_database_entity::Column::LeaseUntil.is_null())
+                        .add(import_job_database_entity::Column::LeaseUntil.lt(now)),
+                ),
+        )
+}
+
+#[derive(Debug)]
+pub struct ImportJobSeaOrmPostgresRepository {
+    db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+}
+
+impl ImportJobSeaOrmPostgresRepository {
+    pub fn new(db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ImportJobRepositoryPort for ImportJobSeaOrmPostgresRepository {
+    #[tracing::instrument(skip_all, err)]
+    async fn add(&self, format: ImportFormat, lines: &[ImportLine]) -> Result<ImportJob> {
+        let actor = REQUEST_DATA
+            .try_with(|data| data.actor.clone())
+            .unwrap_or_default();
+        let now = Utc::now();
+
+        let txn = self.db.conn.begin().await?;
+
+        let job = match (import_job_database_entity::ActiveModel {
+            id: ActiveValue::NotSet,
+            tenant_id: ActiveValue::Set(current_tenant_id()),
+            format: ActiveValue::Set(format.as_str().to_string()),
+            status: ActiveValue::Set(ImportJobStatus::Pending.as_str().to_string()),
+            actor: ActiveValue::Set(actor),
+            error: ActiveValue::Set(None),
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+            finished_at: ActiveValue::Set(None),
+            claimed_by: ActiveValue::Set(None),
+            lease_until: ActiveValue::Set(None),
+        })
+        .insert(&txn)
+        .await
+        {
+            Ok(job) => job.to_domain()?,
+            Err(err) => bail!(err),
+        };
+
+        for chunk in lines.chunks(LINE_INSERT_CHUNK_SIZE) {
+            let active_models = chunk
+                .iter()
+                .map(|line| import_job_line_database_entity::ActiveModel::from_domain(job.id, line));
+            if let Err(err) = import_job_line_database_entity::Entity::insert_many(active_models)
+                .exec(&txn)
+                .await
+            {
+                bail!(err);
+            }
+        }
+
+        txn.commit().await?;
+        Ok(job)
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get(&self, id: i32) -> Result<Option<ImportJob>> {
+        let job = import_job_database_entity::Entity::find_by_id(id)
+            .filter(import_job_database_entity::Column::TenantId.eq(current_tenant_id()))
+            .one(&self.db.conn)
+            .await;
+
+        match job {
+            Ok(Some(job)) => Ok(Some(job.to_domain()?)),
+            Ok(None) => Ok(None),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_unfinished(&self) -> Result<Vec<ImportJob>> {
+        let jobs = import_job_database_entity::Entity::find()
+            .filter(claimable(Utc::now()))
+            .order_by_asc(import_job_database_entity::Column::Id)
+            .all(&self.db.conn)
+            .await;
+
+        match jobs {
+            Ok(jobs) => jobs.iter().map(|job| job.to_domain()).collect(),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn claim(
+        &self,
+        job_id: i32,
+        worker_id: &str,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Option<ImportJob>> {
+        let now = Utc::now();
+        let jobs = import_job_database_entity::Entity::update_many()
+            .col_expr(
+                import_job_database_entity::Column::Status,
+                Expr::value(ImportJobStatus::Running.as_str()),
+            )
+            .col_expr(import_job_database_entity::Column::ClaimedBy, Expr::value(worker_id))
+            .col_expr(import_job_database_entity::Column::LeaseUntil, Expr::value(lease_until))
+            .col_expr(import_job_database_entity::Column::UpdatedAt, Expr::value(now))
+            .filter(import_job_database_entity::Column::Id.eq(job_id))
+            .filter(claimable(now))
+            .exec_with_returning(&self.db.conn)
+            .await;
+
+        match jobs {
+            Ok(jobs) => jobs.first().map(|job| job.to_domain()).transpose(),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn renew_lease(&self, job_id: i32, worker_id: &str, lease_until: DateTime<Utc>) -> Result<bool> {
+        let result = import_job_database_entity::Entity::update_many()
+            .col_expr(import_job_database_entity::Column::LeaseUntil, Expr::value(lease_until))
+            .filter(import_job_database_entity::Column::Id.eq(job_id))
+            .filter(import_job_database_entity::Column::Status.eq(ImportJobStatus::Running.as_str()))
+            .filter(import_job_database_entity::Column::ClaimedBy.eq(worker_id))
+            .exec(&self.db.conn)
+            .await;
+
+        match result {
+            Ok(result) => Ok(result.rows_affected > 0),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_lines(&self, job_id: i32) -> Result<Vec<ImportLine>> {
+        let lines = import_job_line_database_entity::Entity::find()
+            .filter(import_job_line_database_entity::Column::JobId.eq(job_id))
+            .order_by_asc(import_job_line_database_entity::Column::LineNumber)
+            .all(&self.db.conn)
+            .await;
+
+        match lines {
+            Ok(lines) => lines.iter().map(|line| line.to_domain()).collect(),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_failed_lines(&self, job_id: i32, limit: u64) -> Result<Vec<ImportLine>> {
+        let lines = import_job_line_database_entity::Entity::find()
+            .filter(import_job_line_database_entity::Column::JobId.eq(job_id))
+            .filter(
+                import_job_line_database_entity::Column::Status
+                    .eq(ImportLineStatus::Failed.as_str()),
+            )
+            .order_by_asc(import_job_line_database_entity::Column::LineNumber)
+            .limit(limit)
+            .all(&self.db.conn)
+            .await;
+
+        match lines {
+            Ok(lines) => lines.iter().map(|line| line.to_domain()).collect(),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_counts(&self, job_id: i32) -> Result<ImportJobCounts> {
+        let rows = import_job_line_database_entity::Entity::find()
+            .select_only()
+            .column(import_job_line_database_entity::Column::Status)
+            .column_as(import_job_line_database_entity::Column::LineNumber.count(), "count")
+            .filter(import_job_line_database_entity::Column::JobId.eq(job_id))
+            .group_by(import_job_line_database_entity::Column::Status)
+            .into_tuple::<(String, i64)>()
+            .all(&self.db.conn)
+            .await;
+
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(err) => bail!(err),
+        };
+
+        let mut counts = ImportJobCounts::default();
+        for (status, count) in rows {
+            let count = count as u64;
+            match ImportLineStatus::from_str(&status)? {
+                ImportLineStatus::Pending => counts.pending = count,
+                ImportLineStatus::Created => counts.created = count,
+                ImportLineStatus::Failed => counts.failed = count,
+            }
+            counts.total += count;
+        }
+        Ok(counts)
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn finish(
+        &self,
+        job_id: i32,
+        worker_id: &str,
+        status: ImportJobStatus,
+        error: Option<String>,
+    ) -> Result<bool> {
+        let now = Utc::now();
+        let result = import_job_database_entity::Entity::update_many()
+            .col_expr(import_job_database_entity::Column::Status, Expr::value(status.as_str()))
+            .col_expr(import_job_database_entity::Column::Error, Expr::value(error))
+            .col_expr(import_job_database_entity::Column::UpdatedAt, Expr::value(now))
+            .col_expr(
+                import_job_database_entity::Column::FinishedAt,
+                Expr::value(status.is_finished().then_some(now)),
+            )
+            .col_expr(
+                import_job_database_entity::Column::LeaseUntil,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .filter(import_job_database_entity::Column::Id.eq(job_id))
+            .filter(import_job_database_entity::Column::ClaimedBy.eq(worker_id))
+            .exec(&self.db.conn)
+            .await;
+
+        match result {
+            Ok(result) => Ok(result.rows_affected > 0),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn fail_line(&self, job_id: i32, line_number: i32, error: String) -> Result<()> {
+        let active_model = import_job_line_database_entity::ActiveModel {
+            job_id: ActiveValue::Unchanged(job_id),
+            line_number: ActiveValue::Unchanged(line_number),
+            status: ActiveValue::Set(ImportLineStatus::Failed.as_str().to_string()),
+            error: ActiveValue::Set(Some(error)),
+            ..Default::default()
+        };
+
+        match active_model.update(&self.db.conn).await {
+            Ok(_) => Ok(()),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn complete_line_within_transaction(
+        &self,
+        job_id: i32,
+        line_number: i32,
+        entity_id: i32,
+        txn: &dyn TransactionPort,
+    ) -> Result<()> {
+        let txn = &txn
+            .as_any()
+            .downcast_ref::<Transaction<DatabaseTransaction>>()
+            .unwrap()
+            .txn;
+
+        let result = import_job_line_database_entity::Entity::update_many()
+            .col_expr(
+                import_job_line_database_entity::Column::Status,
+                Expr::value(ImportLineStatus::Created.as_str()),
+            )
+            .col_expr(import_job_line_database_entity::Column::EntityId, Expr::value(entity_id))
+            .filter(import_job_line_database_entity::Column::JobId.eq(job_id))
+            .filter(import_job_line_database_entity::Column::LineNumber.eq(line_number))
+            .filter(
+                import_job_line_database_entity::Column::Status
+                    .eq(ImportLineStatus::Pending.as_str()),
+            )
+            .exec(txn)
+            .await;
+
+        match result {
+            Ok(result) if result.rows_affected > 0 => Ok(()),
+            Ok(_) => bail!("Import job {} line {} is no longer pending", job_id, line_number),
+            Err(err) => bail!(err),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::database::entities::outbound_message_database_entity;
+use crate::logging::logging_task_local::current_tenant_id;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::ports::{
+    messaging::messaging_service_port::{OutboundMessage, SendError},
+    repositories::outbound_message_repository_port::{
+        OutboundMessageRepositoryPort, QueuedOutboundMessage,
+    },
+};
+use sea_orm::{
+    sea_query::Expr, ActiveValue, ColumnTrait, ConnectionTrait, DbBackend, EntityTrait,
+    QueryFilter, QuerySelect, Statement, TransactionTrait,
+};
+
+use super::database_data::DatabaseConnection;
+
+/// Claims are taken one at a time, so each one sees the messages earlier claims hold
+const CLAIM_LOCK_QUERY: &str = "SELECT pg_advisory_xact_lock(hashtext('outbound_message_claim'))";
+
+/// Claimed messages are not due again before the lease ends. A message waits for earlier
+/// messages of its partition that are claimed or still to be sent again, so a partition is
+/// drained in order; given up messages no longer hold the partition back.
+const CLAIM_DUE_QUERY: &str = r#"
+UPDATE outbound_message SET next_attempt_at = $2
+WHERE id IN (
+    SELECT id FROM outbound_message
+    WHERE next_attempt_at <= $1
+    AND NOT EXISTS (
+        SELECT 1 FROM outbound_message AS earlier
+        WHERE earlier.tenant_id = outbound_message.tenant_id
+        AND earlier.partition_id = outbound_message.partition_id
+        AND earlier.id < outbound_message.id
+        AND earlier.next_attempt_at > $1
+    )
+    ORDER BY id
+    LIMIT $3
+    FOR UPDATE SKIP LOCKED
+)
+RETURNING *
+"#;
+
+#[derive(Debug)]
+pub struct OutboundMessageSeaOrmPostgresRepository {
+    db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+}
+
+impl OutboundMessageSeaOrmPostgresRepository {
+    pub fn new(db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OutboundMessageRepositoryPort for OutboundMessageSeaOrmPostgresRepository {
+    #[tracing::instrument(skip_all, err)]
+    async fn enqueue(&self, messages: &[(OutboundMessage, Option<SendError>)]) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let tenant_id = current_tenant_id();
+        let now = Utc::now();
+        let active_models = messages.iter().map(|(message, error)| outbound_message_database_entity::ActiveModel {
+            id: ActiveValue::NotSet,
+            tenant_id: ActiveValue::Set(tenant_id.clone()),
+            partition_id: ActiveValue::Set(message.partition_id.clone()),
+            deduplication_id: ActiveValue::Set(message.deduplication_id.clone()),
+            body: ActiveValue::Set(message.body.clone()),
+            attempts: ActiveValue::Set(i32::from(error.is_some())),
+            last_error: ActiveValue::Set(error.as_ref().map(SendError::to_string)),
+            next_attempt_at: ActiveValue::Set(Some(now)),
+            created_at: ActiveValue::Set(now),
+        });
+
+        match outbound_message_database_entity::Entity::insert_many(active_models)
+            .exec(&self.db.conn)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_queued_partition_ids(&self, partition_ids: &[String]) -> Result<HashSet<String>> {
+        if partition_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let queued = outbound_message_database_entity::Entity::find()
+            .select_only()
+            .column(outbound_message_database_entity::Column::PartitionId)
+            .distinct()
+            .filter(outbound_message_database_entity::Column::TenantId.eq(current_tenant_id()))
+            .filter(outbound_message_database_entity::Column::PartitionId.is_in(partition_ids.iter().cloned()))
+            .filter(outbound_message_database_entity::Column::NextAttemptAt.is_not_null())
+            .into_tuple::<String>()
+            .all(&self.db.conn)
+            .await;
+
+        match queued {
+            Ok(queued) => Ok(queued.into_iter().collect()),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        batch_size: u64,
+    ) -> Result<Vec<QueuedOutboundMessage>> {
+        let txn = self.db.conn.begin().await?;
+        txn.execute(Statement::from_string(DbBackend::Postgres, CLAIM_LOCK_QUERY))
+            .await?;
+        let messages = outbound_message_database_entity::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                CLAIM_DUE_QUERY,
+                [now.into(), lease_until.into(), (batch_size as i64).into()],
+            ))
+            .all(&txn)
+            .await;
+        let mut messages = match messages {
+            Ok(messages) => messages,
+            Err(err) => bail!(err),
+        };
+        txn.commit().await?;
+
+        messages.sort_by_key(|message| message.id);
+        Ok(messages.iter().map(|message| message.to_domain()).collect())
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn delete(&self, id: i64) -> Result<()> {
+        match outbound_message_database_entity::Entity::delete_by_id(id)
+            .exec(&self.db.conn)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn record_failure(
+        &self,
+        id: i64,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let result = outbound_message_database_entity::Entity::update_many()
+            .col_expr(
+                outbound_message_database_entity::Column::Attempts,
+                Expr::col(outbound_message_database_entity::Column::Attempts).add(1),
+            )
+            .col_expr(
+                outbound_message_database_entity::Column::LastError,
+                Expr::value(error.to_string()),
+            )
+            .col_expr(
+                outbound_message_database_entity::Column::NextAttemptAt,
+                Expr::value(next_attempt_at),
+            )
+            .filter(outbound_message_database_entity::Column::Id.eq(id))
+            .exec(&self.db.conn)
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => bail!(err),
+        }
+    }
+}
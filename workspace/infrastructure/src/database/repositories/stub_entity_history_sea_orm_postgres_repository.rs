@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use crate::database::entities::stub_history_database_entity::*;
+use crate::logging::logging_task_local::{current_tenant_id, REQUEST_DATA};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    entities::{
+        page_domain_entity::Page, stub_domain_entity::StubEntity,
+        stub_history_domain_entity::{
+            StubEntityChangeCursor, StubEntityHistory, StubEntityHistoryOperation,
+        },
+    },
+    ports::repositories::stub_entity_history_repository_port::StubEntityHistoryRepositoryPort,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait,
+    DbBackend, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Statement,
+};
+use serde_json::Value;
+
+use super::database_data::DatabaseConnection;
+
+/// Rows written by a transaction that may still be open are not read yet,
+/// or a slower transaction could commit a row behind the cursor
+const OLDEST_OPEN_TRANSACTION_FILTER: &str =
+    "transaction_id < pg_snapshot_xmin(pg_current_snapshot())::text::bigint";
+
+#[derive(Debug)]
+pub struct StubEntityHistorySeaOrmPostgresRepository {
+    db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+}
+
+impl StubEntityHistorySeaOrmPostgresRepository {
+    pub fn new(db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl StubEntityHistoryRepositoryPort for StubEntityHistorySeaOrmPostgresRepository {
+    #[tracing::instrument(skip_all, err)]
+    async fn get_history(
+        &self,
+        entity_id: i32,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Page<StubEntityHistory>> {
+        let paginator = Entity::find()
+            .filter(Column::TenantId.eq(current_tenant_id()))
+            .filter(Column::EntityId.eq(entity_id))
+            .order_by_desc(Column::Id)
+            .paginate(&self.db.conn, page_size);
+
+        let totals = paginator.num_items_and_pages().await?;
+        let models = paginator.fetch_page(page).await?;
+
+        let mut items = Vec::with_capacity(models.len());
+        for model in models {
+            items.push(model.to_domain()?);
+        }
+
+        Ok(Page {
+            items,
+            page,
+            page_size,
+            total_items: totals.number_of_items,
+            total_pages: totals.number_of_pages,
+        })
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_as_of(
+        &self,
+        entity_id: i32,
+        as_of: DateTime<Utc>,
+        include_deleted: bool,
+    ) -> Result<Option<StubEntity>> {
+        let entry = Entity::find()
+            .filter(Column::TenantId.eq(current_tenant_id()))
+            .filter(Column::EntityId.eq(entity_id))
+            .filter(Column::ChangedAt.lte(as_of))
+            .order_by_desc(Column::ChangedAt)
+            .order_by_desc(Column::Id)
+            .one(&self.db.conn)
+            .await;
+
+        match entry {
+            Ok(Some(Model { after: Some(after), .. })) => {
+                let entity: StubEntity = serde_json::from_value(after)?;
+                Ok((include_deleted || entity.deleted_at.is_none()).then_some(entity))
+            }
+            Ok(_) => Ok(None),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_changes_since(
+        &self,
+        after: StubEntityChangeCursor,
+        limit: u64,
+    ) -> Result<Vec<StubEntityHistory>> {
+        let models = Entity::find()
+            .filter(Column::TenantId.eq(current_tenant_id()))
+            .filter(
+                Condition::any()
+                    .add(Column::TransactionId.gt(after.transaction_id))
+                    .add(
+                        Condition::all()
+                            .add(Column::TransactionId.eq(after.transaction_id))
+                            .add(Column::Id.gt(after.id)),
+                    ),
+            )
+            .filter(Expr::cust(OLDEST_OPEN_TRANSACTION_FILTER))
+            .order_by_asc(Column::TransactionId)
+            .order_by_asc(Column::Id)
+            .limit(limit)
+            .all(&self.db.conn)
+            .await;
+
+        match models {
+            Ok(models) => models.into_iter().map(|model| model.to_domain()).collect(),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_current_cursor(&self) -> Result<StubEntityChangeCursor> {
+        let statement = Statement::from_string(
+            DbBackend::Postgres,
+            "SELECT pg_snapshot_xmin(pg_current_snapshot())::text::bigint AS transaction_id",
+        );
+        let row = self.db.conn.query_one(statement).await;
+
+        // Every transaction below the snapshot's xmin has finished, so the next change lands at or above it
+        match row {
+            Ok(Some(row)) => Ok(StubEntityChangeCursor {
+                transaction_id: row.try_get::<i64>("", "transaction_id")? - 1,
+                id: i64::MAX,
+            }),
+            Ok(None) => bail!("The database returned no snapshot"),
+            Err(err) => bail!(err),
+        }
+    }
+}
+
+/// Appends a history row for a change made through `conn`, which must be the
+/// transaction that carries the change itself.
+pub(crate) async fn append_stub_entity_history<C: ConnectionTrait>(
+    conn: &C,
+    tenant_id: &str,
+    entity_id: i32,
+    operation: StubEntityHistoryOperation,
+    before: Option<&StubEntity>,
+    after: Option<&StubEntity>,
+) -> Result<()> {
+    let before = before.map(serde_json::to_value).transpose()?;
+    let after = after.map(serde_json::to_value).transpose()?;
+    let changed_fields = changed_fields(before.as_ref(), after.as_ref());
+
+    let (actor, correlation_id) = REQUEST_DATA
+        .try_with(|data| (data.actor.clone(), Some(data.correlation_id.clone())))
+        .unwrap_or((None, None));
+
+    let active_model = ActiveModel {
+        id: ActiveValue::NotSet,
+        entity_id: ActiveValue::Set(entity_id),
+        operation: ActiveValue::Set(operation.as_str().to_string()),
+        before: ActiveValue::Set(before),
+        after: ActiveValue::Set(after),
+        changed_fields: ActiveValue::Set(serde_json::to_value(changed_fields)?),
+        actor: ActiveValue::Set(actor),
+        correlation_id: ActiveValue::Set(correlation_id),
+        changed_at: ActiveValue::Set(Utc::now()),
+        tenant_id: ActiveValue::Set(tenant_id.to_string()),
+        transaction_id: ActiveValue::NotSet,
+    };
+
+    match active_model.insert(conn).await {
+        Ok(_) => Ok(()),
+        Err(err) => bail!(err),
+    }
+}
+
+fn changed_fields(before: Option<&Value>, after: Option<&Value>) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| key.as_str() != "id" && before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_changed_fields_on_insert() {
+        let after = json!({ "id": 1, "name": "name", "value": { "id": 1, "name": "value" }, "auto_ref": null });
+
+        let fields = changed_fields(None, Some(&after));
+
+        assert_eq!(fields, vec!["auto_ref", "name", "value"]);
+    }
+
+    #[test]
+    fn test_changed_fields_on_update() {
+        let before = json!({ "id": 1, "name": "name", "value": { "id": 1, "name": "value" }, "auto_ref": null });
+        let after = json!({ "id": 1, "name": "name", "value": { "id": 2, "name": "value" }, "auto_ref": 3 });
+
+        let fields = changed_fields(Some(&before), Some(&after));
+
+        assert_eq!(fields, vec!["auto_ref", "value"]);
+    }
+
+    #[test]
+    fn test_changed_fields_without_changes() {
+        let before = json!({ "id": 1, "name": "name" });
+
+        let fields = changed_fields(Some(&before), Some(&before));
+
+        assert!(fields.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use crate::database::entities::{stub_child_count_database_entity, stub_projection_database_entity};
+use crate::logging::logging_task_local::current_tenant_id;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use domain::{
+    entities::stub_projection_domain_entity::StubEntityProjection,
+    ports::repositories::stub_entity_projection_repository_port::StubEntityProjectionRepositoryPort,
+};
+use sea_orm::{
+    ConnectionTrait, DbBackend, EntityTrait, QuerySelect, Statement, TransactionTrait,
+};
+
+use super::database_data::DatabaseConnection;
+
+/// Projection changes of a tenant are serialized so child counts never miss a concurrent change
+const PROJECTION_LOCK_QUERY: &str =
+    "SELECT pg_advisory_xact_lock(hashtext('stub_entity_projection#' || $1))";
+
+const INSERT_PROCESSED_MESSAGE_QUERY: &str = r#"
+    INSERT INTO processed_message (tenant_id, handler, message_id, processed_at)
+    VALUES ($1, $2, $3, now())
+    ON CONFLICT DO NOTHING
+"#;
+
+/// The entity version decides which event is newer; the time only orders events published without one
+const UPSERT_PROJECTION_QUERY: &str = r#"
+    INSERT INTO stub_entity_projection (tenant_id, entity_id, name, value, auto_ref, deleted_at, event_time, entity_version)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    ON CONFLICT (tenant_id, entity_id) DO UPDATE SET
+        name = EXCLUDED.name,
+        value = EXCLUDED.value,
+        auto_ref = EXCLUDED.auto_ref,
+        deleted_at = EXCLUDED.deleted_at,
+        event_time = EXCLUDED.event_time,
+        entity_version = EXCLUDED.entity_version
+    WHERE (stub_entity_projection.entity_version, stub_entity_projection.event_time)
+        <= (EXCLUDED.entity_version, EXCLUDED.event_time)
+"#;
+
+const RECOUNT_CHILDREN_QUERY: &str = r#"
+    INSERT INTO stub_entity_child_count (tenant_id, auto_ref, children)
+    SELECT $1, $2, COUNT(*)
+    FROM stub_entity_projection
+    WHERE tenant_id = $1 AND auto_ref = $2 AND deleted_at IS NULL
+    ON CONFLICT (tenant_id, auto_ref) DO UPDATE SET children = EXCLUDED.children
+"#;
+
+#[derive(Debug)]
+pub struct StubEntityProjectionSeaOrmPostgresRepository {
+    db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>,
+}
+
+impl StubEntityProjectionSeaOrmPostgresRepository {
+    pub fn new(db: Arc<DatabaseConnection<sea_orm::DatabaseConnection>>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl StubEntityProjectionRepositoryPort for StubEntityProjectionSeaOrmPostgresRepository {
+    #[tracing::instrument(skip_all, err)]
+    async fn apply(
+        &self,
+        handler: &str,
+        message_id: &str,
+        projection: &StubEntityProjection,
+    ) -> Result<bool> {
+        let tenant_id = current_tenant_id();
+        let txn = self.db.conn.begin().await?;
+
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            PROJECTION_LOCK_QUERY,
+            [tenant_id.clone().into()],
+        ))
+        .await?;
+
+        let processed = txn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                INSERT_PROCESSED_MESSAGE_QUERY,
+                [tenant_id.clone().into(), handler.into(), message_id.into()],
+            ))
+            .await?;
+        if processed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let previous_auto_ref = stub_projection_database_entity::Entity::find_by_id((
+            tenant_id.clone(),
+            projection.entity_id,
+        ))
+        .select_only()
+        .column(stub_projection_database_entity::Column::AutoRef)
+        .into_tuple::<Option<i32>>()
+        .one(&txn)
+        .await?
+        .flatten();
+
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            UPSERT_PROJECTION_QUERY,
+            [
+                tenant_id.clone().into(),
+                projection.entity_id.into(),
+                projection.name.clone().into(),
+                serde_json::to_value(&projection.value)?.into(),
+                projection.auto_ref.into(),
+                projection.deleted_at.into(),
+                projection.event_time.into(),
+                projection.entity_version.into(),
+            ],
+        ))
+        .await?;
+
+        let affected_auto_refs: BTreeSet<i32> =
+            [previous_auto_ref, projection.auto_ref].into_iter().flatten().collect();
+        for auto_ref in affected_auto_refs {
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                RECOUNT_CHILDREN_QUERY,
+                [tenant_id.clone().into(), auto_ref.into()],
+            ))
+            .await?;
+        }
+
+        txn.commit().await?;
+        Ok(true)
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get(&self, entity_id: i32) -> Result<Option<StubEntityProjection>> {
+        let model = stub_projection_database_entity::Entity::find_by_id((current_tenant_id(), entity_id))
+            .one(&self.db.conn)
+            .await;
+
+        match model {
+            Ok(model) => Ok(model.map(|model| model.to_domain())),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_child_count(&self, auto_ref: i32) -> Result<i64> {
+        let model = stub_child_count_database_entity::Entity::find_by_id((current_tenant_id(), auto_ref))
+            .one(&self.db.conn)
+            .await;
+
+        match model {
+            Ok(model) => Ok(model.map(|model| model.children).unwrap_or_default()),
+            Err(err) => bail!(err),
+        }
+    }
+}
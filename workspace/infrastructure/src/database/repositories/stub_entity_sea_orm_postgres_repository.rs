@@ -1,17 +1,37 @@
-use std::sync::Arc;
+use std::{collections::HashSet, pin::pin, sync::Arc};
 
 use crate::database::entities::stub_database_entity::*;
+use crate::logging::logging_task_local::current_tenant_id;
 use anyhow::{bail, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use domain::{
-    entities::stub_domain_entity::StubEntity,
+    entities::{
+        page_domain_entity::Page,
+        stub_domain_entity::{StubEntity, StubEntityFilter},
+        stub_enrichment_domain_entity::PendingEnrichment,
+        stub_history_domain_entity::StubEntityHistoryOperation,
+        stub_search_domain_entity::{StubEntitySearch, StubEntitySearchHit},
+        stub_tree_domain_entity::StubEntityNode,
+    },
+    errors::domain_errors::DomainError,
     ports::repositories::{
         stub_entity_repository_port::StubEntityRepositoryPort, transaction_port::TransactionPort,
     },
 };
-use sea_orm::{ActiveModelTrait, DatabaseTransaction, EntityTrait};
+use futures::{stream, stream::BoxStream, StreamExt};
+use sea_orm::{
+    prelude::DateTimeUtc,
+    sea_query::{Alias, Expr, Query},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, DbBackend, EntityTrait,
+    FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select, Statement,
+    TransactionTrait,
+};
 
-use super::database_data::{DatabaseConnection, Transaction};
+use super::{
+    database_data::{DatabaseConnection, Transaction},
+    stub_entity_history_sea_orm_postgres_repository::append_stub_entity_history,
+};
 
 #[derive(Debug)]
 pub struct StubEntitySeaOrmPostgresRepository {
@@ -28,19 +48,81 @@ impl StubEntitySeaOrmPostgresRepository {
 impl StubEntityRepositoryPort for StubEntitySeaOrmPostgresRepository {
     #[tracing::instrument(skip_all, err)]
     async fn add(&self, entity: &StubEntity) -> Result<StubEntity> {
-        let active_model: ActiveModel = ActiveModel::from_domain(entity, false);
+        let txn = self.db.conn.begin().await?;
+        let inserted_entity = insert_entity(&txn, entity).await?;
+        txn.commit().await?;
+        Ok(inserted_entity)
+    }
 
-        let inserted_entity = active_model.insert(&self.db.conn).await;
+    #[tracing::instrument(skip_all, err)]
+    async fn add_within_transaction(
+        &self,
+        entity: &StubEntity,
+        txn: &dyn TransactionPort,
+    ) -> Result<StubEntity> {
+        let txn = &txn
+            .as_any()
+            .downcast_ref::<Transaction<DatabaseTransaction>>()
+            .unwrap()
+            .txn;
 
-        match inserted_entity {
-            Ok(inserted_entity) => Ok(inserted_entity.to_domain()),
+        insert_entity(txn, entity).await
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn add_many(&self, entities: &[StubEntity]) -> Result<Vec<StubEntity>> {
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let tenant_id = current_tenant_id();
+        let active_models: Vec<ActiveModel> = entities
+            .iter()
+            .map(|entity| ActiveModel::from_domain(entity, false, &tenant_id))
+            .collect();
+
+        let txn = self.db.conn.begin().await?;
+
+        let auto_refs: Vec<i32> = entities.iter().filter_map(|entity| entity.auto_ref).collect();
+        let existing_auto_refs = find_existing_ids(&txn, &auto_refs).await?;
+        if auto_refs.iter().any(|auto_ref| !existing_auto_refs.contains(auto_ref)) {
+            bail!(DomainError::UnprocessableEntity(
+                "auto_ref does not exist".to_string()
+            ));
+        }
+
+        let inserted_entities: Vec<StubEntity> = match Entity::insert_many(active_models)
+            .exec_with_returning_many(&txn)
+            .await
+        {
+            Ok(inserted_entities) => inserted_entities.into_iter().map(|e| e.to_domain()).collect(),
             Err(err) => bail!(err),
+        };
+
+        for inserted_entity in &inserted_entities {
+            append_stub_entity_history(
+                &txn,
+                &tenant_id,
+                inserted_entity.id.unwrap(),
+                StubEntityHistoryOperation::Insert,
+                None,
+                Some(inserted_entity),
+            )
+            .await?;
         }
+
+        txn.commit().await?;
+        Ok(inserted_entities)
     }
 
     #[tracing::instrument(skip_all, err)]
-    async fn get(&self, id: i32) -> Result<Option<StubEntity>> {
-        let entity = Entity::find_by_id(id).one(&self.db.conn).await;
+    async fn get_existing_ids(&self, ids: &[i32]) -> Result<HashSet<i32>> {
+        find_existing_ids(&self.db.conn, ids).await
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get(&self, id: i32, include_deleted: bool) -> Result<Option<StubEntity>> {
+        let entity = find_by_id(id, include_deleted).one(&self.db.conn).await;
 
         match entity {
             Ok(Some(entity)) => Ok(Some(entity.to_domain())),
@@ -53,9 +135,11 @@ impl StubEntityRepositoryPort for StubEntitySeaOrmPostgresRepository {
     async fn get_within_transaction(
         &self,
         id: i32,
-        txn: &Box<dyn TransactionPort>,
+        include_deleted: bool,
+        txn: &dyn TransactionPort,
     ) -> Result<Option<StubEntity>> {
-        let entity = Entity::find_by_id(id)
+        let entity = find_by_id(id, include_deleted)
+            .lock_exclusive()
             .one(
                 &txn.as_any()
                     .downcast_ref::<Transaction<DatabaseTransaction>>()
@@ -75,31 +159,601 @@ impl StubEntityRepositoryPort for StubEntitySeaOrmPostgresRepository {
     async fn update_within_transaction(
         &self,
         entity: &StubEntity,
-        txn: &Box<dyn TransactionPort>,
+        txn: &dyn TransactionPort,
     ) -> Result<StubEntity> {
-        let active_model: ActiveModel = ActiveModel::from_domain(entity, true);
-
-        let updated_entity = active_model
-            .update(
-                &txn.as_any()
-                    .downcast_ref::<Transaction<DatabaseTransaction>>()
-                    .unwrap()
-                    .txn,
-            )
-            .await;
+        let tenant_id = current_tenant_id();
+        let active_model: ActiveModel = ActiveModel::from_domain(entity, true, &tenant_id);
+        let txn = &txn
+            .as_any()
+            .downcast_ref::<Transaction<DatabaseTransaction>>()
+            .unwrap()
+            .txn;
 
-        match updated_entity {
-            Ok(inserted_entity) => Ok(inserted_entity.to_domain()),
+        let before = match find_by_id(entity.id.unwrap_or_default(), true).one(txn).await {
+            Ok(Some(before)) => before.to_domain(),
+            Ok(None) => bail!("Stub entity {:?} not found", entity.id),
             Err(err) => bail!(err),
+        };
+
+        if before.auto_ref != entity.auto_ref {
+            ensure_auto_ref_exists(txn, entity.auto_ref).await?;
+            ensure_auto_ref_is_acyclic(txn, entity.id.unwrap_or_default(), entity.auto_ref)
+                .await?;
         }
+
+        let updated_entity = match active_model.update(txn).await {
+            Ok(updated_entity) => updated_entity.to_domain(),
+            Err(err) => bail!(err),
+        };
+
+        append_stub_entity_history(
+            txn,
+            &tenant_id,
+            updated_entity.id.unwrap(),
+            StubEntityHistoryOperation::Update,
+            Some(&before),
+            Some(&updated_entity),
+        )
+        .await?;
+
+        Ok(updated_entity)
     }
 
     #[tracing::instrument(skip_all, err)]
-    async fn get_all(&self) -> Result<Vec<StubEntity>> {
-        let entities = Entity::find().all(&self.db.conn).await;
+    async fn delete_within_transaction(
+        &self,
+        id: i32,
+        txn: &dyn TransactionPort,
+    ) -> Result<Option<StubEntity>> {
+        let txn = &txn
+            .as_any()
+            .downcast_ref::<Transaction<DatabaseTransaction>>()
+            .unwrap()
+            .txn;
+
+        let before = match find_by_id(id, false).one(txn).await {
+            Ok(Some(before)) => before.to_domain(),
+            Ok(None) => return Ok(None),
+            Err(err) => bail!(err),
+        };
+
+        let mut deleted_entity = before.clone();
+        deleted_entity.deleted_at = Some(Utc::now());
+
+        let tenant_id = current_tenant_id();
+        let deleted_entity = match ActiveModel::from_domain(&deleted_entity, true, &tenant_id)
+            .update(txn)
+            .await
+        {
+            Ok(deleted_entity) => deleted_entity.to_domain(),
+            Err(err) => bail!(err),
+        };
+
+        append_stub_entity_history(
+            txn,
+            &tenant_id,
+            id,
+            StubEntityHistoryOperation::Delete,
+            Some(&before),
+            Some(&deleted_entity),
+        )
+        .await?;
+
+        Ok(Some(deleted_entity))
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn restore_within_transaction(
+        &self,
+        id: i32,
+        txn: &dyn TransactionPort,
+    ) -> Result<Option<StubEntity>> {
+        let txn = &txn
+            .as_any()
+            .downcast_ref::<Transaction<DatabaseTransaction>>()
+            .unwrap()
+            .txn;
+
+        let before = match find_by_id(id, true)
+            .filter(Column::DeletedAt.is_not_null())
+            .one(txn)
+            .await
+        {
+            Ok(Some(before)) => before.to_domain(),
+            Ok(None) => return Ok(None),
+            Err(err) => bail!(err),
+        };
+
+        ensure_auto_ref_exists(txn, before.auto_ref).await?;
+
+        let mut restored_entity = before.clone();
+        restored_entity.deleted_at = None;
+
+        let tenant_id = current_tenant_id();
+        let restored_entity = match ActiveModel::from_domain(&restored_entity, true, &tenant_id)
+            .update(txn)
+            .await
+        {
+            Ok(restored_entity) => restored_entity.to_domain(),
+            Err(err) => bail!(err),
+        };
+
+        append_stub_entity_history(
+            txn,
+            &tenant_id,
+            id,
+            StubEntityHistoryOperation::Restore,
+            Some(&before),
+            Some(&restored_entity),
+        )
+        .await?;
+
+        Ok(Some(restored_entity))
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_all(&self, filter: &StubEntityFilter) -> Result<Vec<StubEntity>> {
+        let entities = find_all(filter).all(&self.db.conn).await;
         match entities {
             Ok(entities) => Ok(entities.into_iter().map(|e| e.to_domain()).collect()),
             Err(err) => bail!(err),
         }
     }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn stream_all(&self, filter: &StubEntityFilter) -> Result<BoxStream<'static, Result<StubEntity>>> {
+        let query = find_all(filter).order_by_asc(Column::Id);
+        let db = self.db.clone();
+        let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFER_SIZE);
+
+        // The row stream borrows the connection, so it is driven by a task owning it; the bounded
+        // channel keeps memory constant and stops the task once the consumer goes away
+        tokio::spawn(async move {
+            let rows = match query.stream(&db.conn).await {
+                Ok(rows) => rows,
+                Err(err) => {
+                    let _ = sender.send(Err(err.into())).await;
+                    return;
+                }
+            };
+            let mut rows = pin!(rows);
+            while let Some(row) = rows.next().await {
+                let entity = row.map(|model| model.to_domain()).map_err(anyhow::Error::from);
+                if sender.send(entity).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|entity| (entity, receiver))
+        })
+        .boxed())
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn search(&self, search: &StubEntitySearch) -> Result<Page<StubEntitySearchHit>> {
+        let paginator = StubEntitySearchRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            SEARCH_QUERY,
+            [
+                current_tenant_id().into(),
+                search.prefix_query().into(),
+                search.value.clone().into(),
+                search.include_deleted.into(),
+            ],
+        ))
+        .paginate(&self.db.conn, search.page_size);
+
+        let totals = paginator.num_items_and_pages().await?;
+        let rows = paginator.fetch_page(search.page).await?;
+
+        Ok(Page {
+            items: rows.into_iter().map(StubEntitySearchRow::into_domain).collect(),
+            page: search.page,
+            page_size: search.page_size,
+            total_items: totals.number_of_items,
+            total_pages: totals.number_of_pages,
+        })
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_children(&self, id: i32) -> Result<Vec<StubEntityNode>> {
+        let entities = find_in_tenant()
+            .filter(Column::AutoRef.eq(id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Id)
+            .all(&self.db.conn)
+            .await;
+
+        match entities {
+            Ok(entities) => Ok(entities
+                .into_iter()
+                .map(|e| StubEntityNode {
+                    depth: 1,
+                    entity: e.to_domain(),
+                })
+                .collect()),
+            Err(err) => bail!(err),
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_descendants(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>> {
+        find_tree_rows(&self.db.conn, DESCENDANTS_QUERY, id, max_depth).await
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_ancestors(&self, id: i32, max_depth: i32) -> Result<Vec<StubEntityNode>> {
+        find_tree_rows(&self.db.conn, ANCESTORS_QUERY, id, max_depth).await
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn purge_deleted(&self, deleted_before: DateTime<Utc>, batch_size: u64) -> Result<u64> {
+        let mut purged = 0;
+
+        loop {
+            let txn = self.db.conn.begin().await?;
+
+            let children = Alias::new("children");
+            let candidates = Entity::find()
+                .filter(Column::DeletedAt.lt(deleted_before))
+                .filter(
+                    Expr::exists(
+                        Query::select()
+                            .expr(Expr::val(1))
+                            .from_as(Entity, children.clone())
+                            .and_where(
+                                Expr::col((children.clone(), Column::AutoRef))
+                                    .equals((Entity, Column::Id)),
+                            )
+                            .to_owned(),
+                    )
+                    .not(),
+                )
+                .limit(batch_size)
+                .lock_exclusive()
+                .all(&txn)
+                .await?;
+
+            if candidates.is_empty() {
+                txn.commit().await?;
+                return Ok(purged);
+            }
+
+            let ids: Vec<i32> = candidates.iter().map(|candidate| candidate.id).collect();
+            let result = Entity::delete_many()
+                .filter(Column::Id.is_in(ids))
+                .exec(&txn)
+                .await?;
+
+            for candidate in candidates {
+                append_stub_entity_history(
+                    &txn,
+                    &candidate.tenant_id,
+                    candidate.id,
+                    StubEntityHistoryOperation::Purge,
+                    Some(&candidate.to_domain()),
+                    None,
+                )
+                .await?;
+            }
+
+            txn.commit().await?;
+            purged += result.rows_affected;
+        }
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn get_pending_enrichments(&self, batch_size: u64) -> Result<Vec<PendingEnrichment>> {
+        let rows = Entity::find()
+            .select_only()
+            .column(Column::TenantId)
+            .column(Column::Id)
+            .filter(Column::EnrichmentPendingSince.is_not_null())
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::EnrichmentPendingSince)
+            .order_by_asc(Column::Id)
+            .limit(batch_size)
+            .into_tuple::<(String, i32)>()
+            .all(&self.db.conn)
+            .await;
+
+        match rows {
+            Ok(rows) => Ok(rows
+                .into_iter()
+                .map(|(tenant_id, entity_id)| PendingEnrichment { tenant_id, entity_id })
+                .collect()),
+            Err(err) => bail!(err),
+        }
+    }
+}
+
+const DESCENDANTS_QUERY: &str = r#"
+    WITH RECURSIVE descendants AS (
+        SELECT s.id, s.name, s.value, s.auto_ref, s.deleted_at, s.tenant_id, s.enrichment_pending_since, s.version, 1 AS depth
+        FROM stub_entity s
+        WHERE s.auto_ref = $1 AND s.deleted_at IS NULL AND s.tenant_id = $3
+        UNION ALL
+        SELECT s.id, s.name, s.value, s.auto_ref, s.deleted_at, s.tenant_id, s.enrichment_pending_since, s.version, d.depth + 1
+        FROM stub_entity s
+        JOIN descendants d ON s.auto_ref = d.id
+        WHERE s.deleted_at IS NULL AND s.tenant_id = $3 AND d.depth < $2
+    )
+    SELECT id, name, value, auto_ref, deleted_at, tenant_id, enrichment_pending_since, version, depth
+    FROM descendants
+    ORDER BY depth, id
+"#;
+
+const ANCESTORS_QUERY: &str = r#"
+    WITH RECURSIVE ancestors AS (
+        SELECT p.id, p.name, p.value, p.auto_ref, p.deleted_at, p.tenant_id, p.enrichment_pending_since, p.version, 1 AS depth
+        FROM stub_entity c
+        JOIN stub_entity p ON p.id = c.auto_ref
+        WHERE c.id = $1 AND c.tenant_id = $3 AND p.deleted_at IS NULL AND p.tenant_id = $3
+        UNION ALL
+        SELECT p.id, p.name, p.value, p.auto_ref, p.deleted_at, p.tenant_id, p.enrichment_pending_since, p.version, a.depth + 1
+        FROM stub_entity p
+        JOIN ancestors a ON p.id = a.auto_ref
+        WHERE p.deleted_at IS NULL AND p.tenant_id = $3 AND a.depth < $2
+    )
+    SELECT id, name, value, auto_ref, deleted_at, tenant_id, enrichment_pending_since, version, depth
+    FROM ancestors
+    ORDER BY depth
+"#;
+
+#[derive(Debug, FromQueryResult)]
+struct StubEntityTreeRow {
+    id: i32,
+    name: String,
+    value: KeyValue,
+    auto_ref: Option<i32>,
+    deleted_at: Option<DateTimeUtc>,
+    tenant_id: String,
+    enrichment_pending_since: Option<DateTimeUtc>,
+    version: i32,
+    depth: i32,
+}
+
+impl StubEntityTreeRow {
+    fn into_domain(self) -> StubEntityNode {
+        let model = Model {
+            id: self.id,
+            name: self.name,
+            value: self.value,
+            auto_ref: self.auto_ref,
+            deleted_at: self.deleted_at,
+            tenant_id: self.tenant_id,
+            enrichment_pending_since: self.enrichment_pending_since,
+            version: self.version,
+        };
+
+        StubEntityNode {
+            depth: self.depth,
+            entity: model.to_domain(),
+        }
+    }
+}
+
+async fn find_tree_rows<C: ConnectionTrait>(
+    conn: &C,
+    query: &str,
+    id: i32,
+    max_depth: i32,
+) -> Result<Vec<StubEntityNode>> {
+    let rows = StubEntityTreeRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        query,
+        [id.into(), max_depth.into(), current_tenant_id().into()],
+    ))
+    .all(conn)
+    .await;
+
+    match rows {
+        Ok(rows) => Ok(rows.into_iter().map(StubEntityTreeRow::into_domain).collect()),
+        Err(err) => bail!(err),
+    }
+}
+
+const SEARCH_QUERY: &str = r#"
+    SELECT id, name, value, auto_ref, deleted_at, tenant_id, enrichment_pending_since, version,
+        CASE WHEN $2::text IS NULL THEN 0::real
+            ELSE ts_rank(search_vector, to_tsquery('simple', $2::text)) END AS rank,
+        CASE WHEN $2::text IS NULL THEN NULL
+            ELSE ts_headline('simple', name, to_tsquery('simple', $2::text),
+                'StartSel=<mark>, StopSel=</mark>, HighlightAll=true') END AS highlight
+    FROM stub_entity
+    WHERE tenant_id = $1
+        AND ($2::text IS NULL OR search_vector @@ to_tsquery('simple', $2::text))
+        AND ($3::jsonb IS NULL OR value @> $3::jsonb)
+        AND ($4 OR deleted_at IS NULL)
+    ORDER BY rank DESC, id
+"#;
+
+#[derive(Debug, FromQueryResult)]
+struct StubEntitySearchRow {
+    id: i32,
+    name: String,
+    value: KeyValue,
+    auto_ref: Option<i32>,
+    deleted_at: Option<DateTimeUtc>,
+    tenant_id: String,
+    enrichment_pending_since: Option<DateTimeUtc>,
+    version: i32,
+    rank: f32,
+    highlight: Option<String>,
+}
+
+impl StubEntitySearchRow {
+    fn into_domain(self) -> StubEntitySearchHit {
+        let model = Model {
+            id: self.id,
+            name: self.name,
+            value: self.value,
+            auto_ref: self.auto_ref,
+            deleted_at: self.deleted_at,
+            tenant_id: self.tenant_id,
+            enrichment_pending_since: self.enrichment_pending_since,
+            version: self.version,
+        };
+
+        StubEntitySearchHit {
+            entity: model.to_domain(),
+            rank: self.rank,
+            highlight: self.highlight,
+        }
+    }
+}
+
+const AUTO_REF_PATH_QUERY: &str = r#"
+    WITH RECURSIVE chain AS (
+        SELECT id, auto_ref, ARRAY[id] AS path
+        FROM stub_entity
+        WHERE id = $1 AND tenant_id = $3
+        UNION ALL
+        SELECT s.id, s.auto_ref, c.path || s.id
+        FROM stub_entity s
+        JOIN chain c ON s.id = c.auto_ref
+        WHERE s.tenant_id = $3 AND NOT s.id = ANY(c.path)
+    )
+    SELECT array_to_string(path, ',') AS path
+    FROM chain
+    WHERE id = $2
+    LIMIT 1
+"#;
+
+/// Rows buffered between the database and a `stream_all` consumer
+const STREAM_BUFFER_SIZE: usize = 64;
+
+/// Hierarchies never span tenants, so each tenant serializes its own changes
+const AUTO_REF_LOCK_QUERY: &str =
+    "SELECT pg_advisory_xact_lock(hashtext('stub_entity.auto_ref#' || $1))";
+
+#[derive(Debug, FromQueryResult)]
+struct AutoRefPathRow {
+    path: String,
+}
+
+fn find_in_tenant() -> Select<Entity> {
+    Entity::find().filter(Column::TenantId.eq(current_tenant_id()))
+}
+
+fn find_all(filter: &StubEntityFilter) -> Select<Entity> {
+    let query = find_in_tenant();
+    if filter.include_deleted {
+        query
+    } else {
+        query.filter(Column::DeletedAt.is_null())
+    }
+}
+
+fn find_by_id(id: i32, include_deleted: bool) -> Select<Entity> {
+    let query = find_in_tenant().filter(Column::Id.eq(id));
+    if include_deleted {
+        query
+    } else {
+        query.filter(Column::DeletedAt.is_null())
+    }
+}
+
+async fn insert_entity<C: ConnectionTrait>(conn: &C, entity: &StubEntity) -> Result<StubEntity> {
+    let tenant_id = current_tenant_id();
+    let active_model: ActiveModel = ActiveModel::from_domain(entity, false, &tenant_id);
+
+    ensure_auto_ref_exists(conn, entity.auto_ref).await?;
+
+    let inserted_entity = match active_model.insert(conn).await {
+        Ok(inserted_entity) => inserted_entity.to_domain(),
+        Err(err) => bail!(err),
+    };
+
+    append_stub_entity_history(
+        conn,
+        &tenant_id,
+        inserted_entity.id.unwrap(),
+        StubEntityHistoryOperation::Insert,
+        None,
+        Some(&inserted_entity),
+    )
+    .await?;
+
+    Ok(inserted_entity)
+}
+
+async fn find_existing_ids<C: ConnectionTrait>(conn: &C, ids: &[i32]) -> Result<HashSet<i32>> {
+    if ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let existing_ids = find_in_tenant()
+        .select_only()
+        .column(Column::Id)
+        .filter(Column::Id.is_in(ids.iter().copied()))
+        .filter(Column::DeletedAt.is_null())
+        .into_tuple::<i32>()
+        .all(conn)
+        .await;
+
+    match existing_ids {
+        Ok(existing_ids) => Ok(existing_ids.into_iter().collect()),
+        Err(err) => bail!(err),
+    }
+}
+
+async fn ensure_auto_ref_exists<C: ConnectionTrait>(conn: &C, auto_ref: Option<i32>) -> Result<()> {
+    let Some(auto_ref) = auto_ref else {
+        return Ok(());
+    };
+
+    match find_by_id(auto_ref, false).one(conn).await {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => bail!(DomainError::UnprocessableEntity(
+            "auto_ref does not exist".to_string()
+        )),
+        Err(err) => bail!(err),
+    }
+}
+
+/// Rejects an `auto_ref` change that would make `id` one of its own ancestors.
+/// Hierarchy changes are serialized through a transaction-level advisory lock so
+/// two concurrent updates cannot each pass the check and close a cycle together.
+async fn ensure_auto_ref_is_acyclic<C: ConnectionTrait>(
+    conn: &C,
+    id: i32,
+    auto_ref: Option<i32>,
+) -> Result<()> {
+    let Some(auto_ref) = auto_ref else {
+        return Ok(());
+    };
+
+    if auto_ref == id {
+        bail!(DomainError::CycleDetected(vec![id, id]));
+    }
+
+    let tenant_id = current_tenant_id();
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        AUTO_REF_LOCK_QUERY,
+        [tenant_id.clone().into()],
+    ))
+    .await?;
+
+    let path = AutoRefPathRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        AUTO_REF_PATH_QUERY,
+        [auto_ref.into(), id.into(), tenant_id.into()],
+    ))
+    .one(conn)
+    .await?;
+
+    match path {
+        Some(row) => {
+            let mut cycle = vec![id];
+            for ancestor in row.path.split(',') {
+                cycle.push(ancestor.parse()?);
+            }
+            bail!(DomainError::CycleDetected(cycle))
+        }
+        None => Ok(()),
+    }
 }
@@ -1,22 +1,58 @@
 pub mod database {
     pub mod migrations {
         mod m20241126_000001_create_stub_table;
+        mod m20241203_000001_create_stub_entity_history_table;
+        mod m20241204_000001_add_deleted_at_to_stub_table;
+        mod m20241205_000001_create_api_key_table;
+        mod m20241206_000001_add_tenant_id_to_stub_tables;
+        mod m20241207_000001_add_stub_entity_history_notify_trigger;
+        mod m20241208_000001_create_import_job_tables;
+        mod m20241209_000001_add_stub_entity_search;
+        mod m20241210_000001_add_entity_id_to_stub_entity_change_notification;
+        mod m20241211_000001_add_enrichment_pending_since_to_stub_table;
+        mod m20241212_000001_create_stub_entity_projection_tables;
+        mod m20241213_000001_create_outbound_message_table;
+        mod m20241214_000001_add_tenant_id_to_api_key_table;
+        mod m20241215_000001_add_transaction_id_to_stub_entity_history_table;
+        mod m20241216_000001_add_version_to_stub_tables;
+        mod m20241217_000001_add_partition_index_to_outbound_message_table;
+        mod m20241218_000001_add_lease_to_import_job_table;
         pub mod migrator;
     }
 
     pub mod repositories {
         pub mod stub_entity_sea_orm_postgres_repository;
+        pub mod stub_entity_history_sea_orm_postgres_repository;
+        pub mod api_key_sea_orm_postgres_repository;
+        pub mod import_job_sea_orm_postgres_repository;
+        pub mod stub_entity_projection_sea_orm_postgres_repository;
+        pub mod outbound_message_sea_orm_postgres_repository;
         pub mod database_data_seaorm;
         pub mod database_data;
     }
 
+    pub mod notifications {
+        pub mod stub_entity_change_listener;
+    }
+
     pub mod entities {
         pub mod stub_database_entity;
+        pub mod stub_history_database_entity;
+        pub mod api_key_database_entity;
+        pub mod import_job_database_entity;
+        pub mod import_job_line_database_entity;
+        pub mod stub_projection_database_entity;
+        pub mod stub_child_count_database_entity;
+        pub mod outbound_message_database_entity;
     }   
 
     mod postgres_database_configuration;
 }
 
+pub mod cache {
+    pub mod stub_entity_caching_repository;
+}
+
 pub mod env_var {
     pub mod env_var_util;
 }
@@ -28,6 +64,7 @@ pub mod logging {
 
 pub mod tracing {
     pub mod tracing_util;
+    pub mod tracing_configuration;
 }
 
 pub mod http {
@@ -40,4 +77,13 @@ pub mod http {
 pub mod messaging {
     pub mod aws_sqs_messaging_service;
     pub mod aws_sqs_messaging_configuration;
+    pub mod kafka_messaging_service;
+    pub mod kafka_messaging_configuration;
+    pub mod local_messaging_service;
+    pub mod local_messaging_configuration;
+
+    pub mod handlers {
+        pub mod handler_registry;
+        pub mod stub_entity_projection_handler;
+    }
 }
\ No newline at end of file
@@ -1,8 +1,12 @@
 use tokio::task_local;
 
+pub const DEFAULT_TENANT_ID: &str = "default";
+
 #[derive(Clone)]
 pub struct RequestData {
     pub correlation_id: String,
+    pub actor: Option<String>,
+    pub tenant_id: Option<String>,
     // pub app_name: String,
     // pub app_version: String,
 }
@@ -12,14 +16,28 @@ task_local! {
 }
 
 impl RequestData {
-    pub fn new(correlation_id: String, 
+    pub fn new(correlation_id: String,
+        actor: Option<String>,
+        tenant_id: Option<String>,
         // app_name: String, 
         // app_version: String
     ) -> Self {
         Self {
             correlation_id,
+            actor,
+            tenant_id,
             // app_name,
             // app_version,
         }
     }
 }
+
+/// Tenant of the current request; work running outside a request (jobs, consumers)
+/// belongs to the default tenant.
+pub fn current_tenant_id() -> String {
+    REQUEST_DATA
+        .try_with(|data| data.tenant_id.clone())
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_TENANT_ID.to_string())
+}
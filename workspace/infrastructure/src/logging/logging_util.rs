@@ -7,7 +7,7 @@ macro_rules! log_with_span {
             let otel_span = context.span();
             let span_id = otel_span.span_context().span_id().to_string();
             let trace_id = otel_span.span_context().trace_id().to_string();
-            let correlation_id = REQUEST_DATA
+            let correlation_id = $crate::logging::logging_task_local::REQUEST_DATA
                 .try_with(|data| data.correlation_id.clone())
                 .unwrap_or_else(|_| "none".to_string());
             let app_name = env!("CARGO_PKG_NAME");
@@ -23,7 +23,7 @@ macro_rules! log_with_span {
             let otel_span = context.span();
             let span_id = otel_span.span_context().span_id().to_string();
             let trace_id = otel_span.span_context().trace_id().to_string();
-            let correlation_id = REQUEST_DATA
+            let correlation_id = $crate::logging::logging_task_local::REQUEST_DATA
                 .try_with(|data| data.correlation_id.clone())
                 .unwrap_or_else(|_| "none".to_string());
             let app_name = env!("CARGO_PKG_NAME");
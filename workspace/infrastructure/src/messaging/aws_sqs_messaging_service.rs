@@ -1,16 +1,28 @@
+use core::fmt;
 use std::sync::Arc;
 
-use anyhow::{bail, Result};
 use async_trait::async_trait;
-use domain::ports::messaging::messaging_service_port::MessagingServicePort;
+use aws_sdk_sqs::error::{ProvideErrorMetadata, SdkError};
+use aws_sdk_sqs::types::{MessageAttributeValue, SendMessageBatchRequestEntry};
+use domain::ports::messaging::messaging_service_port::{
+    MessagingServicePort, OutboundMessage, SendError, SendReceipt,
+};
 
 use tracing::{instrument, Level};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::log_with_span;
-use crate::logging::logging_task_local::REQUEST_DATA;
+use crate::logging::logging_task_local::current_tenant_id;
 use opentelemetry::trace::TraceContextExt;
 
+pub const TENANT_ID_MESSAGE_ATTRIBUTE: &str = "tenant_id";
+
+/// SQS rejects SendMessageBatch requests with more entries than this
+const MAX_BATCH_ENTRIES: usize = 10;
+
+/// Faults on the SQS side that say nothing about whether the message was stored
+const SERVER_FAULT_CODES: [&str; 3] = ["InternalError", "InternalFailure", "ServiceUnavailable"];
+
 #[derive(Debug)]
 pub struct AwsSqsMessagingService {
     aws_client: Arc<aws_sdk_sqs::Client>,
@@ -26,26 +38,185 @@ impl AwsSqsMessagingService {
 #[async_trait]
 impl MessagingServicePort for AwsSqsMessagingService {
     #[instrument(skip_all, err)]
-    async fn send_message(
-        &self,
-        partition_id: String,
-        deduplication_id: String,
-        body: String) -> Result<()> {
-        let response = &self.aws_client
+    async fn send_message(&self, message: OutboundMessage) -> Result<SendReceipt, SendError> {
+        let tenant_id = tenant_id_attribute()?;
+
+        let response = self.aws_client
             .send_message()
             .queue_url(&self.aws_sqs_queue_url)
-            .message_body(body)
-            .message_group_id(partition_id)
-            .message_deduplication_id(format!("CREATE#{}", deduplication_id))
+            .message_body(message.body)
+            .message_group_id(message.partition_id)
+            .message_deduplication_id(message.deduplication_id)
+            .message_attributes(TENANT_ID_MESSAGE_ATTRIBUTE, tenant_id)
             .send()
             .await;
 
         match response {
-            Ok(_) => {
-                log_with_span!(Level::INFO, "Message sent. Response={:?}", response);
-                Ok(())
+            Ok(response) => {
+                let receipt = SendReceipt {
+                    message_id: response.message_id().unwrap_or_default().to_string(),
+                    sequence_number: response.sequence_number().map(str::to_string),
+                };
+                log_with_span!(
+                    Level::INFO,
+                    "Message sent. MessageId={} SequenceNumber={:?}",
+                    receipt.message_id,
+                    receipt.sequence_number
+                );
+                Ok(receipt)
             },
-            Err(err) => bail!("Error sending message: {:?}", err),
+            Err(err) => Err(send_error(err)),
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn send_message_batch(
+        &self,
+        messages: Vec<OutboundMessage>) -> Vec<Result<SendReceipt, SendError>> {
+        let tenant_id = match tenant_id_attribute() {
+            Ok(tenant_id) => tenant_id,
+            Err(err) => return vec![Err(err); messages.len()],
+        };
+
+        let mut outcomes = Vec::with_capacity(messages.len());
+
+        for chunk in messages.chunks(MAX_BATCH_ENTRIES) {
+            let entries = match chunk
+                .iter()
+                .enumerate()
+                .map(|(index, message)| {
+                    SendMessageBatchRequestEntry::builder()
+                        .id(index.to_string())
+                        .message_body(&message.body)
+                        .message_group_id(&message.partition_id)
+                        .message_deduplication_id(&message.deduplication_id)
+                        .message_attributes(TENANT_ID_MESSAGE_ATTRIBUTE, tenant_id.clone())
+                        .build()
+                })
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(entries) => entries,
+                Err(err) => {
+                    outcomes.extend(chunk.iter().map(|_| Err(SendError::Permanent(err.to_string()))));
+                    continue;
+                }
+            };
+
+            let response = self.aws_client
+                .send_message_batch()
+                .queue_url(&self.aws_sqs_queue_url)
+                .set_entries(Some(entries))
+                .send()
+                .await;
+
+            match response {
+                Ok(response) => {
+                    let mut chunk_outcomes = vec![
+                        Err(SendError::Unknown(String::from("missing from the batch response")));
+                        chunk.len()
+                    ];
+                    for successful in response.successful() {
+                        if let Some(outcome) = batch_outcome(&mut chunk_outcomes, successful.id()) {
+                            *outcome = Ok(SendReceipt {
+                                message_id: successful.message_id().to_string(),
+                                sequence_number: successful.sequence_number().map(str::to_string),
+                            });
+                        }
+                    }
+                    for failed in response.failed() {
+                        if let Some(outcome) = batch_outcome(&mut chunk_outcomes, failed.id()) {
+                            *outcome = Err(batch_entry_error(
+                                failed.code(),
+                                failed.message().unwrap_or_default(),
+                                failed.sender_fault(),
+                            ));
+                        }
+                    }
+                    log_with_span!(
+                        Level::INFO,
+                        "Message batch sent. Successful={} Failed={}",
+                        response.successful().len(),
+                        response.failed().len()
+                    );
+                    outcomes.extend(chunk_outcomes);
+                },
+                Err(err) => {
+                    log_with_span!(Level::ERROR, "Error sending message batch: {:?}", err);
+                    let error = send_error(err);
+                    outcomes.extend(chunk.iter().map(|_| Err(error.clone())));
+                },
+            }
+        }
+
+        outcomes
+    }
+}
+
+fn tenant_id_attribute() -> Result<MessageAttributeValue, SendError> {
+    MessageAttributeValue::builder()
+        .data_type("String")
+        .string_value(current_tenant_id())
+        .build()
+        .map_err(|err| SendError::Permanent(err.to_string()))
+}
+
+fn batch_outcome<'a>(
+    outcomes: &'a mut [Result<SendReceipt, SendError>],
+    id: &str,
+) -> Option<&'a mut Result<SendReceipt, SendError>> {
+    id.parse::<usize>().ok().and_then(|index| outcomes.get_mut(index))
+}
+
+/// Requests that never reached SQS or whose response was lost may still have been accepted
+fn send_error<E, R>(err: SdkError<E, R>) -> SendError
+where
+    E: ProvideErrorMetadata + fmt::Debug,
+    R: fmt::Debug,
+{
+    let code = err.code().map(str::to_string);
+    let message = format!("{:?}", err);
+    match (&err, code) {
+        (SdkError::ServiceError(_), Some(code)) if is_throttling(&code) => SendError::Throttled(message),
+        (SdkError::ServiceError(_), Some(code)) if SERVER_FAULT_CODES.contains(&code.as_str()) => {
+            SendError::Unknown(message)
         }
+        (SdkError::ServiceError(_), _) | (SdkError::ConstructionFailure(_), _) => {
+            SendError::Permanent(message)
+        }
+        _ => SendError::Unknown(message),
+    }
+}
+
+fn batch_entry_error(code: &str, message: &str, sender_fault: bool) -> SendError {
+    let message = format!("{}: {}", code, message);
+    if is_throttling(code) {
+        SendError::Throttled(message)
+    } else if sender_fault {
+        SendError::Permanent(message)
+    } else {
+        SendError::Unknown(message)
+    }
+}
+
+/// Covers `RequestThrottled`, `KmsThrottled` and the generic `ThrottlingException`
+fn is_throttling(code: &str) -> bool {
+    code.contains("Throttl")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_entry_error() {
+        assert!(matches!(
+            batch_entry_error("AWS.SimpleQueueService.RequestThrottled", "slow down", false),
+            SendError::Throttled(_)
+        ));
+        assert_eq!(
+            batch_entry_error("InvalidMessageContents", "bad character", true),
+            SendError::Permanent(String::from("InvalidMessageContents: bad character"))
+        );
+        assert!(batch_entry_error("InternalError", "", false).is_retryable());
     }
 }
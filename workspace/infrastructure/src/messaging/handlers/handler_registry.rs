@@ -0,0 +1,137 @@
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use domain::entities::stub_event_domain_entity::CloudEvent;
+use serde_json::Value;
+
+use crate::logging::logging_task_local::{RequestData, REQUEST_DATA};
+
+pub const HANDLER_DURATION: &str = "message_handler_duration_seconds";
+
+/// Message taken off a queue, keyed by the attributes of its CloudEvent envelope
+#[derive(Debug, Clone)]
+pub struct ConsumedMessage {
+    /// CloudEvent id; unlike the SQS message id it survives redeliveries and redrives
+    pub id: String,
+    pub event_type: String,
+    pub body: String,
+    /// Tenant the message was published for; handlers run in the default tenant without one
+    pub tenant_id: Option<String>,
+}
+
+impl ConsumedMessage {
+    pub fn parse(body: &str, tenant_id: Option<String>) -> Result<Self> {
+        let event: CloudEvent<Value> =
+            serde_json::from_str(body).context("message body is not a CloudEvent")?;
+
+        Ok(Self {
+            id: event.id,
+            event_type: event.event_type,
+            body: body.to_string(),
+            tenant_id,
+        })
+    }
+}
+
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    /// Key under which the handler records the messages it has processed
+    fn name(&self) -> &'static str;
+    async fn handle(&self, message: &ConsumedMessage) -> Result<()>;
+}
+
+#[derive(Default)]
+pub struct HandlerRegistry {
+    handlers: HashMap<String, Vec<Arc<dyn MessageHandler>>>,
+}
+
+impl HandlerRegistry {
+    pub fn register(mut self, event_type: impl Into<String>, handler: Arc<dyn MessageHandler>) -> Self {
+        self.handlers.entry(event_type.into()).or_default().push(handler);
+        self
+    }
+
+    pub fn register_all(self, event_types: &[&str], handler: Arc<dyn MessageHandler>) -> Self {
+        event_types
+            .iter()
+            .fold(self, |registry, event_type| registry.register(*event_type, handler.clone()))
+    }
+
+    /// Runs the handlers of the message's event type in registration order, in the tenant
+    /// of the message, and returns how many ran. Stops at the first failure so the whole
+    /// message is redelivered.
+    pub async fn dispatch(&self, message: &ConsumedMessage) -> Result<usize> {
+        let Some(handlers) = self.handlers.get(&message.event_type) else {
+            return Ok(0);
+        };
+
+        let request_data = RequestData::new(message.id.clone(), None, message.tenant_id.clone());
+        REQUEST_DATA.scope(request_data, run_handlers(handlers, message)).await?;
+        Ok(handlers.len())
+    }
+}
+
+async fn run_handlers(handlers: &[Arc<dyn MessageHandler>], message: &ConsumedMessage) -> Result<()> {
+    for handler in handlers {
+        let started = Instant::now();
+        let result = handler.handle(message).await;
+        metrics::histogram!(
+            HANDLER_DURATION,
+            "handler" => handler.name(),
+            "event_type" => message.event_type.clone()
+        )
+        .record(started.elapsed().as_secs_f64());
+
+        result.with_context(|| format!("handler {} failed on message {}", handler.name(), message.id))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        handled: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl MessageHandler for RecordingHandler {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn handle(&self, message: &ConsumedMessage) -> Result<()> {
+            self.handled.lock().unwrap().push(message.id.clone());
+            Ok(())
+        }
+    }
+
+    fn message(event_type: &str) -> ConsumedMessage {
+        ConsumedMessage {
+            id: "42-created-1".to_string(),
+            event_type: event_type.to_string(),
+            body: "{}".to_string(),
+            tenant_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_by_event_type() {
+        let handler = Arc::new(RecordingHandler::default());
+        let registry = HandlerRegistry::default().register("created", handler.clone());
+
+        assert_eq!(registry.dispatch(&message("created")).await.unwrap(), 1);
+        assert_eq!(registry.dispatch(&message("deleted")).await.unwrap(), 0);
+        assert_eq!(*handler.handled.lock().unwrap(), ["42-created-1"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_non_cloud_events() {
+        assert!(ConsumedMessage::parse(r#"{"id": 1, "name": "legacy"}"#, None).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use domain::{
+    entities::{
+        stub_event_domain_entity::{
+            CloudEvent, StubEntityEventData, STUB_ENTITY_CREATED_EVENT_TYPE,
+            STUB_ENTITY_DELETED_EVENT_TYPE, STUB_ENTITY_UPDATED_EVENT_TYPE,
+        },
+        stub_projection_domain_entity::StubEntityProjection,
+    },
+    ports::repositories::stub_entity_projection_repository_port::StubEntityProjectionRepositoryPort,
+};
+use tracing::info;
+
+use super::handler_registry::{ConsumedMessage, HandlerRegistry, MessageHandler};
+
+pub const STUB_ENTITY_PROJECTION_EVENT_TYPES: [&str; 3] = [
+    STUB_ENTITY_CREATED_EVENT_TYPE,
+    STUB_ENTITY_UPDATED_EVENT_TYPE,
+    STUB_ENTITY_DELETED_EVENT_TYPE,
+];
+
+/// Maintains the `stub_entity_projection` and `stub_entity_child_count` read models
+pub struct StubEntityProjectionHandler {
+    repository: Arc<dyn StubEntityProjectionRepositoryPort>,
+}
+
+impl StubEntityProjectionHandler {
+    pub fn new(repository: Arc<dyn StubEntityProjectionRepositoryPort>) -> Self {
+        Self { repository }
+    }
+
+    /// Registers the handler for every stub entity event type
+    pub fn register(self, registry: HandlerRegistry) -> HandlerRegistry {
+        registry.register_all(&STUB_ENTITY_PROJECTION_EVENT_TYPES, Arc::new(self))
+    }
+}
+
+#[async_trait]
+impl MessageHandler for StubEntityProjectionHandler {
+    fn name(&self) -> &'static str {
+        "stub_entity_projection"
+    }
+
+    async fn handle(&self, message: &ConsumedMessage) -> Result<()> {
+        let event: CloudEvent<StubEntityEventData> = serde_json::from_str(&message.body)?;
+        let Some(projection) = StubEntityProjection::from_event(event) else {
+            bail!("event {} does not identify its entity", message.id);
+        };
+
+        if !self.repository.apply(self.name(), &message.id, &projection).await? {
+            info!(message_id = %message.id, "Message was already projected");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::{bail, Result};
+
+use crate::env_var::env_var_util::{get_env_var, get_required_string_env_var, get_u64_env_var};
+
+#[derive(Debug, Clone)]
+pub struct KafkaProducerConfig {
+    pub bootstrap_servers: String,
+    pub topic: String,
+    /// `all` (or `-1`), `1` or `0`; the producer is only idempotent with `all`
+    pub acks: String,
+    /// `none`, `gzip`, `snappy`, `lz4` or `zstd`
+    pub compression_type: String,
+    pub linger_ms: u64,
+    pub message_timeout_ms: u64,
+}
+
+impl KafkaProducerConfig {
+    /// librdkafka refuses idempotence unless every in-sync replica acknowledges
+    pub fn idempotence(&self) -> bool {
+        matches!(self.acks.as_str(), "all" | "-1")
+    }
+}
+
+pub fn get_kafka_producer_config() -> Result<KafkaProducerConfig> {
+    let acks = get_env_var("KAFKA_ACKS", String::from("all"))?;
+    if !matches!(acks.as_str(), "all" | "-1" | "1" | "0") {
+        bail!("KAFKA_ACKS must be all, -1, 1 or 0, not {}", acks);
+    }
+
+    Ok(KafkaProducerConfig {
+        bootstrap_servers: get_required_string_env_var("KAFKA_BOOTSTRAP_SERVERS")?,
+        topic: get_env_var("KAFKA_TOPIC", String::from("stub-entity-events"))?,
+        acks,
+        compression_type: get_env_var("KAFKA_COMPRESSION_TYPE", String::from("lz4"))?,
+        linger_ms: get_u64_env_var("KAFKA_LINGER_MS", 5)?,
+        message_timeout_ms: get_u64_env_var("KAFKA_MESSAGE_TIMEOUT_MS", 30000)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotence_requires_all_acks() {
+        let config = |acks: &str| KafkaProducerConfig {
+            bootstrap_servers: String::from("localhost:9092"),
+            topic: String::from("events"),
+            acks: acks.to_string(),
+            compression_type: String::from("lz4"),
+            linger_ms: 5,
+            message_timeout_ms: 30000,
+        };
+
+        assert!(config("all").idempotence());
+        assert!(config("-1").idempotence());
+        assert!(!config("1").idempotence());
+        assert!(!config("0").idempotence());
+    }
+}
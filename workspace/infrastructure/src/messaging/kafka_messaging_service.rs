@@ -0,0 +1,260 @@
+use std::{collections::HashMap, fmt, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use domain::ports::messaging::messaging_service_port::{
+    MessagingServicePort, OutboundMessage, SendError, SendReceipt,
+};
+use futures::future::join_all;
+use opentelemetry::{global, trace::TraceContextExt};
+use rdkafka::{
+    error::{KafkaError, RDKafkaErrorCode},
+    message::{Header, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord},
+    ClientConfig,
+};
+use tracing::{instrument, Level};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::log_with_span;
+use crate::logging::logging_task_local::current_tenant_id;
+
+use super::{
+    aws_sqs_messaging_service::TENANT_ID_MESSAGE_ATTRIBUTE,
+    kafka_messaging_configuration::KafkaProducerConfig,
+};
+
+pub const DEDUPLICATION_ID_HEADER: &str = "deduplication_id";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaRecord {
+    pub topic: String,
+    pub key: String,
+    pub payload: String,
+    pub headers: Vec<(String, String)>,
+}
+
+/// Seam between the adapter and rdkafka, so records can be captured without a broker
+#[async_trait]
+pub trait KafkaRecordProducer: Send + Sync {
+    async fn produce(&self, record: KafkaRecord) -> Result<SendReceipt, SendError>;
+}
+
+#[async_trait]
+impl KafkaRecordProducer for FutureProducer {
+    /// A record is identified by its topic, partition and offset
+    async fn produce(&self, record: KafkaRecord) -> Result<SendReceipt, SendError> {
+        let headers = record
+            .headers
+            .iter()
+            .fold(OwnedHeaders::new(), |headers, (key, value)| {
+                headers.insert(Header {
+                    key,
+                    value: Some(value),
+                })
+            });
+        let future_record = FutureRecord::to(&record.topic)
+            .key(&record.key)
+            .payload(&record.payload)
+            .headers(headers);
+
+        // Delivery is bounded by `message.timeout.ms` rather than by the enqueue timeout
+        match self.send(future_record, Duration::from_secs(0)).await {
+            Ok((partition, offset)) => Ok(SendReceipt {
+                message_id: format!("{}/{}/{}", record.topic, partition, offset),
+                sequence_number: Some(offset.to_string()),
+            }),
+            Err((err, _)) => Err(send_error(&err)),
+        }
+    }
+}
+
+/// Timeouts and broker failures leave the outcome unknown, since the record may have been
+/// written before the acknowledgement was lost
+fn send_error(err: &KafkaError) -> SendError {
+    match err.rdkafka_error_code() {
+        Some(RDKafkaErrorCode::QueueFull | RDKafkaErrorCode::ThrottlingQuotaExceeded) => {
+            SendError::Throttled(err.to_string())
+        }
+        Some(
+            RDKafkaErrorCode::MessageSizeTooLarge
+            | RDKafkaErrorCode::BadMessage
+            | RDKafkaErrorCode::InvalidRecord
+            | RDKafkaErrorCode::UnknownTopic
+            | RDKafkaErrorCode::TopicAuthorizationFailed,
+        ) => SendError::Permanent(err.to_string()),
+        _ => SendError::Unknown(err.to_string()),
+    }
+}
+
+/// Publishes each message as a record keyed by its `partition_id`, so the messages of one
+/// entity land on one partition in order. The producer is idempotent, and the
+/// `deduplication_id` header lets consumers drop redeliveries across producer sessions.
+pub struct KafkaMessagingService {
+    producer: Arc<dyn KafkaRecordProducer>,
+    topic: String,
+}
+
+impl KafkaMessagingService {
+    pub fn new(producer: Arc<dyn KafkaRecordProducer>, topic: String) -> Self {
+        Self { producer, topic }
+    }
+
+    pub fn from_config(config: &KafkaProducerConfig) -> Result<Self> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &config.bootstrap_servers)
+            .set("acks", &config.acks)
+            .set("compression.type", &config.compression_type)
+            .set("linger.ms", config.linger_ms.to_string())
+            .set("message.timeout.ms", config.message_timeout_ms.to_string())
+            .set("enable.idempotence", config.idempotence().to_string())
+            .create()?;
+        Ok(Self::new(Arc::new(producer), config.topic.clone()))
+    }
+
+    fn record(&self, message: &OutboundMessage, tenant_id: &str) -> KafkaRecord {
+        let mut headers = vec![
+            (DEDUPLICATION_ID_HEADER.to_string(), message.deduplication_id.clone()),
+            (TENANT_ID_MESSAGE_ATTRIBUTE.to_string(), tenant_id.to_string()),
+        ];
+        headers.extend(trace_context_headers());
+
+        KafkaRecord {
+            topic: self.topic.clone(),
+            key: message.partition_id.clone(),
+            payload: message.body.clone(),
+            headers,
+        }
+    }
+}
+
+/// W3C `traceparent`/`tracestate` of the current span, through the global propagator
+fn trace_context_headers() -> Vec<(String, String)> {
+    let context = tracing::Span::current().context();
+    let mut headers = HashMap::new();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut headers));
+    let mut headers: Vec<(String, String)> = headers.into_iter().collect();
+    headers.sort();
+    headers
+}
+
+#[async_trait]
+impl MessagingServicePort for KafkaMessagingService {
+    #[instrument(skip_all, err)]
+    async fn send_message(&self, message: OutboundMessage) -> Result<SendReceipt, SendError> {
+        let record = self.record(&message, &current_tenant_id());
+        let receipt = self.producer.produce(record).await?;
+        log_with_span!(
+            Level::INFO,
+            "Record produced. Key={} MessageId={}",
+            message.partition_id,
+            receipt.message_id
+        );
+        Ok(receipt)
+    }
+
+    /// Records are enqueued in the given order before any delivery is awaited
+    #[instrument(skip_all)]
+    async fn send_message_batch(
+        &self,
+        messages: Vec<OutboundMessage>,
+    ) -> Vec<Result<SendReceipt, SendError>> {
+        let tenant_id = current_tenant_id();
+        let outcomes = join_all(
+            messages
+                .iter()
+                .map(|message| self.producer.produce(self.record(message, &tenant_id))),
+        )
+        .await;
+
+        let failed = outcomes.iter().filter(|outcome| outcome.is_err()).count();
+        log_with_span!(
+            Level::INFO,
+            "Record batch produced. Records={} Failed={}",
+            outcomes.len(),
+            failed
+        );
+        outcomes
+    }
+}
+
+impl fmt::Debug for KafkaMessagingService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KafkaMessagingService")
+            .field("topic", &self.topic)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingProducer {
+        records: Mutex<Vec<KafkaRecord>>,
+    }
+
+    #[async_trait]
+    impl KafkaRecordProducer for RecordingProducer {
+        async fn produce(&self, record: KafkaRecord) -> Result<SendReceipt, SendError> {
+            if record.key == "fail" {
+                return Err(SendError::Unknown(String::from("broker unavailable")));
+            }
+            let mut records = self.records.lock().unwrap();
+            records.push(record);
+            Ok(SendReceipt {
+                message_id: format!("events/0/{}", records.len() - 1),
+                sequence_number: Some((records.len() - 1).to_string()),
+            })
+        }
+    }
+
+    fn message(partition_id: &str) -> OutboundMessage {
+        OutboundMessage {
+            partition_id: partition_id.to_string(),
+            deduplication_id: format!("{}-created", partition_id),
+            body: String::from("{}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_message_batch_maps_messages_to_records() {
+        let producer = Arc::new(RecordingProducer::default());
+        let service = KafkaMessagingService::new(producer.clone(), String::from("events"));
+
+        let outcomes = service
+            .send_message_batch(vec![message("1"), message("fail"), message("2")])
+            .await;
+
+        assert_eq!(
+            outcomes,
+            vec![
+                Ok(SendReceipt {
+                    message_id: String::from("events/0/0"),
+                    sequence_number: Some(String::from("0")),
+                }),
+                Err(SendError::Unknown(String::from("broker unavailable"))),
+                Ok(SendReceipt {
+                    message_id: String::from("events/0/1"),
+                    sequence_number: Some(String::from("1")),
+                }),
+            ]
+        );
+        let records = producer.records.lock().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].topic, "events");
+        assert_eq!(records[0].key, "1");
+        assert_eq!(records[0].payload, "{}");
+        assert_eq!(
+            records[0].headers[..2],
+            [
+                (String::from("deduplication_id"), String::from("1-created")),
+                (String::from("tenant_id"), String::from("default")),
+            ]
+        );
+        assert_eq!(records[1].key, "2");
+    }
+}
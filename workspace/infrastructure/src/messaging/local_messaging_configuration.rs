@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::env_var::env_var_util::{get_env_var, get_u32_env_var, get_u64_env_var};
+
+#[derive(Debug, Clone)]
+pub struct LocalMessagingConfig {
+    /// Messages are kept here until delivered; without it they only live in memory
+    pub queue_dir: Option<PathBuf>,
+    pub max_delivery_attempts: u32,
+    /// Multiplied by the attempt number before each retry
+    pub retry_delay_ms: u64,
+}
+
+pub fn get_local_messaging_config() -> Result<LocalMessagingConfig> {
+    let queue_dir = get_env_var("LOCAL_MESSAGING_QUEUE_DIR", String::new())?;
+
+    Ok(LocalMessagingConfig {
+        queue_dir: (!queue_dir.is_empty()).then(|| PathBuf::from(queue_dir)),
+        max_delivery_attempts: get_u32_env_var("LOCAL_MESSAGING_MAX_DELIVERY_ATTEMPTS", 5)?,
+        retry_delay_ms: get_u64_env_var("LOCAL_MESSAGING_RETRY_DELAY_MS", 1000)?,
+    })
+}
@@ -0,0 +1,437 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use domain::ports::messaging::messaging_service_port::{
+    MessagingServicePort, OutboundMessage, SendError, SendReceipt,
+};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+use tracing::{error, instrument, warn};
+
+use crate::logging::logging_task_local::current_tenant_id;
+
+use super::handlers::handler_registry::{ConsumedMessage, HandlerRegistry};
+use super::local_messaging_configuration::LocalMessagingConfig;
+
+/// Subdirectory of the queue directory that messages go to once every attempt failed
+const FAILED_DIR: &str = "failed";
+
+/// A published message, as queued in memory and as persisted to the queue directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LocalMessage {
+    tenant_id: String,
+    deduplication_id: String,
+    body: String,
+}
+
+struct QueuedMessage {
+    message: LocalMessage,
+    path: Option<PathBuf>,
+}
+
+/// Delivers published messages to a handler registry running in the same process, one at
+/// a time and in publication order, so the API runs without a broker
+pub struct LocalMessagingService {
+    sender: UnboundedSender<QueuedMessage>,
+    queue_dir: Option<PathBuf>,
+    /// Next sequence to assign; held until the message is queued so senders reach the
+    /// channel in sequence order
+    next_sequence: Mutex<u64>,
+}
+
+impl LocalMessagingService {
+    /// Messages a previous run left in the queue directory are delivered before new ones,
+    /// and new ones are numbered after the highest sequence found there
+    pub async fn start(registry: HandlerRegistry, config: &LocalMessagingConfig) -> Result<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let mut next_sequence = 0;
+        if let Some(queue_dir) = &config.queue_dir {
+            let failed_dir = queue_dir.join(FAILED_DIR);
+            fs::create_dir_all(&failed_dir).await?;
+            for path in pending_files(&failed_dir).await? {
+                next_sequence = next_sequence.max(sequence_of(&path).map_or(0, |sequence| sequence + 1));
+            }
+            for path in pending_files(queue_dir).await? {
+                next_sequence = next_sequence.max(sequence_of(&path).map_or(0, |sequence| sequence + 1));
+                let message = serde_json::from_slice(&fs::read(&path).await?)?;
+                enqueue(&sender, QueuedMessage { message, path: Some(path) })?;
+            }
+        }
+
+        tokio::spawn(deliver_all(registry, receiver, config.clone()));
+
+        Ok(Self {
+            sender,
+            queue_dir: config.queue_dir.clone(),
+            next_sequence: Mutex::new(next_sequence),
+        })
+    }
+
+    /// Returns the position of the message in the publication order
+    async fn publish(&self, message: LocalMessage) -> Result<u64> {
+        let mut next_sequence = self.next_sequence.lock().await;
+        let sequence = *next_sequence;
+        let path = match &self.queue_dir {
+            Some(queue_dir) => Some(persist(queue_dir, sequence, &message).await?),
+            None => None,
+        };
+        enqueue(&self.sender, QueuedMessage { message, path })?;
+        *next_sequence += 1;
+        Ok(sequence)
+    }
+}
+
+/// Written under a temporary name and renamed so a restart never replays a partial
+/// file; zero-padded names sort in publication order across restarts
+async fn persist(queue_dir: &Path, sequence: u64, message: &LocalMessage) -> Result<PathBuf> {
+    let name = format!("{:020}", sequence);
+    let temporary_path = queue_dir.join(format!("{}.tmp", name));
+    let path = queue_dir.join(format!("{}.json", name));
+
+    fs::write(&temporary_path, serde_json::to_vec(message)?).await?;
+    fs::rename(&temporary_path, &path).await?;
+    Ok(path)
+}
+
+#[async_trait]
+impl MessagingServicePort for LocalMessagingService {
+    /// The event is delivered as is, so its deduplication id identifies the message
+    #[instrument(skip_all, err)]
+    async fn send_message(&self, message: OutboundMessage) -> Result<SendReceipt, SendError> {
+        let message_id = message.deduplication_id.clone();
+        match self
+            .publish(LocalMessage {
+                tenant_id: current_tenant_id(),
+                deduplication_id: message.deduplication_id,
+                body: message.body,
+            })
+            .await
+        {
+            Ok(sequence) => Ok(SendReceipt {
+                message_id,
+                sequence_number: Some(sequence.to_string()),
+            }),
+            Err(err) => Err(SendError::Unknown(format!("{:?}", err))),
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn send_message_batch(
+        &self,
+        messages: Vec<OutboundMessage>,
+    ) -> Vec<Result<SendReceipt, SendError>> {
+        let mut outcomes = Vec::with_capacity(messages.len());
+        for message in messages {
+            outcomes.push(self.send_message(message).await);
+        }
+        outcomes
+    }
+}
+
+fn enqueue(sender: &UnboundedSender<QueuedMessage>, message: QueuedMessage) -> Result<()> {
+    sender
+        .send(message)
+        .map_err(|_| anyhow!("the local message bus is no longer delivering"))
+}
+
+fn sequence_of(path: &Path) -> Option<u64> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+async fn pending_files(queue_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    let mut entries = fs::read_dir(queue_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+async fn deliver_all(
+    registry: HandlerRegistry,
+    mut receiver: UnboundedReceiver<QueuedMessage>,
+    config: LocalMessagingConfig,
+) {
+    while let Some(QueuedMessage { message, path }) = receiver.recv().await {
+        let delivered = deliver(&registry, &message, &config).await;
+
+        let Some(path) = path else {
+            continue;
+        };
+        let result = if delivered {
+            fs::remove_file(&path).await
+        } else {
+            move_to_failed(&path).await
+        };
+        if let Err(err) = result {
+            error!(path = %path.display(), error = ?err, "Failed to update the local message queue");
+        }
+    }
+}
+
+async fn deliver(registry: &HandlerRegistry, message: &LocalMessage, config: &LocalMessagingConfig) -> bool {
+    let consumed = match ConsumedMessage::parse(&message.body, Some(message.tenant_id.clone())) {
+        Ok(consumed) => consumed,
+        Err(err) => {
+            error!(message_id = %message.deduplication_id, error = ?err, "Dropping undeliverable local message");
+            return false;
+        }
+    };
+
+    let max_attempts = config.max_delivery_attempts.max(1);
+    for attempt in 1..=max_attempts {
+        match registry.dispatch(&consumed).await {
+            Ok(_) => return true,
+            Err(err) if attempt < max_attempts => {
+                warn!(message_id = %consumed.id, attempt, error = ?err, "Local message delivery failed, retrying");
+                tokio::time::sleep(Duration::from_millis(config.retry_delay_ms * u64::from(attempt))).await;
+            }
+            Err(err) => {
+                error!(message_id = %consumed.id, attempt, error = ?err, "Local message delivery failed, giving up");
+            }
+        }
+    }
+    false
+}
+
+async fn move_to_failed(path: &Path) -> std::io::Result<()> {
+    let (Some(queue_dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return Ok(());
+    };
+    fs::rename(path, queue_dir.join(FAILED_DIR).join(file_name)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::sync::Arc;
+
+    use chrono::Utc;
+    use domain::entities::{
+        stub_domain_entity::{KeyValue, StubEntity},
+        stub_event_domain_entity::{StubEntityEvent, STUB_ENTITY_CREATED_EVENT_TYPE},
+    };
+
+    use super::*;
+    use crate::logging::logging_task_local::{RequestData, REQUEST_DATA};
+    use crate::messaging::handlers::handler_registry::MessageHandler;
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        handled: std::sync::Mutex<Vec<(String, String)>>,
+        failing: bool,
+    }
+
+    #[async_trait]
+    impl MessageHandler for RecordingHandler {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn handle(&self, message: &ConsumedMessage) -> Result<()> {
+            if self.failing {
+                return Err(anyhow!("handler failure"));
+            }
+            self.handled.lock().unwrap().push((message.id.clone(), current_tenant_id()));
+            Ok(())
+        }
+    }
+
+    impl RecordingHandler {
+        async fn wait_for(&self, count: usize) -> Vec<(String, String)> {
+            wait_until(|| async { self.handled.lock().unwrap().len() >= count }).await;
+            self.handled.lock().unwrap().clone()
+        }
+    }
+
+    /// Polls the condition for up to two seconds, as delivery runs on its own task
+    async fn wait_until<F, Fut>(mut condition: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        for _ in 0..200 {
+            if condition().await {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("condition not met in time");
+    }
+
+    fn config(queue_dir: Option<PathBuf>) -> LocalMessagingConfig {
+        LocalMessagingConfig {
+            queue_dir,
+            max_delivery_attempts: 1,
+            retry_delay_ms: 0,
+        }
+    }
+
+    fn created(id: i32) -> OutboundMessage {
+        OutboundMessage::from_event(StubEntityEvent::Created(StubEntity {
+            id: Some(id),
+            name: format!("name {}", id),
+            value: KeyValue {
+                id: 1,
+                name: "value".to_string(),
+            },
+            auto_ref: None,
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 0,
+        }))
+        .unwrap()
+    }
+
+    fn queue_dir(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}", test, Utc::now().timestamp_micros()))
+    }
+
+    #[tokio::test]
+    async fn test_delivers_in_publication_order_and_tenant() {
+        let handler = Arc::new(RecordingHandler::default());
+        let registry =
+            HandlerRegistry::default().register(STUB_ENTITY_CREATED_EVENT_TYPE, handler.clone());
+        let service = LocalMessagingService::start(registry, &config(None)).await.unwrap();
+
+        let first = created(1);
+        let second = created(2);
+        let request_data = RequestData::new("local".to_string(), None, Some("tenant-a".to_string()));
+        let receipt = REQUEST_DATA
+            .scope(request_data, async {
+                service.send_message(first.clone()).await.unwrap();
+                service.send_message(second.clone()).await.unwrap()
+            })
+            .await;
+        assert_eq!(receipt.message_id, second.deduplication_id);
+        assert_eq!(receipt.sequence_number.as_deref(), Some("1"));
+
+        let handled = handler.wait_for(2).await;
+        assert_eq!(
+            handled,
+            [
+                (first.deduplication_id, "tenant-a".to_string()),
+                (second.deduplication_id, "tenant-a".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replays_persisted_messages() {
+        let queue_dir = queue_dir("local-messaging-replay");
+        fs::create_dir_all(&queue_dir).await.unwrap();
+        let message = created(3);
+        let persisted = LocalMessage {
+            tenant_id: "tenant-b".to_string(),
+            deduplication_id: message.deduplication_id.clone(),
+            body: message.body,
+        };
+        let path = queue_dir.join("00000000000000000001.json");
+        fs::write(&path, serde_json::to_vec(&persisted).unwrap()).await.unwrap();
+
+        let handler = Arc::new(RecordingHandler::default());
+        let registry =
+            HandlerRegistry::default().register(STUB_ENTITY_CREATED_EVENT_TYPE, handler.clone());
+        let _service = LocalMessagingService::start(registry, &config(Some(queue_dir.clone())))
+            .await
+            .unwrap();
+
+        let handled = handler.wait_for(1).await;
+        assert_eq!(handled, [(message.deduplication_id, "tenant-b".to_string())]);
+        let queue_dir = &queue_dir;
+        wait_until(|| async move { pending_files(queue_dir).await.unwrap().is_empty() }).await;
+
+        fs::remove_dir_all(queue_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_moves_failed_messages_aside() {
+        let queue_dir = queue_dir("local-messaging-failed");
+        let handler = Arc::new(RecordingHandler {
+            failing: true,
+            ..Default::default()
+        });
+        let registry = HandlerRegistry::default().register(STUB_ENTITY_CREATED_EVENT_TYPE, handler);
+        let service = LocalMessagingService::start(registry, &config(Some(queue_dir.clone())))
+            .await
+            .unwrap();
+
+        service.send_message(created(4)).await.unwrap();
+
+        let failed_dir = &queue_dir.join(FAILED_DIR);
+        wait_until(|| async move { !pending_files(failed_dir).await.unwrap().is_empty() }).await;
+        assert_eq!(pending_files(failed_dir).await.unwrap().len(), 1);
+        assert!(pending_files(&queue_dir).await.unwrap().is_empty());
+
+        fs::remove_dir_all(&queue_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resumes_sequence_after_persisted_messages() {
+        let queue_dir = queue_dir("local-messaging-resume");
+        fs::create_dir_all(queue_dir.join(FAILED_DIR)).await.unwrap();
+        let persisted = LocalMessage {
+            tenant_id: "tenant-c".to_string(),
+            deduplication_id: "failed".to_string(),
+            body: "{}".to_string(),
+        };
+        let failed_path = queue_dir.join(FAILED_DIR).join("00000000000000000007.json");
+        fs::write(&failed_path, serde_json::to_vec(&persisted).unwrap()).await.unwrap();
+
+        let registry = HandlerRegistry::default().register(
+            STUB_ENTITY_CREATED_EVENT_TYPE,
+            Arc::new(RecordingHandler::default()),
+        );
+        let service = LocalMessagingService::start(registry, &config(Some(queue_dir.clone())))
+            .await
+            .unwrap();
+
+        let receipt = service.send_message(created(5)).await.unwrap();
+        assert_eq!(receipt.sequence_number.as_deref(), Some("8"));
+
+        fs::remove_dir_all(&queue_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_senders_are_delivered_in_sequence_order() {
+        let queue_dir = queue_dir("local-messaging-concurrent");
+        let handler = Arc::new(RecordingHandler::default());
+        let registry =
+            HandlerRegistry::default().register(STUB_ENTITY_CREATED_EVENT_TYPE, handler.clone());
+        let service = Arc::new(
+            LocalMessagingService::start(registry, &config(Some(queue_dir.clone())))
+                .await
+                .unwrap(),
+        );
+
+        let senders = (0..20).map(|id| {
+            let service = service.clone();
+            tokio::spawn(async move {
+                let message = created(id);
+                let receipt = service.send_message(message.clone()).await.unwrap();
+                (receipt.sequence_number.unwrap().parse::<u64>().unwrap(), message.deduplication_id)
+            })
+        });
+        let mut sent = Vec::new();
+        for sender in senders.collect::<Vec<_>>() {
+            sent.push(sender.await.unwrap());
+        }
+        sent.sort();
+
+        let handled = handler.wait_for(sent.len()).await;
+        let handled_ids: Vec<_> = handled.into_iter().map(|(id, _)| id).collect();
+        let sent_ids: Vec<_> = sent.into_iter().map(|(_, id)| id).collect();
+        assert_eq!(handled_ids, sent_ids);
+
+        fs::remove_dir_all(&queue_dir).await.unwrap();
+    }
+}
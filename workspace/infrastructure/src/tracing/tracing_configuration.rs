@@ -7,17 +7,18 @@ use tracing_subscriber::{
     EnvFilter, Layer,
 };
 
-pub fn configure_tracing() {
+/// JSON logs filtered by `RUST_LOG`, plus spans exported over OTLP as `service_name`
+pub fn configure_tracing(service_name: &'static str, service_version: &'static str) {
     global::set_text_map_propagator(TraceContextPropagator::new());
 
     let resource = Resource::new(vec![
         KeyValue::new(
             opentelemetry_semantic_conventions::resource::SERVICE_NAME,
-            env!("CARGO_PKG_NAME"),
+            service_name,
         ),
         KeyValue::new(
             opentelemetry_semantic_conventions::resource::SERVICE_VERSION,
-            env!("CARGO_PKG_VERSION"),
+            service_version,
         ),
     ]);
 
@@ -1,10 +1,16 @@
 use std::env;
 use std::sync::Arc;
 
-use domain::entities::stub_domain_entity::{KeyValue, StubEntity};
+use chrono::{Duration, Utc};
+use domain::entities::stub_domain_entity::{KeyValue, StubEntity, StubEntityFilter};
+use domain::entities::stub_enrichment_domain_entity::PendingEnrichment;
+use domain::entities::stub_search_domain_entity::StubEntitySearch;
+use domain::errors::domain_errors::DomainError;
 use domain::ports::repositories::stub_entity_repository_port::StubEntityRepositoryPort;
-use infrastructure::database::repositories::database_data::DatabaseConnection;
+use infrastructure::database::repositories::database_data::{DatabaseConnection, Transaction};
 use infrastructure::database::repositories::stub_entity_sea_orm_postgres_repository::StubEntitySeaOrmPostgresRepository;
+use infrastructure::logging::logging_task_local::{RequestData, REQUEST_DATA};
+use futures::StreamExt;
 use tokio;
 
 async fn setup_db() -> Arc<DatabaseConnection<sea_orm::DatabaseConnection>> {
@@ -35,6 +41,9 @@ async fn test_add_and_get_stub_entity() {
             name: "Test Value".to_string(),
         },
         auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: None,
+        version: 0,
     };
 
     // Test add
@@ -42,6 +51,447 @@ async fn test_add_and_get_stub_entity() {
     assert_eq!(inserted_entity.name, "Test Entity");
 
     // Test get
-    let fetched_entity = repository.get(inserted_entity.id.unwrap()).await.unwrap();
+    let fetched_entity = repository.get(inserted_entity.id.unwrap(), false).await.unwrap();
     assert_eq!(fetched_entity.unwrap().name, "Test Entity");
 }
+
+#[tokio::test]
+async fn test_soft_delete_and_restore_stub_entity() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db.clone());
+
+    let stub_entity = StubEntity {
+        id: None,
+        name: "Deleted Entity".to_string(),
+        value: KeyValue {
+            id: 1,
+            name: "Deleted Value".to_string(),
+        },
+        auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: None,
+        version: 0,
+    };
+    let inserted_entity = repository.add(&stub_entity).await.unwrap();
+    assert_eq!(inserted_entity.version, 1);
+    let id = inserted_entity.id.unwrap();
+
+    let txn = Transaction::begin(&db).await.unwrap();
+    let deleted_entity = repository.delete_within_transaction(id, txn.as_ref()).await.unwrap().unwrap();
+    txn.commit().await.unwrap();
+    assert!(deleted_entity.deleted_at.is_some());
+    assert_eq!(deleted_entity.version, 2);
+
+    assert!(repository.get(id, false).await.unwrap().is_none());
+    assert!(repository.get(id, true).await.unwrap().is_some());
+
+    let txn = Transaction::begin(&db).await.unwrap();
+    let restored_entity = repository.restore_within_transaction(id, txn.as_ref()).await.unwrap();
+    txn.commit().await.unwrap();
+    let restored_entity = restored_entity.unwrap();
+    assert!(restored_entity.deleted_at.is_none());
+    assert_eq!(restored_entity.version, 3);
+
+    assert!(repository.get(id, false).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_concurrent_updates_get_versions_in_commit_order() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db.clone());
+
+    let stub_entity = StubEntity {
+        id: None,
+        name: "Versioned Entity".to_string(),
+        value: KeyValue {
+            id: 1,
+            name: "Versioned Value".to_string(),
+        },
+        auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: None,
+        version: 0,
+    };
+    let inserted_entity = repository.add(&stub_entity).await.unwrap();
+
+    let mut first = inserted_entity.clone();
+    first.name = "First".to_string();
+    let first_txn = Transaction::begin(&db).await.unwrap();
+    let first = repository.update_within_transaction(&first, first_txn.as_ref()).await.unwrap();
+
+    // The second update waits for the row lock of the first
+    let mut second = inserted_entity.clone();
+    second.name = "Second".to_string();
+    let second_db = db.clone();
+    let second = tokio::spawn(async move {
+        let repository = StubEntitySeaOrmPostgresRepository::new(second_db.clone());
+        let txn = Transaction::begin(&second_db).await.unwrap();
+        let second = repository.update_within_transaction(&second, txn.as_ref()).await.unwrap();
+        txn.commit().await.unwrap();
+        second
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    first_txn.commit().await.unwrap();
+    let second = second.await.unwrap();
+
+    assert_eq!(first.version, 2);
+    assert_eq!(second.version, 3);
+    let stored = repository.get(inserted_entity.id.unwrap(), false).await.unwrap().unwrap();
+    assert_eq!((stored.name.as_str(), stored.version), ("Second", 3));
+}
+
+#[tokio::test]
+async fn test_get_descendants_and_ancestors() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db);
+
+    let mut auto_ref = None;
+    let mut ids = Vec::new();
+    for name in ["Root", "Child", "Grandchild"] {
+        let stub_entity = StubEntity {
+            id: None,
+            name: name.to_string(),
+            value: KeyValue {
+                id: 1,
+                name: "Tree Value".to_string(),
+            },
+            auto_ref,
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 0,
+        };
+        let id = repository.add(&stub_entity).await.unwrap().id.unwrap();
+        auto_ref = Some(id);
+        ids.push(id);
+    }
+
+    let children = repository.get_children(ids[0]).await.unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].entity.id, Some(ids[1]));
+
+    let descendants = repository.get_descendants(ids[0], 1).await.unwrap();
+    assert_eq!(descendants.len(), 1);
+
+    let descendants = repository.get_descendants(ids[0], 10).await.unwrap();
+    assert_eq!(descendants.len(), 2);
+    assert_eq!(descendants[1].depth, 2);
+    assert_eq!(descendants[1].entity.id, Some(ids[2]));
+
+    let ancestors = repository.get_ancestors(ids[2], 10).await.unwrap();
+    assert_eq!(ancestors.len(), 2);
+    assert_eq!(ancestors[0].entity.id, Some(ids[1]));
+    assert_eq!(ancestors[1].entity.id, Some(ids[0]));
+}
+
+#[tokio::test]
+async fn test_update_rejects_auto_ref_cycle() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db.clone());
+
+    let mut auto_ref = None;
+    let mut entities = Vec::new();
+    for name in ["Cycle Root", "Cycle Child", "Cycle Grandchild"] {
+        let stub_entity = StubEntity {
+            id: None,
+            name: name.to_string(),
+            value: KeyValue {
+                id: 1,
+                name: "Cycle Value".to_string(),
+            },
+            auto_ref,
+            deleted_at: None,
+            enrichment_pending_since: None,
+            version: 0,
+        };
+        let inserted_entity = repository.add(&stub_entity).await.unwrap();
+        auto_ref = inserted_entity.id;
+        entities.push(inserted_entity);
+    }
+    let ids: Vec<i32> = entities.iter().map(|e| e.id.unwrap()).collect();
+
+    let mut root = entities[0].clone();
+    root.auto_ref = Some(ids[2]);
+    let txn = Transaction::begin(&db).await.unwrap();
+    let err = repository
+        .update_within_transaction(&root, txn.as_ref())
+        .await
+        .unwrap_err();
+    txn.rollback().await.unwrap();
+    match err.downcast_ref::<DomainError>() {
+        Some(DomainError::CycleDetected(path)) => {
+            assert_eq!(path, &vec![ids[0], ids[2], ids[1], ids[0]])
+        }
+        _ => panic!("expected cycle error, got {:?}", err),
+    }
+
+    root.auto_ref = Some(ids[0]);
+    let txn = Transaction::begin(&db).await.unwrap();
+    let err = repository
+        .update_within_transaction(&root, txn.as_ref())
+        .await
+        .unwrap_err();
+    txn.rollback().await.unwrap();
+    assert!(matches!(
+        err.downcast_ref::<DomainError>(),
+        Some(DomainError::CycleDetected(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_tenant_isolation() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db);
+
+    let tenant_a = RequestData::new("tenant-a-test".to_string(), None, Some("tenant-a".to_string()));
+    let tenant_b = RequestData::new("tenant-b-test".to_string(), None, Some("tenant-b".to_string()));
+
+    let stub_entity = StubEntity {
+        id: None,
+        name: "Tenant A Entity".to_string(),
+        value: KeyValue {
+            id: 1,
+            name: "Tenant A Value".to_string(),
+        },
+        auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: None,
+        version: 0,
+    };
+
+    let inserted = REQUEST_DATA
+        .scope(tenant_a.clone(), repository.add(&stub_entity))
+        .await
+        .unwrap();
+    let id = inserted.id.unwrap();
+
+    let from_a = REQUEST_DATA
+        .scope(tenant_a, repository.get(id, false))
+        .await
+        .unwrap();
+    assert!(from_a.is_some());
+
+    let from_b = REQUEST_DATA
+        .scope(tenant_b.clone(), repository.get(id, true))
+        .await
+        .unwrap();
+    assert!(from_b.is_none());
+
+    let cross_tenant_child = StubEntity {
+        auto_ref: Some(id),
+        ..stub_entity
+    };
+    let result = REQUEST_DATA
+        .scope(tenant_b, repository.add(&cross_tenant_child))
+        .await;
+    assert!(matches!(
+        result.unwrap_err().downcast_ref::<DomainError>(),
+        Some(DomainError::UnprocessableEntity(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_add_many_stub_entities() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db);
+
+    let parent = StubEntity {
+        id: None,
+        name: "Batch Parent".to_string(),
+        value: KeyValue {
+            id: 1,
+            name: "Batch Value".to_string(),
+        },
+        auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: None,
+        version: 0,
+    };
+    let parent_id = repository.add(&parent).await.unwrap().id.unwrap();
+
+    let entities: Vec<StubEntity> = ["Batch First", "Batch Second"]
+        .into_iter()
+        .map(|name| StubEntity {
+            name: name.to_string(),
+            auto_ref: Some(parent_id),
+            ..parent.clone()
+        })
+        .collect();
+
+    let inserted = repository.add_many(&entities).await.unwrap();
+    assert_eq!(inserted.len(), 2);
+    assert_eq!(inserted[0].name, "Batch First");
+    assert_eq!(inserted[1].name, "Batch Second");
+
+    let ids: Vec<i32> = inserted.iter().map(|e| e.id.unwrap()).collect();
+    let existing = repository
+        .get_existing_ids(&[ids[0], ids[1], i32::MAX])
+        .await
+        .unwrap();
+    assert_eq!(existing.len(), 2);
+    assert!(!existing.contains(&i32::MAX));
+
+    let dangling = vec![StubEntity {
+        auto_ref: Some(i32::MAX),
+        ..parent
+    }];
+    let err = repository.add_many(&dangling).await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DomainError>(),
+        Some(DomainError::UnprocessableEntity(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_stream_all_stub_entities() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db);
+
+    let tenant = RequestData::new("stream-test".to_string(), None, Some("stream-tenant".to_string()));
+
+    let stub_entity = StubEntity {
+        id: None,
+        name: "Streamed Entity".to_string(),
+        value: KeyValue {
+            id: 1,
+            name: "Streamed Value".to_string(),
+        },
+        auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: None,
+        version: 0,
+    };
+
+    let (streamed, listed) = REQUEST_DATA
+        .scope(tenant, async {
+            repository.add(&stub_entity).await.unwrap();
+            let filter = StubEntityFilter::default();
+            let streamed: Vec<StubEntity> = repository
+                .stream_all(&filter)
+                .await
+                .unwrap()
+                .map(|entity| entity.unwrap())
+                .collect()
+                .await;
+            (streamed, repository.get_all(&filter).await.unwrap())
+        })
+        .await;
+
+    assert!(!streamed.is_empty());
+    assert_eq!(streamed.len(), listed.len());
+    assert!(streamed.windows(2).all(|pair| pair[0].id < pair[1].id));
+}
+
+#[tokio::test]
+async fn test_search_stub_entities() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db);
+
+    let tenant_id = format!("search-{}", chrono::Utc::now().timestamp_micros());
+    let tenant = RequestData::new("search-test".to_string(), None, Some(tenant_id));
+
+    let entity = |name: &str, value_id: i32, value_name: &str| StubEntity {
+        id: None,
+        name: name.to_string(),
+        value: KeyValue {
+            id: value_id,
+            name: value_name.to_string(),
+        },
+        auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: None,
+        version: 0,
+    };
+
+    let (by_text, by_value, by_both) = REQUEST_DATA
+        .scope(tenant, async {
+            repository.add(&entity("Searchable Falcon", 1, "Bird")).await.unwrap();
+            repository.add(&entity("Other", 2, "Falcon wing")).await.unwrap();
+            repository.add(&entity("Unrelated", 2, "Nothing")).await.unwrap();
+
+            let search = |text: Option<&str>, value: Option<serde_json::Value>| StubEntitySearch {
+                text: text.map(String::from),
+                value,
+                page_size: 10,
+                ..Default::default()
+            };
+            (
+                repository.search(&search(Some("falc"), None)).await.unwrap(),
+                repository.search(&search(None, Some(serde_json::json!({"id": 2})))).await.unwrap(),
+                repository
+                    .search(&search(Some("falc"), Some(serde_json::json!({"id": 2}))))
+                    .await
+                    .unwrap(),
+            )
+        })
+        .await;
+
+    assert_eq!(by_text.total_items, 2);
+    assert_eq!(by_text.items[0].entity.name, "Searchable Falcon");
+    assert!(by_text.items[0].rank > by_text.items[1].rank);
+    assert_eq!(
+        by_text.items[0].highlight.as_deref(),
+        Some("Searchable <mark>Falcon</mark>")
+    );
+
+    assert_eq!(by_value.total_items, 2);
+    assert!(by_value.items.iter().all(|hit| hit.entity.value.id == 2));
+
+    assert_eq!(by_both.total_items, 1);
+    assert_eq!(by_both.items[0].entity.name, "Other");
+}
+
+#[tokio::test]
+async fn test_get_pending_enrichments() {
+    let db = setup_db().await;
+    let repository = StubEntitySeaOrmPostgresRepository::new(db.clone());
+
+    let tenant = RequestData::new("enrichment-test".to_string(), None, Some("tenant-enrichment".to_string()));
+
+    let pending_entity = StubEntity {
+        id: None,
+        name: "Pending Entity".to_string(),
+        value: KeyValue {
+            id: 1,
+            name: "Client Value".to_string(),
+        },
+        auto_ref: None,
+        deleted_at: None,
+        enrichment_pending_since: Some(Utc::now() - Duration::days(1)),
+        version: 0,
+    };
+    let enriched_entity = StubEntity {
+        name: "Enriched Entity".to_string(),
+        enrichment_pending_since: None,
+        ..pending_entity.clone()
+    };
+
+    let pending_id = REQUEST_DATA
+        .scope(tenant.clone(), repository.add(&pending_entity))
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    let enriched_id = REQUEST_DATA
+        .scope(tenant.clone(), repository.add(&enriched_entity))
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+
+    let pending = repository.get_pending_enrichments(10000).await.unwrap();
+    assert!(pending.contains(&PendingEnrichment {
+        tenant_id: "tenant-enrichment".to_string(),
+        entity_id: pending_id,
+    }));
+    assert!(pending.iter().all(|pending| pending.entity_id != enriched_id));
+
+    let txn = Transaction::begin(&db).await.unwrap();
+    REQUEST_DATA
+        .scope(tenant, repository.delete_within_transaction(pending_id, txn.as_ref()))
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let pending = repository.get_pending_enrichments(10000).await.unwrap();
+    assert!(pending.iter().all(|pending| pending.entity_id != pending_id));
+}
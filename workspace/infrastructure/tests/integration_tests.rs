@@ -1,5 +1,18 @@
 mod database {
     mod repositories {
         mod stub_entity_sea_orm_postgres_repository_it;
+        mod stub_entity_history_sea_orm_postgres_repository_it;
+        mod api_key_sea_orm_postgres_repository_it;
+        mod import_job_sea_orm_postgres_repository_it;
+        mod stub_entity_projection_sea_orm_postgres_repository_it;
+        mod outbound_message_sea_orm_postgres_repository_it;
     }
-}
\ No newline at end of file
+}
+
+mod cache {
+    mod stub_entity_caching_repository_it;
+}
+
+mod messaging {
+    mod kafka_messaging_service_it;
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use domain::ports::messaging::messaging_service_port::{MessagingServicePort, OutboundMessage};
+use infrastructure::logging::logging_task_local::{RequestData, REQUEST_DATA};
+use infrastructure::messaging::kafka_messaging_configuration::KafkaProducerConfig;
+use infrastructure::messaging::kafka_messaging_service::KafkaMessagingService;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::message::{Headers, Message};
+use rdkafka::mocking::MockCluster;
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+
+const TOPIC: &str = "stub-entity-events";
+
+#[tokio::test]
+async fn test_send_message_batch_to_mock_cluster() {
+    let cluster = MockCluster::new(1).unwrap();
+    cluster.create_topic(TOPIC, 3, 1).unwrap();
+
+    let service = KafkaMessagingService::from_config(&KafkaProducerConfig {
+        bootstrap_servers: cluster.bootstrap_servers(),
+        topic: TOPIC.to_string(),
+        acks: "all".to_string(),
+        compression_type: "lz4".to_string(),
+        linger_ms: 5,
+        message_timeout_ms: 5000,
+    })
+    .unwrap();
+
+    let messages = (1..=3)
+        .map(|id| OutboundMessage {
+            partition_id: "7".to_string(),
+            deduplication_id: format!("7-updated-{}", id),
+            body: format!("{{\"version\":{}}}", id),
+        })
+        .collect();
+    let request_data = RequestData::new("kafka-test".to_string(), None, Some("tenant-kafka".to_string()));
+    let outcomes = REQUEST_DATA
+        .scope(request_data, service.send_message_batch(messages))
+        .await;
+    let offsets: Vec<String> = outcomes
+        .into_iter()
+        .map(|outcome| outcome.unwrap().sequence_number.unwrap())
+        .collect();
+    assert_eq!(offsets, ["0", "1", "2"]);
+
+    let consumer: BaseConsumer = ClientConfig::new()
+        .set("bootstrap.servers", cluster.bootstrap_servers())
+        .set("group.id", "kafka-test")
+        .set("auto.offset.reset", "earliest")
+        .create()
+        .unwrap();
+    let mut assignment = TopicPartitionList::new();
+    for partition in 0..3 {
+        assignment
+            .add_partition_offset(TOPIC, partition, Offset::Beginning)
+            .unwrap();
+    }
+    consumer.assign(&assignment).unwrap();
+
+    let mut received = Vec::new();
+    while received.len() < 3 {
+        let message = match consumer.poll(Duration::from_secs(10)) {
+            Some(message) => message.unwrap(),
+            None => panic!("timed out waiting for records"),
+        };
+        let headers = message.headers().unwrap();
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|header| header.key == name)
+                .and_then(|header| header.value)
+                .map(|value| String::from_utf8(value.to_vec()).unwrap())
+        };
+        received.push((
+            message.partition(),
+            String::from_utf8(message.key().unwrap().to_vec()).unwrap(),
+            message.payload_view::<str>().unwrap().unwrap().to_string(),
+            header("deduplication_id").unwrap(),
+            header("tenant_id").unwrap(),
+        ));
+    }
+
+    // One key, one partition, produce order
+    assert!(received.iter().all(|record| record.0 == received[0].0 && record.1 == "7"));
+    assert_eq!(
+        received.iter().map(|record| record.3.as_str()).collect::<Vec<_>>(),
+        ["7-updated-1", "7-updated-2", "7-updated-3"]
+    );
+    assert_eq!(received[2].2, "{\"version\":3}");
+    assert!(received.iter().all(|record| record.4 == "tenant-kafka"));
+}